@@ -0,0 +1,334 @@
+//! ISO 26262 ASIL Checks
+//!
+//! Safety integrity rules over the requirement tree:
+//!
+//! - a requirement derived from another must not have a lower ASIL than its
+//!   parent unless it is part of a valid ASIL decomposition. Decomposed
+//!   requirements carry the parent's ASIL in parentheses (`ASIL-C(D)`,
+//!   `A(D)`, `QM(B)`), and the marked siblings must together form one of the
+//!   decompositions of ISO 26262-9 (D → C+A, B+B, D+QM; C → B+A, C+QM;
+//!   B → A+A, B+QM; A → A+QM), i.e. two requirements whose levels add up to
+//!   the parent level;
+//! - a requirement allocated to (or satisfied by) a block must not exceed the
+//!   block's ASIL.
+//!
+//! The ASIL is read from `tags.safety`, falling back to `tags.asil`. Accepted
+//! values are `QM`, `A`-`D` and `ASIL-A`-`ASIL-D`, optionally followed by the
+//! decomposed-from level in parentheses.
+
+use crate::diagnostics::Diagnostic;
+use crate::model_graph::ModelGraph;
+use crate::types::{Element, LinkKind};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Parsed ASIL value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Asil {
+    /// 0 = QM, 1 = A ... 4 = D
+    pub level: u8,
+    /// Level of the requirement this one was decomposed from
+    pub decomposed_from: Option<u8>,
+}
+
+fn parse_level(s: &str) -> Option<u8> {
+    let s = s.trim();
+    let s = s.strip_prefix("ASIL-").or_else(|| s.strip_prefix("ASIL ")).unwrap_or(s);
+    match s {
+        "QM" => Some(0),
+        "A" => Some(1),
+        "B" => Some(2),
+        "C" => Some(3),
+        "D" => Some(4),
+        _ => None,
+    }
+}
+
+fn level_name(level: u8) -> &'static str {
+    ["QM", "ASIL-A", "ASIL-B", "ASIL-C", "ASIL-D"][level.min(4) as usize]
+}
+
+impl Asil {
+    /// Parse "ASIL-C", "C", "QM", "ASIL-C(D)", "C(D)"
+    pub fn parse(s: &str) -> Option<Asil> {
+        match s.trim().strip_suffix(')').and_then(|rest| rest.split_once('(')) {
+            Some((level, from)) => Some(Asil {
+                level: parse_level(level)?,
+                decomposed_from: Some(parse_level(from)?),
+            }),
+            None => Some(Asil { level: parse_level(s)?, decomposed_from: None }),
+        }
+    }
+}
+
+impl fmt::Display for Asil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(level_name(self.level))?;
+        if let Some(from) = self.decomposed_from {
+            write!(f, "({})", level_name(from).trim_start_matches("ASIL-"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Run all ASIL checks on the model graph
+pub fn validate_asil(graph: &ModelGraph) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // Parse ASIL tags of requirements and blocks
+    let mut asil: BTreeMap<NodeIndex, Asil> = BTreeMap::new();
+    for node in graph.node_indices() {
+        let (id, element) = &graph[node];
+        if !matches!(element, Element::Req(_) | Element::BlockDefinition(_)) {
+            continue;
+        }
+        let Some(raw) = element.safety_tag() else {
+            continue;
+        };
+        match Asil::parse(raw) {
+            Some(value) => {
+                asil.insert(node, value);
+            }
+            None => diagnostics.push(
+                Diagnostic::new("AL034", format!("{} has unrecognised ASIL value \"{}\"", id, raw))
+                    .on(id)
+                    .hint("Use QM, ASIL-A .. ASIL-D, or ASIL-X(Y) for decomposed requirements"),
+            ),
+        }
+    }
+
+    for parent in graph.node_indices() {
+        if matches!(graph[parent].1, Element::Req(_)) {
+            check_children(graph, parent, &asil, &mut diagnostics);
+        }
+    }
+    check_allocations(graph, &asil, &mut diagnostics);
+
+    diagnostics
+}
+
+/// Inheritance and decomposition checks for the children of one requirement
+fn check_children(
+    graph: &ModelGraph,
+    parent: NodeIndex,
+    asil: &BTreeMap<NodeIndex, Asil>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(parent_asil) = asil.get(&parent) else {
+        return;
+    };
+    let parent_id = &graph[parent].0;
+    let mut children: Vec<NodeIndex> = graph
+        .edges_directed(parent, Direction::Incoming)
+        .filter(|e| e.weight() == LinkKind::DerivesFrom.as_str())
+        .map(|e| e.source())
+        .filter(|c| matches!(graph[*c].1, Element::Req(_)))
+        .collect();
+    children.sort();
+    children.dedup();
+
+    let mut decomposed: Vec<(NodeIndex, Asil)> = Vec::new();
+    for child in children {
+        let Some(&child_asil) = asil.get(&child) else {
+            continue;
+        };
+        let child_id = &graph[child].0;
+        match child_asil.decomposed_from {
+            Some(from) if from != parent_asil.level => diagnostics.push(
+                Diagnostic::new(
+                    "AL031",
+                    format!(
+                        "{} is marked {} but its parent {} is {}",
+                        child_id, child_asil, parent_id, level_name(parent_asil.level)
+                    ),
+                )
+                .on(child_id)
+                .related([parent_id])
+                .hint(format!(
+                    "Mark the decomposition with the parent level: {}({})",
+                    level_name(child_asil.level),
+                    level_name(parent_asil.level).trim_start_matches("ASIL-")
+                )),
+            ),
+            Some(_) => decomposed.push((child, child_asil)),
+            None if child_asil.level < parent_asil.level => diagnostics.push(
+                Diagnostic::new(
+                    "AL030",
+                    format!(
+                        "{} is {} but derives from {} which is {}",
+                        child_id,
+                        child_asil,
+                        parent_id,
+                        level_name(parent_asil.level)
+                    ),
+                )
+                .on(child_id)
+                .related([parent_id])
+                .hint(format!(
+                    "Raise the ASIL to {} or mark a valid decomposition, e.g. {}({})",
+                    level_name(parent_asil.level),
+                    level_name(child_asil.level),
+                    level_name(parent_asil.level).trim_start_matches("ASIL-")
+                )),
+            ),
+            None => {}
+        }
+    }
+
+    if decomposed.is_empty() {
+        return;
+    }
+    let ids: Vec<&str> = decomposed.iter().map(|(n, _)| graph[*n].0.as_str()).collect();
+    let levels: Vec<String> = decomposed.iter().map(|(_, a)| a.to_string()).collect();
+    let sum: u8 = decomposed.iter().map(|(_, a)| a.level).sum();
+    if decomposed.len() != 2 || sum != parent_asil.level {
+        diagnostics.push(
+            Diagnostic::new(
+                "AL032",
+                format!(
+                    "Invalid ASIL decomposition of {} ({}) into [{}]",
+                    parent_id,
+                    level_name(parent_asil.level),
+                    levels.join(", ")
+                ),
+            )
+            .on(parent_id)
+            .related(ids)
+            .hint(decomposition_hint(parent_asil.level)),
+        );
+    }
+}
+
+/// Valid decompositions of a level, e.g. "ASIL-D decomposes into C(D)+A(D), B(D)+B(D) or D(D)+QM(D)"
+fn decomposition_hint(level: u8) -> String {
+    if level == 0 {
+        return "QM requirements cannot be decomposed".to_string();
+    }
+    let from = level_name(level).trim_start_matches("ASIL-");
+    let short = |l: u8| level_name(l).trim_start_matches("ASIL-").to_string();
+    let options: Vec<String> = (level.div_ceil(2)..=level)
+        .rev()
+        .map(|high| format!("{}({})+{}({})", short(high), from, short(level - high), from))
+        .collect();
+    format!("{} decomposes into exactly two requirements: {}", level_name(level), options.join(", "))
+}
+
+/// Requirements allocated to or satisfied by a block must not exceed the block's ASIL
+fn check_allocations(graph: &ModelGraph, asil: &BTreeMap<NodeIndex, Asil>, diagnostics: &mut Vec<Diagnostic>) {
+    for edge in graph.edge_references() {
+        let kind = edge.weight().as_str();
+        if kind != LinkKind::Allocate.as_str() && kind != LinkKind::Satisfy.as_str() {
+            continue;
+        }
+        let (block, req) = (edge.source(), edge.target());
+        if !matches!(graph[block].1, Element::BlockDefinition(_)) || !matches!(graph[req].1, Element::Req(_)) {
+            continue;
+        }
+        let (Some(block_asil), Some(req_asil)) = (asil.get(&block), asil.get(&req)) else {
+            continue;
+        };
+        if req_asil.level > block_asil.level {
+            let (block_id, req_id) = (&graph[block].0, &graph[req].0);
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL033",
+                    format!(
+                        "{} ({}) is linked to block {} ({}) via {}, which has a lower ASIL",
+                        req_id,
+                        req_asil,
+                        block_id,
+                        level_name(block_asil.level),
+                        kind
+                    ),
+                )
+                .on(req_id)
+                .related([block_id])
+                .hint(format!(
+                    "Develop {} to {} or decompose the requirement",
+                    block_id,
+                    level_name(req_asil.level)
+                )),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::graph_from_json;
+    use serde_json::json;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(String, Option<String>)> {
+        let mut codes: Vec<_> = diagnostics.iter().map(|d| (d.code.clone(), d.element_id.clone())).collect();
+        codes.sort();
+        codes
+    }
+
+    #[test]
+    fn test_parse_asil_values() {
+        assert_eq!(Asil::parse("ASIL-D"), Some(Asil { level: 4, decomposed_from: None }));
+        assert_eq!(Asil::parse("B"), Some(Asil { level: 2, decomposed_from: None }));
+        assert_eq!(Asil::parse("ASIL-C(D)"), Some(Asil { level: 3, decomposed_from: Some(4) }));
+        assert_eq!(Asil::parse("QM(B)").unwrap().to_string(), "QM(B)");
+        assert_eq!(Asil::parse("ASIL-E"), None);
+    }
+
+    #[test]
+    fn test_inheritance_and_decomposition() {
+        let graph = graph_from_json(
+            json!({
+                "R-D": {"type": "req", "id": "R-D", "tags": {"safety": "ASIL-D"}},
+                "R-D.1": {"type": "req", "id": "R-D.1", "derives_from": "R-D", "tags": {"safety": "ASIL-C(D)"}},
+                "R-D.2": {"type": "req", "id": "R-D.2", "derives_from": "R-D", "tags": {"safety": "ASIL-A(D)"}},
+                "R-D.3": {"type": "req", "id": "R-D.3", "derives_from": "R-D", "tags": {"safety": "ASIL-D"}},
+                "R-C": {"type": "req", "id": "R-C", "tags": {"asil": "C"}},
+                "R-C.1": {"type": "req", "id": "R-C.1", "derives_from": "R-C", "tags": {"asil": "B"}},
+                "R-C.2": {"type": "req", "id": "R-C.2", "derives_from": "R-C", "tags": {"asil": "A(D)"}},
+                "R-B": {"type": "req", "id": "R-B", "tags": {"safety": "ASIL-B"}},
+                "R-B.1": {"type": "req", "id": "R-B.1", "derives_from": "R-B", "tags": {"safety": "A(B)"}},
+                "R-X": {"type": "req", "id": "R-X", "tags": {"safety": "high"}}
+            }),
+            json!([]),
+        );
+        let diagnostics = validate_asil(&graph);
+        assert_eq!(
+            codes(&diagnostics),
+            vec![
+                ("AL030".to_string(), Some("R-C.1".to_string())),
+                ("AL031".to_string(), Some("R-C.2".to_string())),
+                ("AL032".to_string(), Some("R-B".to_string())),
+                ("AL034".to_string(), Some("R-X".to_string())),
+            ]
+        );
+        let invalid = diagnostics.iter().find(|d| d.code == "AL032").unwrap();
+        assert_eq!(invalid.related_ids, vec!["R-B.1"]);
+        assert_eq!(
+            invalid.hint.as_deref(),
+            Some("ASIL-B decomposes into exactly two requirements: B(B)+QM(B), A(B)+A(B)")
+        );
+    }
+
+    #[test]
+    fn test_block_asil_limits_allocated_requirements() {
+        let graph = graph_from_json(
+            json!({
+                "R-1": {"type": "req", "id": "R-1", "tags": {"safety": "ASIL-C"}},
+                "R-2": {"type": "req", "id": "R-2", "tags": {"safety": "QM"}},
+                "BLK-QM": {"type": "block_definition", "id": "BLK-QM", "tags": {"asil": "QM"}},
+                "BLK-D": {"type": "block_definition", "id": "BLK-D", "tags": {"asil": "D"}}
+            }),
+            json!([
+                {"source": "BLK-QM", "type": "allocate", "target": "R-1"},
+                {"source": "BLK-QM", "type": "allocate", "target": "R-2"},
+                {"source": "BLK-D", "type": "satisfy", "target": "R-1"}
+            ]),
+        );
+        let diagnostics = validate_asil(&graph);
+        assert_eq!(codes(&diagnostics), vec![("AL033".to_string(), Some("R-1".to_string()))]);
+        assert_eq!(diagnostics[0].related_ids, vec!["BLK-QM"]);
+    }
+}
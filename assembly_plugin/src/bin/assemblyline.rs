@@ -0,0 +1,161 @@
+//! AssemblyLine Command Line
+//!
+//! Native entry points for tasks that read the local file system, which the
+//! Typst plugin cannot do.
+//!
+//! ```text
+//! assemblyline trace <source-dir> [--registry <registry.json>] [--id-prefix <prefix>]
+//! assemblyline verify <junit.xml>... --registry <registry.json> [--links <links.json>]
+//!                     [--match-attribute <attribute>] [--match-pattern <pattern>]
+//! assemblyline cargo <Cargo.toml or dir> [--members] [--root-id <id>] [--id-prefix <prefix>]
+//! ```
+//!
+//! Results are written to stdout as JSON in the registry format; problems found
+//! along the way (e.g. unknown requirement IDs) are reported on stderr. Without
+//! `--registry`, `trace` links every annotated ID without checking it.
+
+use assembly_plugin::cargo_import::{import_cargo_path, CargoImportOptions};
+use assembly_plugin::source_trace::{scan_source_tree, SourceTraceOptions};
+use assembly_plugin::verification::{junit_report, load_junit_files, JUnitMatch};
+use assembly_plugin::types::Element;
+use assembly_plugin::{Link, Registry};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage:
+  assemblyline trace <source-dir> [--registry <registry.json>] [--id-prefix <prefix>]
+  assemblyline verify <junit.xml>... --registry <registry.json> [--links <links.json>]
+                      [--match-attribute <attribute>] [--match-pattern <pattern>]
+  assemblyline cargo <Cargo.toml or dir> [--members] [--root-id <id>] [--id-prefix <prefix>]";
+
+/// Options that take no value
+const SWITCHES: [&str; 1] = ["members"];
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "trace" => trace(rest),
+        Some((command, rest)) if command == "verify" => verify(rest),
+        Some((command, rest)) if command == "cargo" => cargo(rest),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(json) => {
+            println!("{}", json);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Split arguments into positional values and `--name value` options
+fn parse_args(args: &[String]) -> Result<(Vec<&str>, HashMap<&str, &str>), String> {
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if SWITCHES.contains(&name) => {
+                options.insert(name, "true");
+            }
+            Some(name) => {
+                let value = args.next().ok_or_else(|| format!("Missing value for --{}\n{}", name, USAGE))?;
+                options.insert(name, value.as_str());
+            }
+            None => positional.push(arg.as_str()),
+        }
+    }
+    Ok((positional, options))
+}
+
+/// Registry JSON (ID → element) from a file
+fn read_registry(path: &str) -> Result<Registry, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid registry {}: {}", path, e))
+}
+
+/// Link records from a JSON file, empty if no file is given
+fn read_links(path: Option<&str>) -> Result<Vec<Link>, String> {
+    let Some(path) = path else {
+        return Ok(Vec::new());
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid links {}: {}", path, e))
+}
+
+/// Scan a source tree for trace annotations
+fn trace(args: &[String]) -> Result<String, String> {
+    let (positional, options) = parse_args(args)?;
+    let [root] = positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+    let registry = options.get("registry").map(|path| read_registry(path)).transpose()?;
+    let mut trace_options = SourceTraceOptions::default();
+    if let Some(prefix) = options.get("id-prefix") {
+        trace_options.id_prefix = prefix.to_string();
+    }
+
+    let scan = scan_source_tree(Path::new(root), registry.as_ref(), &trace_options)?;
+    for annotation in &scan.unknown {
+        eprintln!(
+            "{}:{}: unknown requirement ID '{}'",
+            annotation.file, annotation.line, annotation.target
+        );
+    }
+    serde_json::to_string_pretty(&scan).map_err(|e| e.to_string())
+}
+
+/// Roll JUnit XML reports up to verification status per test case, requirement and feature
+fn verify(args: &[String]) -> Result<String, String> {
+    let (positional, options) = parse_args(args)?;
+    let Some(registry_path) = options.get("registry").filter(|_| !positional.is_empty()) else {
+        return Err(USAGE.to_string());
+    };
+    let registry = read_registry(registry_path)?;
+    let links = read_links(options.get("links").copied())?;
+    let mut match_options = JUnitMatch::default();
+    if let Some(attribute) = options.get("match-attribute") {
+        match_options.attribute = attribute.to_string();
+    }
+    match_options.pattern = options.get("match-pattern").map(|p| p.to_string());
+
+    let paths: Vec<PathBuf> = positional.iter().map(PathBuf::from).collect();
+    let cases = load_junit_files(&paths)?;
+    let report = junit_report(&registry, &links, &cases, Vec::new(), &match_options);
+    for name in &report.unmatched {
+        eprintln!("unmatched JUnit test case '{}'", name);
+    }
+    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+}
+
+/// Import a crate's (or workspace's) Cargo features as feature elements
+fn cargo(args: &[String]) -> Result<String, String> {
+    let (positional, options) = parse_args(args)?;
+    let [path] = positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+    let mut import_options = CargoImportOptions::default();
+    if let Some(root_id) = options.get("root-id") {
+        import_options.root_id = root_id.to_string();
+    }
+    if let Some(prefix) = options.get("id-prefix") {
+        import_options.id_prefix = prefix.to_string();
+    }
+
+    let import = import_cargo_path(Path::new(path), options.contains_key("members"), &import_options)?;
+    for warning in &import.warnings {
+        eprintln!("{}", warning);
+    }
+    let registry: Registry = import
+        .features
+        .into_iter()
+        .map(|f| (f.id.clone(), Element::Feature(f)))
+        .collect();
+    serde_json::to_string_pretty(&registry).map_err(|e| e.to_string())
+}
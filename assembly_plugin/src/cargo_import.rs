@@ -0,0 +1,649 @@
+//! Cargo Feature Import Module
+//!
+//! Reads `Cargo.toml` manifests and translates their `[features]` tables and optional
+//! dependencies into feature elements.
+//!
+//! Layout of the imported tree (`<p>` is the ID prefix):
+//! - `<root>`: one feature for the import (crate name, or "Cargo workspace")
+//! - `<p><crate>`: mandatory feature per package
+//! - `<p><crate>.<feature>`: optional feature per `[features]` entry
+//! - `<p><crate>.dep-<name>`: optional feature per optional dependency
+//!
+//! Feature-enables-feature entries become `requires` tags. Every imported feature
+//! carries `cargo-package` and `cargo-feature` tags so `codegen` and
+//! `check_cargo_configurations` can map selections back to Cargo flags.
+//! Optional dependencies only enabled through `dep:` entries have no Cargo flag of
+//! their own; they are tagged `hidden` so selection lists can leave them out.
+
+use crate::codegen;
+use crate::types::{ConfigElement, Element, ElementCore, FeatureElement};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Options controlling the Cargo → feature translation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoImportOptions {
+    /// ID of the feature created for the import itself
+    #[serde(default = "default_root_id")]
+    pub root_id: String,
+    /// Optional parent for the root feature (to graft the tree into an existing model)
+    #[serde(default)]
+    pub root_parent: Option<String>,
+    /// Prefix prepended to crate names to form feature IDs
+    #[serde(default = "default_id_prefix")]
+    pub id_prefix: String,
+}
+
+fn default_root_id() -> String {
+    "ROOT".to_string()
+}
+
+fn default_id_prefix() -> String {
+    "F-".to_string()
+}
+
+impl Default for CargoImportOptions {
+    fn default() -> Self {
+        CargoImportOptions {
+            root_id: default_root_id(),
+            root_parent: None,
+            id_prefix: default_id_prefix(),
+        }
+    }
+}
+
+/// Result of a Cargo import
+#[derive(Debug, Clone)]
+pub struct CargoImport {
+    pub features: Vec<FeatureElement>,
+    pub warnings: Vec<String>,
+}
+
+/// Variability-relevant parts of one package manifest
+#[derive(Debug, Clone, Default)]
+pub struct CrateManifest {
+    pub name: String,
+    /// `[features]` table
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Optional dependency names (as used in feature entries)
+    pub optional_deps: BTreeSet<String>,
+}
+
+impl CrateManifest {
+    /// Optional dependencies referenced with `dep:` have no implicit feature
+    fn hidden_deps(&self) -> BTreeSet<&str> {
+        self.features
+            .values()
+            .flatten()
+            .filter_map(|entry| entry.strip_prefix("dep:"))
+            .collect()
+    }
+}
+
+/// Parse a manifest, returning `None` for a virtual (workspace-only) manifest
+pub fn parse_manifest(text: &str) -> Result<Option<CrateManifest>, String> {
+    let table: toml::Table = text
+        .parse()
+        .map_err(|e| format!("Invalid Cargo.toml: {}", e))?;
+
+    let Some(package) = table.get("package").and_then(|p| p.as_table()) else {
+        return Ok(None);
+    };
+    let name = package
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| "Cargo.toml [package] has no name".to_string())?
+        .to_string();
+
+    let mut manifest = CrateManifest {
+        name,
+        ..Default::default()
+    };
+
+    if let Some(features) = table.get("features").and_then(|f| f.as_table()) {
+        for (feature, entries) in features {
+            let entries = entries
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default();
+            manifest.features.insert(feature.clone(), entries);
+        }
+    }
+
+    // Optional dependencies from [dependencies], [build-dependencies] and target tables
+    let mut dep_tables: Vec<&toml::Table> = ["dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|key| table.get(*key).and_then(|t| t.as_table()))
+        .collect();
+    if let Some(targets) = table.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values().filter_map(|t| t.as_table()) {
+            dep_tables.extend(
+                ["dependencies", "build-dependencies"]
+                    .iter()
+                    .filter_map(|key| target.get(*key).and_then(|t| t.as_table())),
+            );
+        }
+    }
+    for deps in dep_tables {
+        for (dep, spec) in deps {
+            let optional = spec
+                .as_table()
+                .and_then(|t| t.get("optional"))
+                .and_then(|o| o.as_bool())
+                .unwrap_or(false);
+            if optional {
+                manifest.optional_deps.insert(dep.clone());
+            }
+        }
+    }
+
+    Ok(Some(manifest))
+}
+
+/// Import one or more manifest texts (several = workspace members)
+pub fn import_cargo_manifests(manifests: &[String], options: &CargoImportOptions) -> CargoImport {
+    let mut warnings = Vec::new();
+    let mut crates = Vec::new();
+    for (n, text) in manifests.iter().enumerate() {
+        match parse_manifest(text) {
+            Ok(Some(manifest)) => crates.push(manifest),
+            Ok(None) => warnings.push(format!(
+                "manifest {}: virtual workspace manifest has no [package]; skipped",
+                n + 1
+            )),
+            Err(e) => warnings.push(format!("manifest {}: {}", n + 1, e)),
+        }
+    }
+    let mut import = crates_to_features(&crates, options);
+    warnings.append(&mut import.warnings);
+    import.warnings = warnings;
+    import
+}
+
+/// Translate parsed manifests into feature elements
+pub fn crates_to_features(crates: &[CrateManifest], options: &CargoImportOptions) -> CargoImport {
+    let mut warnings = Vec::new();
+    let workspace = crates.len() > 1;
+    let by_name: HashMap<&str, &CrateManifest> =
+        crates.iter().map(|c| (c.name.as_str(), c)).collect();
+
+    let crate_id = |krate: &str| format!("{}{}", options.id_prefix, krate);
+    let feature_id = |krate: &str, feature: &str| format!("{}{}.{}", options.id_prefix, krate, feature);
+    let dep_id = |krate: &str, dep: &str| format!("{}{}.dep-{}", options.id_prefix, krate, dep);
+    let cargo_name = |krate: &str, feature: &str| {
+        if workspace {
+            format!("{}/{}", krate, feature)
+        } else {
+            feature.to_string()
+        }
+    };
+
+    let root_title = match crates {
+        [single] => single.name.clone(),
+        _ => "Cargo workspace".to_string(),
+    };
+    let mut root = new_feature(&options.root_id, &root_title, options.root_parent.clone());
+    root.tags
+        .insert("cargo-feature".to_string(), serde_json::json!(false));
+    let mut features = vec![root];
+
+    for krate in crates {
+        let hidden = krate.hidden_deps();
+
+        let mut package = new_feature(&crate_id(&krate.name), &krate.name, Some(options.root_id.clone()));
+        package.tags.insert("mandatory".to_string(), serde_json::json!(true));
+        package
+            .tags
+            .insert("cargo-package".to_string(), serde_json::json!(krate.name));
+        package
+            .tags
+            .insert("cargo-feature".to_string(), serde_json::json!(false));
+        features.push(package);
+
+        for dep in &krate.optional_deps {
+            let mut feature = new_feature(
+                &dep_id(&krate.name, dep),
+                &format!("dependency {}", dep),
+                Some(crate_id(&krate.name)),
+            );
+            feature
+                .tags
+                .insert("cargo-package".to_string(), serde_json::json!(krate.name));
+            feature
+                .tags
+                .insert("cargo-dependency".to_string(), serde_json::json!(dep));
+            if hidden.contains(dep.as_str()) {
+                // Only reachable through features that say `dep:<name>`; it stays
+                // concrete so configurations can select what those features require
+                feature
+                    .tags
+                    .insert("cargo-feature".to_string(), serde_json::json!(false));
+                feature.tags.insert("hidden".to_string(), serde_json::json!(true));
+            } else {
+                feature.tags.insert(
+                    "cargo-feature".to_string(),
+                    serde_json::json!(cargo_name(&krate.name, dep)),
+                );
+            }
+            features.push(feature);
+        }
+
+        for (name, entries) in &krate.features {
+            let mut feature = new_feature(
+                &feature_id(&krate.name, name),
+                name,
+                Some(crate_id(&krate.name)),
+            );
+            feature
+                .tags
+                .insert("cargo-package".to_string(), serde_json::json!(krate.name));
+            feature.tags.insert(
+                "cargo-feature".to_string(),
+                serde_json::json!(cargo_name(&krate.name, name)),
+            );
+
+            let mut requires = BTreeSet::new();
+            for entry in entries {
+                if let Some(dep) = entry.strip_prefix("dep:") {
+                    if krate.optional_deps.contains(dep) {
+                        requires.insert(dep_id(&krate.name, dep));
+                    } else {
+                        warnings.push(format!(
+                            "{}: feature '{}' enables 'dep:{}' which is not an optional dependency",
+                            krate.name, name, dep
+                        ));
+                    }
+                } else if let Some((dep, dep_feature)) = entry.split_once('/') {
+                    let (dep, weak) = match dep.strip_suffix('?') {
+                        Some(dep) => (dep, true),
+                        None => (dep, false),
+                    };
+                    if !weak && krate.optional_deps.contains(dep) {
+                        requires.insert(dep_id(&krate.name, dep));
+                    }
+                    match by_name.get(dep) {
+                        Some(member) if !weak => {
+                            if member.features.contains_key(dep_feature) {
+                                requires.insert(feature_id(dep, dep_feature));
+                            } else if member.optional_deps.contains(dep_feature) {
+                                requires.insert(dep_id(dep, dep_feature));
+                            } else {
+                                warnings.push(format!(
+                                    "{}: feature '{}' enables unknown feature '{}' of workspace member {}",
+                                    krate.name, name, dep_feature, dep
+                                ));
+                            }
+                        }
+                        Some(_) => warnings.push(format!(
+                            "{}: weak feature '{}' of feature '{}' is conditional and not modelled",
+                            krate.name, entry, name
+                        )),
+                        None => {}
+                    }
+                } else if krate.features.contains_key(entry) {
+                    requires.insert(feature_id(&krate.name, entry));
+                } else if krate.optional_deps.contains(entry) && !hidden.contains(entry.as_str()) {
+                    requires.insert(dep_id(&krate.name, entry));
+                } else {
+                    warnings.push(format!(
+                        "{}: feature '{}' enables unknown feature '{}'",
+                        krate.name, name, entry
+                    ));
+                }
+            }
+            match requires.len() {
+                0 => {}
+                1 => {
+                    feature.tags.insert(
+                        "requires".to_string(),
+                        serde_json::json!(requires.into_iter().next()),
+                    );
+                }
+                _ => {
+                    feature
+                        .tags
+                        .insert("requires".to_string(), serde_json::json!(requires));
+                }
+            }
+            features.push(feature);
+        }
+    }
+
+    CargoImport { features, warnings }
+}
+
+/// Read a manifest from disk, optionally following `[workspace] members`
+///
+/// `path` may point at a `Cargo.toml` or at the directory containing it. Member
+/// patterns support plain paths and a trailing `/*` wildcard.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_cargo_path(
+    path: &std::path::Path,
+    include_members: bool,
+    options: &CargoImportOptions,
+) -> Result<CargoImport, String> {
+    let manifest_path = if path.is_dir() {
+        path.join("Cargo.toml")
+    } else {
+        path.to_path_buf()
+    };
+    let base = manifest_path
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let read = |p: &std::path::Path| {
+        std::fs::read_to_string(p).map_err(|e| format!("Cannot read {}: {}", p.display(), e))
+    };
+
+    let root_text = read(&manifest_path)?;
+    let table: toml::Table = root_text
+        .parse()
+        .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?;
+
+    // A virtual workspace root contributes members only
+    let mut manifests = Vec::new();
+    if table.contains_key("package") || !include_members {
+        manifests.push(root_text);
+    }
+
+    if include_members {
+        let workspace = table.get("workspace").and_then(|w| w.as_table());
+        let list = |key: &str| -> Vec<String> {
+            workspace
+                .and_then(|w| w.get(key))
+                .and_then(|m| m.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let excluded: BTreeSet<std::path::PathBuf> =
+            list("exclude").iter().map(|e| base.join(e)).collect();
+
+        let mut member_dirs = BTreeSet::new();
+        for pattern in list("members") {
+            if let Some(dir) = pattern.strip_suffix("/*") {
+                let entries = std::fs::read_dir(base.join(dir))
+                    .map_err(|e| format!("Cannot list {}: {}", base.join(dir).display(), e))?;
+                for entry in entries.flatten() {
+                    if entry.path().join("Cargo.toml").is_file() {
+                        member_dirs.insert(entry.path());
+                    }
+                }
+            } else {
+                member_dirs.insert(base.join(pattern));
+            }
+        }
+        for dir in member_dirs {
+            if !excluded.contains(&dir) && dir != base {
+                manifests.push(read(&dir.join("Cargo.toml"))?);
+            }
+        }
+    }
+
+    Ok(import_cargo_manifests(&manifests, options))
+}
+
+/// Outcome of checking one configuration against the imported Cargo features
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoConfigCheck {
+    pub config_id: String,
+    /// True if Cargo accepts the generated flags
+    pub accepted: bool,
+    /// Flags as produced by `codegen`
+    pub cargo_args: String,
+    pub errors: Vec<String>,
+    /// Features Cargo would enable transitively that the configuration does not select
+    pub implicitly_enabled: Vec<String>,
+}
+
+/// Check that every configuration maps to a feature set Cargo would accept
+///
+/// A selection is accepted when every selected Cargo feature exists and can be
+/// enabled from the command line. Features switched on transitively but missing
+/// from the configuration are listed so the product model can be aligned.
+pub fn check_cargo_configurations(registry: &HashMap<String, Element>) -> Vec<CargoConfigCheck> {
+    let cargo_features: HashMap<&str, &FeatureElement> = registry
+        .values()
+        .filter_map(|e| e.as_feature())
+        .filter(|f| f.has_tag("cargo-package"))
+        .map(|f| (f.id.as_str(), f))
+        .collect();
+
+    let mut configs: Vec<&ConfigElement> = registry.values().filter_map(|e| e.as_config()).collect();
+    configs.sort_by(|a, b| a.id.cmp(&b.id));
+
+    configs
+        .into_iter()
+        .map(|config| {
+            let mut errors = Vec::new();
+
+            // Transitive closure over requires, restricted to Cargo features
+            let mut enabled: BTreeSet<&str> = config
+                .selected
+                .iter()
+                .map(String::as_str)
+                .filter(|id| cargo_features.contains_key(id))
+                .collect();
+            let mut stack: Vec<&str> = enabled.iter().copied().collect();
+            while let Some(id) = stack.pop() {
+                for required in required_ids(cargo_features[id]) {
+                    match cargo_features.get_key_value(required.as_str()) {
+                        Some((&key, _)) => {
+                            if enabled.insert(key) {
+                                stack.push(key);
+                            }
+                        }
+                        None => errors.push(format!(
+                            "{}: '{}' requires unknown Cargo feature '{}'",
+                            config.id, id, required
+                        )),
+                    }
+                }
+            }
+            // Dependencies hidden behind `dep:` need a selected feature that enables them
+            for id in &config.selected {
+                let Some(feature) = cargo_features.get(id.as_str()) else {
+                    continue;
+                };
+                let hidden = feature.tags.get("hidden") == Some(&serde_json::json!(true));
+                let enabled_by_feature = enabled
+                    .iter()
+                    .any(|other| required_ids(cargo_features[other]).contains(id));
+                if hidden && !enabled_by_feature {
+                    errors.push(format!(
+                        "{} selects '{}', which Cargo cannot enable directly (only via dep: syntax)",
+                        config.id, id
+                    ));
+                }
+            }
+
+            let implicitly_enabled: Vec<String> = enabled
+                .into_iter()
+                .filter(|id| !config.is_feature_selected(id))
+                .map(str::to_string)
+                .collect();
+
+            let cargo_args = match codegen::generate_build_artefacts(registry, &config.id) {
+                Ok(artefacts) => artefacts.cargo_args,
+                Err(e) => {
+                    errors.push(e);
+                    String::new()
+                }
+            };
+
+            CargoConfigCheck {
+                config_id: config.id.clone(),
+                accepted: errors.is_empty(),
+                cargo_args,
+                errors,
+                implicitly_enabled,
+            }
+        })
+        .collect()
+}
+
+/// IDs listed in a feature's `requires` tag (string or array)
+fn required_ids(feature: &FeatureElement) -> Vec<String> {
+    match feature.tags.get("requires") {
+        Some(serde_json::Value::String(s)) => vec![s.clone()],
+        Some(serde_json::Value::Array(a)) => a
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn new_feature(id: &str, title: &str, parent: Option<String>) -> FeatureElement {
+    FeatureElement {
+        id: id.to_string(),
+        title: title.to_string(),
+        tags: HashMap::new(),
+        parent,
+        concrete: Some(true),
+        group: None,
+        body: serde_json::Value::Null,
+        parameters: None,
+        constraints: None,
+        requires: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "netstack"
+version = "0.1.0"
+
+[features]
+default = ["std"]
+std = []
+tls = ["dep:rustls", "std"]
+json = ["serde", "serde/derive"]
+
+[dependencies]
+rustls = { version = "0.23", optional = true }
+serde = { version = "1", optional = true }
+log = "0.4"
+"#;
+
+    fn find<'a>(import: &'a CargoImport, id: &str) -> &'a FeatureElement {
+        import.features.iter().find(|f| f.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_features_and_optional_deps() {
+        let import = import_cargo_manifests(&[MANIFEST.to_string()], &CargoImportOptions::default());
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+        let tls = find(&import, "F-netstack.tls");
+        assert_eq!(tls.parent.as_deref(), Some("F-netstack"));
+        assert_eq!(
+            tls.tags.get("requires"),
+            Some(&json!(["F-netstack.dep-rustls", "F-netstack.std"]))
+        );
+        assert_eq!(tls.tags.get("cargo-feature"), Some(&json!("tls")));
+
+        // rustls is hidden behind dep:, serde keeps its implicit feature
+        let rustls = find(&import, "F-netstack.dep-rustls");
+        assert!(!rustls.is_abstract());
+        assert_eq!(rustls.tags.get("hidden"), Some(&json!(true)));
+        assert_eq!(rustls.tags.get("cargo-feature"), Some(&json!(false)));
+        let serde = find(&import, "F-netstack.dep-serde");
+        assert_eq!(serde.tags.get("cargo-feature"), Some(&json!("serde")));
+        assert!(!import.features.iter().any(|f| f.id.ends_with("dep-log")));
+    }
+
+    #[test]
+    fn test_workspace_member_features() {
+        let app = r#"
+[package]
+name = "app"
+[features]
+secure = ["netstack/tls"]
+[dependencies]
+netstack = { path = "../netstack" }
+"#;
+        let import = import_cargo_manifests(
+            &[MANIFEST.to_string(), app.to_string()],
+            &CargoImportOptions::default(),
+        );
+        let secure = find(&import, "F-app.secure");
+        assert_eq!(secure.tags.get("requires"), Some(&json!("F-netstack.tls")));
+        assert_eq!(secure.tags.get("cargo-feature"), Some(&json!("app/secure")));
+    }
+
+    #[test]
+    fn test_import_workspace_from_disk() {
+        let dir = std::env::temp_dir().join(format!("assemblyline-cargo-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("crates/netstack")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        std::fs::write(dir.join("crates/netstack/Cargo.toml"), MANIFEST).unwrap();
+
+        let import = import_cargo_path(&dir, true, &CargoImportOptions::default()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(import.features.iter().any(|f| f.id == "F-netstack.tls"));
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+    }
+
+    #[test]
+    fn test_check_configurations() {
+        let import = import_cargo_manifests(&[MANIFEST.to_string()], &CargoImportOptions::default());
+        let mut registry: HashMap<String, Element> = import
+            .features
+            .into_iter()
+            .map(|f| (f.id.clone(), Element::Feature(f)))
+            .collect();
+        let config = |id: &str, selected: &[&str]| {
+            Element::Config(ConfigElement {
+                id: id.to_string(),
+                title: id.to_string(),
+                tags: HashMap::new(),
+                root_feature_id: "ROOT".to_string(),
+                selected: selected.iter().map(|s| s.to_string()).collect(),
+                body: json!({}),
+                bindings: None,
+            })
+        };
+        registry.insert("CONFIG:CFG-OK".to_string(), config("CFG-OK", &["F-netstack.json", "F-netstack.dep-serde"]));
+        registry.insert("CONFIG:CFG-TLS".to_string(), config("CFG-TLS", &["F-netstack.tls", "F-netstack.dep-rustls"]));
+        registry.insert("CONFIG:CFG-BAD".to_string(), config("CFG-BAD", &["F-netstack.dep-rustls"]));
+
+        let checks = check_cargo_configurations(&registry);
+        let bad = checks.iter().find(|c| c.config_id == "CFG-BAD").unwrap();
+        assert!(!bad.accepted);
+        assert!(bad.errors[0].contains("only via dep: syntax"), "{:?}", bad.errors);
+
+        let tls = checks.iter().find(|c| c.config_id == "CFG-TLS").unwrap();
+        assert!(tls.accepted, "{:?}", tls.errors);
+        assert_eq!(tls.implicitly_enabled, vec!["F-netstack.std".to_string()]);
+
+        let ok = checks.iter().find(|c| c.config_id == "CFG-OK").unwrap();
+        assert!(ok.accepted, "{:?}", ok.errors);
+        assert!(ok.implicitly_enabled.is_empty());
+        assert_eq!(ok.cargo_args, "--no-default-features --features serde,json");
+    }
+
+    #[test]
+    fn test_configuration_enabling_dep_feature_is_valid() {
+        let import = import_cargo_manifests(&[MANIFEST.to_string()], &CargoImportOptions::default());
+        let registry: HashMap<String, Element> = import
+            .features
+            .into_iter()
+            .map(|f| (f.id.clone(), Element::Feature(f)))
+            .collect();
+        let selected: Vec<String> = ["ROOT", "F-netstack", "F-netstack.tls", "F-netstack.dep-rustls", "F-netstack.std"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        // No abstract selection (AL008) and the requires closure is satisfiable
+        assert!(selected.iter().all(|id| !registry[id].as_feature().unwrap().is_abstract()));
+        let validation = crate::feature_validation::validate_configuration(&registry, "ROOT", &selected);
+        assert!(validation.is_consistent, "{}", validation.message);
+    }
+}
@@ -0,0 +1,448 @@
+//! Build Artefact Generation Module
+//!
+//! Emits build-system inputs (C header, Kconfig fragment, Cargo features, CMake cache)
+//! for a product configuration.
+//!
+//! Symbol names are derived from feature tags:
+//! - `symbol`: base name used by all generators (default: ID without `F-`, upper-cased)
+//! - `c-define`, `kconfig`, `cargo-feature`, `cmake`: per-generator override;
+//!   setting one of these to `false` leaves the feature out of that artefact
+//! - `param-prefix`: prefix for parameter symbols (default: `<base>_`, or empty for
+//!   features imported from Kconfig, whose parameters are already symbol names)
+
+use crate::types::{ConfigElement, Element, ElementCore, FeatureElement, ParameterSchema};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Generated build artefacts for one configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildArtefacts {
+    pub config_id: String,
+    /// C header with `#define` lines
+    pub c_header: String,
+    /// Kconfig `.config` fragment
+    pub kconfig: String,
+    /// Cargo feature names
+    pub cargo_features: Vec<String>,
+    /// Ready-to-use `--features a,b,c` argument (plus `--no-default-features`
+    /// when a feature named `default` is known but not selected)
+    pub cargo_args: String,
+    /// CMake initial cache file (`cmake -C`)
+    pub cmake_cache: String,
+    pub warnings: Vec<String>,
+}
+
+/// Build system targeted by a symbol lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    C,
+    Kconfig,
+    Cargo,
+    CMake,
+}
+
+impl Target {
+    fn tag(self) -> &'static str {
+        match self {
+            Target::C => "c-define",
+            Target::Kconfig => "kconfig",
+            Target::Cargo => "cargo-feature",
+            Target::CMake => "cmake",
+        }
+    }
+}
+
+/// Resolved parameter value of a selected feature
+struct ParamValue<'a> {
+    name: String,
+    schema: &'a ParameterSchema,
+    value: serde_json::Value,
+}
+
+/// Generate all build artefacts for a configuration
+pub fn generate_build_artefacts(
+    registry: &HashMap<String, Element>,
+    config_id: &str,
+) -> Result<BuildArtefacts, String> {
+    let config_key = format!("CONFIG:{}", config_id);
+    let config = match registry.get(&config_key) {
+        Some(Element::Config(cfg)) => cfg,
+        _ => return Err(format!("Configuration '{}' not found", config_id)),
+    };
+
+    let mut warnings = Vec::new();
+
+    // All concrete features in deterministic order
+    let mut features: Vec<&FeatureElement> = registry
+        .values()
+        .filter_map(|e| e.as_feature())
+        .filter(|f| !f.is_abstract())
+        .collect();
+    features.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for feature_id in &config.selected {
+        if !matches!(registry.get(feature_id), Some(Element::Feature(_))) {
+            warnings.push(format!(
+                "Configuration '{}' selects unknown feature '{}'; skipped",
+                config_id, feature_id
+            ));
+        }
+    }
+
+    // Parameter values per selected feature (binding, else default)
+    let mut params: HashMap<&str, Vec<ParamValue>> = HashMap::new();
+    for feature in &features {
+        if !config.is_feature_selected(&feature.id) {
+            continue;
+        }
+        let Some(schemas) = &feature.parameters else {
+            continue;
+        };
+        let mut names: Vec<&String> = schemas.keys().collect();
+        names.sort();
+        let mut values = Vec::new();
+        for name in names {
+            let schema = &schemas[name];
+            let bound = config
+                .bindings
+                .as_ref()
+                .and_then(|b| b.get(&feature.id))
+                .and_then(|b| b.get(name));
+            let value = bound.cloned().unwrap_or_else(|| schema.default.clone());
+            if value.is_null() {
+                warnings.push(format!(
+                    "Feature '{}', parameter '{}': no binding and no default; not emitted",
+                    feature.id, name
+                ));
+                continue;
+            }
+            values.push(ParamValue {
+                name: format!("{}{}", param_prefix(feature), sanitize(name)),
+                schema,
+                value,
+            });
+        }
+        params.insert(&feature.id, values);
+    }
+
+    // Cargo enables `default` implicitly, so it only shows up when deselected
+    let mut cargo_features = Vec::new();
+    let mut no_default_features = false;
+    for feature in &features {
+        let Some(name) = symbol(feature, Target::Cargo) else {
+            continue;
+        };
+        let is_default = name == "default" || name.ends_with("/default");
+        if !config.is_feature_selected(&feature.id) {
+            no_default_features |= is_default;
+        } else if !is_default {
+            cargo_features.push(name);
+        }
+    }
+    let mut cargo_args = Vec::new();
+    if no_default_features {
+        cargo_args.push("--no-default-features".to_string());
+    }
+    if !cargo_features.is_empty() {
+        cargo_args.push(format!("--features {}", cargo_features.join(",")));
+    }
+
+    Ok(BuildArtefacts {
+        config_id: config_id.to_string(),
+        c_header: c_header(config, &features, &params),
+        kconfig: kconfig_fragment(config, &features, &params),
+        cargo_args: cargo_args.join(" "),
+        cargo_features,
+        cmake_cache: cmake_cache(config, &features, &params),
+        warnings,
+    })
+}
+
+/// Generate a C header: `#define` for each selected feature and its parameters
+fn c_header(
+    config: &ConfigElement,
+    features: &[&FeatureElement],
+    params: &HashMap<&str, Vec<ParamValue>>,
+) -> String {
+    let guard = format!("ASSEMBLYLINE_CONFIG_{}_H", sanitize(&config.id));
+    let mut out = String::new();
+    out.push_str(&format!(
+        "/* Generated by AssemblyLine for configuration {} - do not edit */\n",
+        config.id
+    ));
+    out.push_str(&format!("#ifndef {}\n#define {}\n\n", guard, guard));
+
+    for feature in features {
+        if !config.is_feature_selected(&feature.id) {
+            continue;
+        }
+        let Some(name) = symbol(feature, Target::C) else {
+            continue;
+        };
+        out.push_str(&format!("#define {} 1\n", name));
+        for param in params.get(feature.id.as_str()).into_iter().flatten() {
+            let value = match param.schema.param_type.as_str() {
+                "Integer" => param.value.to_string(),
+                "Boolean" => if param.value.as_bool() == Some(true) { "1" } else { "0" }.to_string(),
+                _ => c_string(&json_text(&param.value)),
+            };
+            out.push_str(&format!("#define {} {}\n", param.name, value));
+        }
+    }
+
+    out.push_str(&format!("\n#endif /* {} */\n", guard));
+    out
+}
+
+/// Generate a Kconfig `.config` fragment covering every concrete feature
+fn kconfig_fragment(
+    config: &ConfigElement,
+    features: &[&FeatureElement],
+    params: &HashMap<&str, Vec<ParamValue>>,
+) -> String {
+    let mut out = format!("# Generated by AssemblyLine for configuration {}\n", config.id);
+    for feature in features {
+        let Some(name) = symbol(feature, Target::Kconfig) else {
+            continue;
+        };
+        if !config.is_feature_selected(&feature.id) {
+            out.push_str(&format!("# CONFIG_{} is not set\n", name));
+            continue;
+        }
+        out.push_str(&format!("CONFIG_{}=y\n", name));
+        for param in params.get(feature.id.as_str()).into_iter().flatten() {
+            match param.schema.param_type.as_str() {
+                "Integer" => out.push_str(&format!("CONFIG_{}={}\n", param.name, param.value)),
+                "Boolean" if param.value.as_bool() == Some(true) => {
+                    out.push_str(&format!("CONFIG_{}=y\n", param.name))
+                }
+                "Boolean" => out.push_str(&format!("# CONFIG_{} is not set\n", param.name)),
+                _ => out.push_str(&format!(
+                    "CONFIG_{}={}\n",
+                    param.name,
+                    c_string(&json_text(&param.value))
+                )),
+            }
+        }
+    }
+    out
+}
+
+/// Generate a CMake initial cache file covering every concrete feature
+fn cmake_cache(
+    config: &ConfigElement,
+    features: &[&FeatureElement],
+    params: &HashMap<&str, Vec<ParamValue>>,
+) -> String {
+    let mut out = format!(
+        "# Generated by AssemblyLine for configuration {} (use with cmake -C)\n",
+        config.id
+    );
+    for feature in features {
+        let Some(name) = symbol(feature, Target::CMake) else {
+            continue;
+        };
+        let selected = config.is_feature_selected(&feature.id);
+        out.push_str(&format!(
+            "set({} {} CACHE BOOL {})\n",
+            name,
+            if selected { "ON" } else { "OFF" },
+            cmake_string(&feature.title)
+        ));
+        if !selected {
+            continue;
+        }
+        for param in params.get(feature.id.as_str()).into_iter().flatten() {
+            let (value, kind) = match param.schema.param_type.as_str() {
+                "Integer" => (param.value.to_string(), "STRING"),
+                "Boolean" => (
+                    if param.value.as_bool() == Some(true) { "ON" } else { "OFF" }.to_string(),
+                    "BOOL",
+                ),
+                _ => (cmake_string(&json_text(&param.value)), "STRING"),
+            };
+            let doc = param.schema.description.clone().unwrap_or_default();
+            out.push_str(&format!(
+                "set({} {} CACHE {} {})\n",
+                param.name,
+                value,
+                kind,
+                cmake_string(&doc)
+            ));
+        }
+    }
+    out
+}
+
+/// Resolve the symbol name of a feature for a target, `None` if opted out
+fn symbol(feature: &FeatureElement, target: Target) -> Option<String> {
+    match feature.tags.get(target.tag()) {
+        Some(serde_json::Value::Bool(false)) | Some(serde_json::Value::Null) => return None,
+        Some(serde_json::Value::String(s)) if !s.is_empty() => return Some(s.clone()),
+        _ => {}
+    }
+    let base = base_name(feature);
+    Some(match target {
+        Target::C | Target::CMake => format!("FEATURE_{}", base),
+        Target::Kconfig => base,
+        Target::Cargo => base.to_lowercase().replace('_', "-"),
+    })
+}
+
+/// Base symbol name: `symbol` tag, then `kconfig` tag, then the sanitized ID
+fn base_name(feature: &FeatureElement) -> String {
+    if let Some(name) = feature.tag::<String>("symbol").or_else(|| feature.tag("kconfig")) {
+        return name;
+    }
+    let id = feature.id.strip_prefix("F-").unwrap_or(&feature.id);
+    sanitize(id)
+}
+
+/// Prefix used for parameter symbols of a feature
+fn param_prefix(feature: &FeatureElement) -> String {
+    if let Some(prefix) = feature.tag::<String>("param-prefix") {
+        return prefix;
+    }
+    if feature.has_tag("kconfig") {
+        return String::new();
+    }
+    format!("{}_", base_name(feature))
+}
+
+/// Upper-case an identifier and replace anything that is not alphanumeric with `_`
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Plain text of a JSON scalar (strings without quotes)
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a string for C and Kconfig
+fn c_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote a string for CMake
+fn cmake_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\$")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_feature(id: &str, tags: serde_json::Value) -> (String, Element) {
+        (
+            id.to_string(),
+            Element::Feature(FeatureElement {
+                id: id.to_string(),
+                title: id.to_string(),
+                tags: serde_json::from_value(tags).unwrap(),
+                parent: Some("ROOT".to_string()),
+                concrete: Some(true),
+                group: None,
+                body: json!({}),
+                parameters: None,
+                constraints: None,
+                requires: None,
+            }),
+        )
+    }
+
+    fn create_test_registry() -> HashMap<String, Element> {
+        let mut registry: HashMap<String, Element> = HashMap::new();
+        let (id, mut cache) = create_test_feature("F-CACHE", json!({}));
+        if let Element::Feature(f) = &mut cache {
+            let mut params = HashMap::new();
+            params.insert(
+                "size".to_string(),
+                ParameterSchema {
+                    param_type: "Integer".to_string(),
+                    range: Some((16, 1024)),
+                    values: None,
+                    unit: None,
+                    default: json!(64),
+                    description: None,
+                },
+            );
+            params.insert(
+                "policy".to_string(),
+                ParameterSchema {
+                    param_type: "Enum".to_string(),
+                    range: None,
+                    values: Some(vec!["LRU".to_string(), "FIFO".to_string()]),
+                    unit: None,
+                    default: json!("LRU"),
+                    description: None,
+                },
+            );
+            f.parameters = Some(params);
+        }
+        registry.insert(id, cache);
+        let (id, f) = create_test_feature("F-TLS", json!({"c-define": "USE_TLS", "cargo-feature": "tls"}));
+        registry.insert(id, f);
+        let (id, f) = create_test_feature("F-DEBUG", json!({"cargo-feature": false}));
+        registry.insert(id, f);
+
+        let mut bindings = HashMap::new();
+        bindings.insert("F-CACHE".to_string(), HashMap::from([("size".to_string(), json!(256))]));
+        registry.insert(
+            "CONFIG:CFG-A".to_string(),
+            Element::Config(ConfigElement {
+                id: "CFG-A".to_string(),
+                title: "A".to_string(),
+                tags: HashMap::new(),
+                root_feature_id: "ROOT".to_string(),
+                selected: vec!["F-CACHE".to_string(), "F-TLS".to_string(), "F-DEBUG".to_string()],
+                body: json!({}),
+                bindings: Some(bindings),
+            }),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_c_header_uses_bindings_and_defaults() {
+        let out = generate_build_artefacts(&create_test_registry(), "CFG-A").unwrap();
+        assert!(out.c_header.contains("#define FEATURE_CACHE 1\n"));
+        assert!(out.c_header.contains("#define CACHE_SIZE 256\n"));
+        assert!(out.c_header.contains("#define CACHE_POLICY \"LRU\"\n"));
+        assert!(out.c_header.contains("#define USE_TLS 1\n"));
+    }
+
+    #[test]
+    fn test_cargo_features_respect_tags() {
+        let out = generate_build_artefacts(&create_test_registry(), "CFG-A").unwrap();
+        assert_eq!(out.cargo_features, vec!["cache".to_string(), "tls".to_string()]);
+        assert_eq!(out.cargo_args, "--features cache,tls");
+    }
+
+    #[test]
+    fn test_kconfig_and_cmake_list_unselected() {
+        let mut registry = create_test_registry();
+        let (id, f) = create_test_feature("F-EXTRA", json!({}));
+        registry.insert(id, f);
+        let out = generate_build_artefacts(&registry, "CFG-A").unwrap();
+        assert!(out.kconfig.contains("CONFIG_CACHE=y\n"));
+        assert!(out.kconfig.contains("CONFIG_CACHE_SIZE=256\n"));
+        assert!(out.kconfig.contains("# CONFIG_EXTRA is not set\n"));
+        assert!(out.cmake_cache.contains("set(FEATURE_EXTRA OFF CACHE BOOL \"F-EXTRA\")\n"));
+        assert!(out.cmake_cache.contains("set(CACHE_SIZE 256 CACHE STRING \"\")\n"));
+    }
+
+    #[test]
+    fn test_missing_config() {
+        assert!(generate_build_artefacts(&create_test_registry(), "CFG-NOPE").is_err());
+    }
+}
@@ -1,8 +1,8 @@
-/// Constraint Evaluator Module
-/// Evaluates parsed constraint expressions against actual parameter values
+//! Constraint Evaluator Module
+//! Evaluates parsed constraint expressions against actual parameter values
 
 use crate::constraint_parser::{ArithOp, CompOp, Expr};
-use crate::types::{ConfigElement, Element, FeatureElement};
+use crate::types::{ConfigElement, Element};
 use std::collections::HashMap;
 
 /// Value type for evaluation
@@ -27,13 +27,6 @@ impl Value {
             _ => Err(format!("Expected boolean, got {:?}", self)),
         }
     }
-
-    fn as_string(&self) -> Result<String, String> {
-        match self {
-            Value::String(s) => Ok(s.clone()),
-            _ => Err(format!("Expected string, got {:?}", self)),
-        }
-    }
 }
 
 /// Evaluate a constraint expression
@@ -186,7 +179,7 @@ fn evaluate_expr(
 mod tests {
     use super::*;
     use crate::constraint_parser::parse_constraint;
-    use crate::types::ParameterSchema;
+    use crate::types::{FeatureElement, ParameterSchema};
     use serde_json::json;
 
     fn create_test_config(
@@ -204,27 +197,6 @@ mod tests {
         }
     }
 
-    fn create_test_feature(
-        id: &str,
-        params: HashMap<String, ParameterSchema>,
-    ) -> (String, Element) {
-        (
-            id.to_string(),
-            Element::Feature(FeatureElement {
-                id: id.to_string(),
-                title: "Test".to_string(),
-                tags: HashMap::new(),
-                parent: None,
-                concrete: Some(true),
-                group: None,
-                body: json!({}),
-                parameters: Some(params),
-                constraints: None,
-                requires: None,
-            }),
-        )
-    }
-
     #[test]
     fn test_evaluate_simple_comparison() {
         let mut registry = HashMap::new();
@@ -1,5 +1,5 @@
-/// Constraint Parser Module
-/// Parses constraint strings into an AST for evaluation
+//! Constraint Parser Module
+//! Parses constraint strings into an AST for evaluation
 
 use serde::{Deserialize, Serialize};
 
@@ -112,8 +112,8 @@ pub fn parse_constraint(input: &str) -> Result<Expr, String> {
     }
 
     // Try to parse as NOT
-    if input.starts_with('!') {
-        let inner = parse_constraint(&input[1..])?;
+    if let Some(rest) = input.strip_prefix('!') {
+        let inner = parse_constraint(rest)?;
         return Ok(Expr::Not(Box::new(inner)));
     }
 
@@ -0,0 +1,368 @@
+//! Requirement Coverage Analysis
+//!
+//! Computes per-requirement design, verification and trace coverage over the
+//! model graph, plus aggregates per feature, requirement type and safety level.
+//!
+//! Following the decomposition rule, design and verification links are placed
+//! on leaf requirements only; a decomposed requirement counts as designed or
+//! verified when all of its derived children are. A requirement counts as
+//! traced when it or one of the requirements it derives from is traced from a
+//! use case.
+
+use crate::model_graph::ModelGraph;
+use crate::types::{Element, LinkKind};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Coverage of a single requirement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementCoverage {
+    pub id: String,
+    pub title: String,
+    /// `tags.type`, "unspecified" if absent
+    pub req_type: String,
+    /// `tags.safety` (or `tags.asil`), "unspecified" if absent
+    pub safety: String,
+    /// Feature the requirement (or its root requirement) belongs to
+    pub feature: Option<String>,
+    /// Requirement this one derives from
+    pub derives_from: Option<String>,
+    /// Requirements derived from this one
+    pub derived: Vec<String>,
+    pub allocated_by: Vec<String>,
+    pub satisfied_by: Vec<String>,
+    pub verified_by: Vec<String>,
+    pub traced_from: Vec<String>,
+    pub leaf: bool,
+    /// Allocated or satisfied (for decomposed requirements: all children designed)
+    pub designed: bool,
+    /// Verified by a test case (for decomposed requirements: all children verified)
+    pub verified: bool,
+    /// Traced from a use case, directly or through an ancestor requirement
+    pub traced: bool,
+    /// Designed, verified and traced
+    pub covered: bool,
+    /// Missing aspects: "design", "verification", "trace"
+    pub missing: Vec<String>,
+}
+
+/// Coverage counts for a group of requirements
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageAggregate {
+    pub total: usize,
+    pub designed: usize,
+    pub verified: usize,
+    pub traced: usize,
+    pub covered: usize,
+    /// Covered requirements in percent (100 for an empty group)
+    pub percent: f64,
+}
+
+impl CoverageAggregate {
+    fn add(&mut self, req: &RequirementCoverage) {
+        self.total += 1;
+        self.designed += req.designed as usize;
+        self.verified += req.verified as usize;
+        self.traced += req.traced as usize;
+        self.covered += req.covered as usize;
+        self.percent = 100.0 * self.covered as f64 / self.total as f64;
+    }
+}
+
+/// Requirements missing each coverage aspect
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UncoveredItems {
+    pub undesigned: Vec<String>,
+    pub unverified: Vec<String>,
+    pub untraced: Vec<String>,
+}
+
+/// Full coverage report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// Per-requirement coverage, sorted by ID
+    pub requirements: Vec<RequirementCoverage>,
+    pub totals: CoverageAggregate,
+    pub by_feature: BTreeMap<String, CoverageAggregate>,
+    pub by_type: BTreeMap<String, CoverageAggregate>,
+    pub by_safety: BTreeMap<String, CoverageAggregate>,
+    pub uncovered: UncoveredItems,
+}
+
+/// Safety level from `tags.safety`, falling back to `tags.asil` ("C" becomes "ASIL-C")
+fn safety_level(element: &Element) -> Option<String> {
+    let value = element.safety_tag()?;
+    Some(match value {
+        "A" | "B" | "C" | "D" => format!("ASIL-{}", value),
+        _ => value.to_string(),
+    })
+}
+
+/// Sources of incoming links of a kind, restricted to element types, sorted by ID
+fn incoming(graph: &ModelGraph, node: NodeIndex, kind: LinkKind, types: &[&str]) -> Vec<String> {
+    let mut ids: Vec<String> = graph
+        .edges_directed(node, Direction::Incoming)
+        .filter(|e| e.weight() == kind.as_str())
+        .map(|e| &graph[e.source()])
+        .filter(|(_, element)| types.is_empty() || types.contains(&element.type_name()))
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// First outgoing link target of a kind
+fn outgoing(graph: &ModelGraph, node: NodeIndex, kind: LinkKind) -> Option<NodeIndex> {
+    graph
+        .edges_directed(node, Direction::Outgoing)
+        .filter(|e| e.weight() == kind.as_str())
+        .map(|e| e.target())
+        .min()
+}
+
+/// Compute the coverage report for all requirements in the graph
+pub fn coverage_report(graph: &ModelGraph) -> CoverageReport {
+    let req_nodes: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|&n| matches!(graph[n].1, Element::Req(_)))
+        .collect();
+
+    // Direct coverage
+    let mut coverage: HashMap<NodeIndex, RequirementCoverage> = HashMap::new();
+    for &node in &req_nodes {
+        let (id, element) = &graph[node];
+        let derived = incoming(graph, node, LinkKind::DerivesFrom, &["req"]);
+        let allocated_by = incoming(graph, node, LinkKind::Allocate, &[]);
+        let satisfied_by = incoming(graph, node, LinkKind::Satisfy, &[]);
+        let verified_by = incoming(graph, node, LinkKind::Verify, &["test_case"]);
+        let traced_from = incoming(graph, node, LinkKind::Trace, &["use_case"]);
+        let parent = outgoing(graph, node, LinkKind::DerivesFrom);
+
+        coverage.insert(
+            node,
+            RequirementCoverage {
+                id: id.clone(),
+                title: element.title().to_string(),
+                req_type: element
+                    .as_req()
+                    .and_then(|r| r.req_type())
+                    .unwrap_or_else(|| "unspecified".to_string()),
+                safety: safety_level(element).unwrap_or_else(|| "unspecified".to_string()),
+                feature: None,
+                derives_from: parent.map(|p| graph[p].0.clone()),
+                leaf: derived.is_empty(),
+                designed: !allocated_by.is_empty() || !satisfied_by.is_empty(),
+                verified: !verified_by.is_empty(),
+                traced: !traced_from.is_empty(),
+                covered: false,
+                derived,
+                allocated_by,
+                satisfied_by,
+                verified_by,
+                traced_from,
+                missing: Vec::new(),
+            },
+        );
+    }
+
+    // Roll design/verification up from leaves, trace and feature down from ancestors
+    let mut rolled_up: HashMap<NodeIndex, (bool, bool)> = HashMap::new();
+    for &node in &req_nodes {
+        roll_up(graph, node, &coverage, &mut rolled_up, &mut Vec::new());
+    }
+    let inherited: HashMap<NodeIndex, (bool, Option<String>)> = req_nodes
+        .iter()
+        .map(|&node| (node, inherit_down(graph, node, &coverage)))
+        .collect();
+
+    let mut report = CoverageReport::default();
+    for node in req_nodes {
+        let mut req = coverage.remove(&node).unwrap();
+        let (designed, verified) = rolled_up[&node];
+        let (traced, feature) = inherited[&node].clone();
+        req.designed = designed;
+        req.verified = verified;
+        req.traced = traced;
+        req.feature = feature;
+        for (ok, aspect) in [(designed, "design"), (verified, "verification"), (traced, "trace")] {
+            if !ok {
+                req.missing.push(aspect.to_string());
+            }
+        }
+        req.covered = req.missing.is_empty();
+
+        report.totals.add(&req);
+        report
+            .by_feature
+            .entry(req.feature.clone().unwrap_or_else(|| "unassigned".to_string()))
+            .or_default()
+            .add(&req);
+        report.by_type.entry(req.req_type.clone()).or_default().add(&req);
+        report.by_safety.entry(req.safety.clone()).or_default().add(&req);
+        if !designed {
+            report.uncovered.undesigned.push(req.id.clone());
+        }
+        if !verified {
+            report.uncovered.unverified.push(req.id.clone());
+        }
+        if !traced {
+            report.uncovered.untraced.push(req.id.clone());
+        }
+        report.requirements.push(req);
+    }
+    if report.totals.total == 0 {
+        report.totals.percent = 100.0;
+    }
+
+    report
+}
+
+/// Effective (designed, verified) of a requirement; decomposed requirements take the
+/// conjunction over their children. `stack` guards against derives_from cycles.
+fn roll_up(
+    graph: &ModelGraph,
+    node: NodeIndex,
+    coverage: &HashMap<NodeIndex, RequirementCoverage>,
+    memo: &mut HashMap<NodeIndex, (bool, bool)>,
+    stack: &mut Vec<NodeIndex>,
+) -> (bool, bool) {
+    if let Some(&result) = memo.get(&node) {
+        return result;
+    }
+    if stack.contains(&node) {
+        return (false, false);
+    }
+    let req = &coverage[&node];
+    let result = if req.leaf {
+        (req.designed, req.verified)
+    } else {
+        stack.push(node);
+        let children: Vec<NodeIndex> = graph
+            .edges_directed(node, Direction::Incoming)
+            .filter(|e| e.weight() == LinkKind::DerivesFrom.as_str() && coverage.contains_key(&e.source()))
+            .map(|e| e.source())
+            .collect();
+        let mut result = (true, true);
+        for child in children {
+            let (designed, verified) = roll_up(graph, child, coverage, memo, stack);
+            result = (result.0 && designed, result.1 && verified);
+        }
+        stack.pop();
+        result
+    };
+    memo.insert(node, result);
+    result
+}
+
+/// Effective trace status and owning feature, walking up the derives_from chain
+fn inherit_down(
+    graph: &ModelGraph,
+    node: NodeIndex,
+    coverage: &HashMap<NodeIndex, RequirementCoverage>,
+) -> (bool, Option<String>) {
+    let mut traced = false;
+    let mut feature = None;
+    let mut current = Some(node);
+    let mut visited = Vec::new();
+    while let Some(n) = current.filter(|n| !visited.contains(n)) {
+        visited.push(n);
+        traced |= coverage.get(&n).is_some_and(|r| r.traced);
+        if feature.is_none() {
+            feature = outgoing(graph, n, LinkKind::BelongsTo)
+                .filter(|&f| matches!(graph[f].1, Element::Feature(_)))
+                .map(|f| graph[f].0.clone());
+        }
+        current = outgoing(graph, n, LinkKind::DerivesFrom);
+    }
+    (traced, feature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::graph_from_json;
+    use serde_json::json;
+
+    #[test]
+    fn test_leaf_coverage_rolls_up_to_decomposed_requirements() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH"},
+                "R-1": {"type": "req", "id": "R-1", "tags": {"type": "functional", "safety": "ASIL-B"}},
+                "R-1.1": {"type": "req", "id": "R-1.1", "tags": {"type": "functional", "asil": "B"}},
+                "R-1.2": {"type": "req", "id": "R-1.2", "tags": {"type": "safety"}},
+                "R-2": {"type": "req", "id": "R-2"},
+                "BLK-1": {"type": "block_definition", "id": "BLK-1"},
+                "UC-1": {"type": "use_case", "id": "UC-1"},
+                "TC-1": {"type": "test_case", "id": "TC-1"}
+            }),
+            json!([
+                {"source": "R-1", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "R-2", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "R-1.1", "type": "derives_from", "target": "R-1"},
+                {"source": "R-1.2", "type": "derives_from", "target": "R-1"},
+                {"source": "UC-1", "type": "trace", "target": "R-1"},
+                {"source": "BLK-1", "type": "allocate", "target": "R-1.1"},
+                {"source": "BLK-1", "type": "satisfy", "target": "R-1.2"},
+                {"source": "TC-1", "type": "verify", "target": "R-1.1"}
+            ]),
+        );
+        let report = coverage_report(&graph);
+        let by_id: HashMap<&str, &RequirementCoverage> =
+            report.requirements.iter().map(|r| (r.id.as_str(), r)).collect();
+
+        let r1 = by_id["R-1"];
+        assert!(!r1.leaf);
+        assert_eq!(r1.derived, vec!["R-1.1", "R-1.2"]);
+        assert!(r1.designed);
+        assert!(!r1.verified);
+        assert_eq!(r1.missing, vec!["verification"]);
+
+        let r11 = by_id["R-1.1"];
+        assert!(r11.covered);
+        assert_eq!(r11.feature.as_deref(), Some("F-AUTH"));
+        assert_eq!(r11.safety, "ASIL-B");
+        assert_eq!(r11.verified_by, vec!["TC-1"]);
+
+        assert_eq!(by_id["R-2"].missing, vec!["design", "verification", "trace"]);
+    }
+
+    #[test]
+    fn test_aggregates_and_uncovered_lists() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH"},
+                "R-1": {"type": "req", "id": "R-1", "tags": {"type": "functional", "safety": "ASIL-B"}},
+                "R-1.1": {"type": "req", "id": "R-1.1", "tags": {"type": "functional", "asil": "B"}},
+                "R-1.2": {"type": "req", "id": "R-1.2", "tags": {"type": "safety"}},
+                "R-2": {"type": "req", "id": "R-2"},
+                "BLK-1": {"type": "block_definition", "id": "BLK-1"},
+                "UC-1": {"type": "use_case", "id": "UC-1"},
+                "TC-1": {"type": "test_case", "id": "TC-1"}
+            }),
+            json!([
+                {"source": "R-1", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "R-2", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "R-1.1", "type": "derives_from", "target": "R-1"},
+                {"source": "R-1.2", "type": "derives_from", "target": "R-1"},
+                {"source": "UC-1", "type": "trace", "target": "R-1"},
+                {"source": "BLK-1", "type": "allocate", "target": "R-1.1"},
+                {"source": "BLK-1", "type": "satisfy", "target": "R-1.2"},
+                {"source": "TC-1", "type": "verify", "target": "R-1.1"}
+            ]),
+        );
+        let report = coverage_report(&graph);
+        assert_eq!(report.totals.total, 4);
+        assert_eq!(report.totals.covered, 1);
+        assert_eq!(report.by_feature["F-AUTH"].total, 4);
+        assert_eq!(report.by_type["functional"].total, 2);
+        assert_eq!(report.by_type["unspecified"].total, 1);
+        assert_eq!(report.by_safety["ASIL-B"].covered, 1);
+        assert_eq!(report.uncovered.unverified, vec!["R-1", "R-1.2", "R-2"]);
+        assert_eq!(report.uncovered.untraced, vec!["R-2"]);
+    }
+}
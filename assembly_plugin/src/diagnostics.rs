@@ -0,0 +1,217 @@
+//! Structured Validation Diagnostics
+//!
+//! Every validation module reports problems as `Diagnostic` values carrying a
+//! stable rule code, a severity and the IDs of the elements involved, so that
+//! results can be filtered, counted and rendered per element on the Typst side.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Diagnostic severity, ordered from most to least severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A rule code with its default severity and a short title
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleInfo {
+    pub code: &'static str,
+    pub severity: Severity,
+    pub title: &'static str,
+}
+
+const fn rule(code: &'static str, severity: Severity, title: &'static str) -> RuleInfo {
+    RuleInfo { code, severity, title }
+}
+
+/// All known rule codes
+///
+/// Codes are stable: never renumber or reuse a code, only append new ones.
+/// AL0xx are traceability/structure rules (`validate_rules`), AL1xx parameter
+/// binding rules, AL2xx feature model rules.
+pub const RULES: &[RuleInfo] = &[
+    rule("AL001", Severity::Error, "Dangling link"),
+    rule("AL002", Severity::Error, "Use case not traced to a requirement"),
+    rule("AL003", Severity::Error, "Duplicate element ID"),
+    rule("AL004", Severity::Error, "Registry key mismatch"),
+    rule("AL005", Severity::Error, "Feature parent does not exist"),
+    rule("AL006", Severity::Error, "Variability group with fewer than two children"),
+    rule("AL007", Severity::Error, "Block part type does not exist"),
+    rule("AL008", Severity::Error, "Configuration selects abstract feature"),
+    rule("AL009", Severity::Error, "Configuration selects non-feature element"),
+    rule("AL010", Severity::Error, "Configuration selects non-existent feature"),
+    rule("AL011", Severity::Error, "Unknown link type"),
+    rule("AL012", Severity::Error, "Link source type not allowed"),
+    rule("AL013", Severity::Error, "Link target type not allowed"),
+    rule("AL014", Severity::Error, "Decomposed requirement has other incoming links"),
+    rule("AL015", Severity::Error, "Requirement without parent"),
+    rule("AL016", Severity::Error, "Requirement with several parents"),
+    rule("AL017", Severity::Error, "Feature model does not have exactly one root"),
+    rule("AL018", Severity::Error, "Feature parent cycle"),
+    rule("AL019", Severity::Error, "Requirement derives_from cycle"),
+    rule("AL020", Severity::Warning, "Unknown rule code in rule configuration"),
+    rule("AL021", Severity::Error, "Suppression without justification"),
+    rule("AL022", Severity::Info, "Suppression did not match any finding"),
+    rule("AL023", Severity::Error, "User rule cannot be parsed"),
+    rule("AL024", Severity::Error, "Required tag missing"),
+    rule("AL025", Severity::Error, "Tag value has wrong type"),
+    rule("AL026", Severity::Error, "Tag value not in allowed values"),
+    rule("AL027", Severity::Error, "Tag value does not match pattern"),
+    rule("AL028", Severity::Warning, "Unknown or misspelled tag"),
+    rule("AL029", Severity::Error, "Tag schema is invalid"),
+    rule("AL030", Severity::Error, "Derived requirement has lower ASIL than its parent"),
+    rule("AL031", Severity::Error, "ASIL decomposition marker does not match parent"),
+    rule("AL032", Severity::Error, "Invalid ASIL decomposition"),
+    rule("AL033", Severity::Error, "Requirement ASIL exceeds block ASIL"),
+    rule("AL034", Severity::Warning, "Unrecognised ASIL value"),
+    rule("AL035", Severity::Error, "Mixed-criticality flow without safety mechanism"),
+    rule("AL101", Severity::Error, "Configuration not found"),
+    rule("AL102", Severity::Error, "Parameter has no binding and no default"),
+    rule("AL103", Severity::Error, "Parameter value has wrong type"),
+    rule("AL104", Severity::Error, "Parameter value out of range"),
+    rule("AL105", Severity::Error, "Parameter value not in enum"),
+    rule("AL106", Severity::Error, "Unknown parameter type"),
+    rule("AL107", Severity::Error, "Constraint cannot be parsed"),
+    rule("AL108", Severity::Error, "Constraint violated"),
+    rule("AL109", Severity::Error, "Constraint cannot be evaluated"),
+    rule("AL201", Severity::Error, "Root feature not found"),
+    rule("AL202", Severity::Error, "Feature model is inconsistent"),
+    rule("AL203", Severity::Error, "Configuration violates feature model"),
+];
+
+/// Look up a rule by code
+pub fn rule_info(code: &str) -> Option<&'static RuleInfo> {
+    RULES.iter().find(|r| r.code == code)
+}
+
+/// A single validation finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Stable rule code, e.g. "AL001"
+    pub code: String,
+    pub severity: Severity,
+    /// Element the finding is reported against (None for model-wide findings)
+    pub element_id: Option<String>,
+    /// Other elements involved (link endpoints, cycle members, children, ...)
+    #[serde(default)]
+    pub related_ids: Vec<String>,
+    pub message: String,
+    /// Suggested fix
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic with the rule's default severity
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            code: code.to_string(),
+            severity: rule_info(code).map(|r| r.severity).unwrap_or(Severity::Error),
+            element_id: None,
+            related_ids: Vec::new(),
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// Set the element the diagnostic is reported against
+    pub fn on(mut self, element_id: impl Into<String>) -> Self {
+        self.element_id = Some(element_id.into());
+        self
+    }
+
+    /// Add related element IDs
+    pub fn related<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.related_ids.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Attach a suggested fix
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+/// Sort diagnostics deterministically (severity, code, element, message) and drop duplicates
+pub fn sort_diagnostics(diagnostics: &mut Vec<Diagnostic>) {
+    for diagnostic in diagnostics.iter_mut() {
+        diagnostic.related_ids.sort();
+        diagnostic.related_ids.dedup();
+    }
+    diagnostics.sort_by(|a, b| {
+        (a.severity, &a.code, &a.element_id, &a.message, &a.related_ids)
+            .cmp(&(b.severity, &b.code, &b.element_id, &b.message, &b.related_ids))
+    });
+    diagnostics.dedup();
+}
+
+/// True if any diagnostic has error severity
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(Diagnostic::is_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_codes_are_unique() {
+        let mut codes: Vec<&str> = RULES.iter().map(|r| r.code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), RULES.len());
+        assert_eq!(rule_info("AL001").unwrap().title, "Dangling link");
+    }
+
+    #[test]
+    fn test_sort_is_deterministic_and_deduplicates() {
+        let mut a = vec![
+            Diagnostic::new("AL005", "b").on("F-2"),
+            Diagnostic::new("AL001", "a").on("R-1").related(["Z", "A"]),
+            Diagnostic::new("AL005", "a").on("F-1"),
+            Diagnostic::new("AL005", "a").on("F-1"),
+        ];
+        let mut b: Vec<Diagnostic> = a.iter().rev().cloned().collect();
+        sort_diagnostics(&mut a);
+        sort_diagnostics(&mut b);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a[0].code, "AL001");
+        assert_eq!(a[0].related_ids, vec!["A", "Z"]);
+        assert_eq!(a[1].element_id.as_deref(), Some("F-1"));
+    }
+}
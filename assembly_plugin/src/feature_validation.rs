@@ -1,10 +1,11 @@
-/// Feature Model SAT-based Validation
-///
-/// This module validates feature models for consistency using a SAT solver.
-/// It encodes the feature model constraints (hierarchy, variability groups,
-/// cross-tree constraints) as CNF and checks if there exists at least one
-/// valid configuration.
-
+//! Feature Model SAT-based Validation
+//!
+//! This module validates feature models for consistency using a SAT solver.
+//! It encodes the feature model constraints (hierarchy, variability groups,
+//! cross-tree constraints) as CNF and checks if there exists at least one
+//! valid configuration.
+
+use crate::diagnostics::Diagnostic;
 use crate::sat_solver::{is_sat, Clause, CNF};
 use crate::types::{Element, FeatureElement, VariabilityGroup};
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ pub struct FeatureModelValidation {
     pub message: String,
     pub num_features: usize,
     pub num_clauses: usize,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 /// CNF Encoder for feature models
@@ -210,26 +212,24 @@ trait FeatureMandatory {
 impl FeatureMandatory for FeatureElement {
     fn is_mandatory(&self, all_features: &HashMap<String, &FeatureElement>) -> bool {
         // Check if feature has "mandatory: true" in tags
-        if let Some(mandatory_val) = self.tags.get("mandatory") {
-            if let Some(b) = mandatory_val.as_bool() {
-                return b;
-            }
+        if let Some(b) = self.tags.get("mandatory").and_then(|v| v.as_bool()) {
+            return b;
         }
 
         // Check if parent has a variability group
-        if let Some(parent_id) = &self.parent {
-            if let Some(&parent) = all_features.get(parent_id) {
-                // If parent has a group, children are not mandatory by default
-                if parent.is_variability_group() {
-                    return false;
-                }
-                // Otherwise, check if this is the only child (implicit mandatory)
-                let sibling_count = all_features
-                    .values()
-                    .filter(|f| f.parent.as_deref() == Some(parent_id.as_str()))
-                    .count();
-                return sibling_count == 1;
+        if let Some(parent_id) = &self.parent
+            && let Some(&parent) = all_features.get(parent_id)
+        {
+            // If parent has a group, children are not mandatory by default
+            if parent.is_variability_group() {
+                return false;
             }
+            // Otherwise, check if this is the only child (implicit mandatory)
+            let sibling_count = all_features
+                .values()
+                .filter(|f| f.parent.as_deref() == Some(parent_id.as_str()))
+                .count();
+            return sibling_count == 1;
         }
 
         false
@@ -257,14 +257,17 @@ pub fn validate_feature_model(
             message: "No features to validate".to_string(),
             num_features: 0,
             num_clauses: 0,
+            diagnostics: Vec::new(),
         };
     }
 
     // Check if root exists
     if !features.contains_key(root_id) {
+        let message = format!("Root feature '{}' not found in registry", root_id);
         return FeatureModelValidation {
             is_consistent: false,
-            message: format!("Root feature '{}' not found in registry", root_id),
+            diagnostics: vec![Diagnostic::new("AL201", message.clone()).on(root_id)],
+            message,
             num_features: features.len(),
             num_clauses: 0,
         };
@@ -282,8 +285,20 @@ pub fn validate_feature_model(
         is_sat(&cnf, num_vars)
     };
 
+    let diagnostics = if is_consistent {
+        Vec::new()
+    } else {
+        vec![Diagnostic::new(
+            "AL202",
+            "Feature model is INCONSISTENT - no valid configuration exists",
+        )
+        .on(root_id)
+        .hint("Review requires/excludes constraints, mandatory features and XOR/OR groups for conflicts")]
+    };
+
     FeatureModelValidation {
         is_consistent,
+        diagnostics,
         message: if is_consistent {
             format!(
                 "Feature model is CONSISTENT ({} features, {} variables, {} clauses)",
@@ -306,12 +321,12 @@ pub fn validate_feature_model(
 
 /// Validate a specific configuration against the feature model
 ///
-/// Returns true if the configuration is valid (respects all constraints)
+/// `is_consistent` is true if the configuration is valid (respects all constraints)
 pub fn validate_configuration(
     registry: &HashMap<String, Element>,
     root_id: &str,
     selected_features: &[String],
-) -> (bool, String) {
+) -> FeatureModelValidation {
     // Extract all features
     let mut features: HashMap<String, &FeatureElement> = HashMap::new();
     for element in registry.values() {
@@ -348,7 +363,22 @@ pub fn validate_configuration(
         )
     };
 
-    (is_valid, message)
+    let mut diagnostics = Vec::new();
+    if !is_valid {
+        diagnostics.push(
+            Diagnostic::new("AL203", message.clone())
+                .related(selected_features.iter().cloned())
+                .hint("Check for missing required features, excluded pairs, XOR/OR group rules and selected features without their parent"),
+        );
+    }
+
+    FeatureModelValidation {
+        is_consistent: is_valid,
+        message,
+        num_features: features.len(),
+        num_clauses: cnf.len(),
+        diagnostics,
+    }
 }
 
 #[cfg(test)]
@@ -464,5 +494,8 @@ mod tests {
         // This should be inconsistent: F1 is mandatory (selected with ROOT),
         // F1 requires F2, but F2 excludes F1
         assert!(!result.is_consistent);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].code, "AL202");
+        assert_eq!(result.diagnostics[0].element_id.as_deref(), Some("ROOT"));
     }
 }
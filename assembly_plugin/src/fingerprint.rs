@@ -0,0 +1,278 @@
+//! Content Fingerprints and Suspect Links
+//!
+//! Every element gets a stable content hash over its title, tags and body.
+//! The body is normalised first (object keys sorted, runs of whitespace in
+//! strings collapsed) so that re-rendering the same document yields the same
+//! hash. A link baseline records, per reviewed link, the hash of the link
+//! target at review time; a link whose target hash has changed since then is
+//! suspect until it is reviewed again and the baseline is refreshed.
+
+use crate::types::LinkKind;
+use crate::{Link, Registry};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Baseline format version
+pub const BASELINE_VERSION: u32 = 1;
+
+/// Link kinds tracked by default: design and verification links
+pub fn default_tracked_kinds() -> Vec<LinkKind> {
+    vec![LinkKind::Satisfy, LinkKind::Verify]
+}
+
+/// Reviewed state of one link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub link_type: String,
+    pub target: String,
+    /// Content hash of the target when the link was reviewed
+    pub target_hash: String,
+}
+
+/// Link baseline, stored as JSON next to the document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkBaseline {
+    pub version: u32,
+    pub links: Vec<BaselineEntry>,
+}
+
+/// Why a link needs review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuspectReason {
+    /// Target content changed since the link was reviewed
+    Changed,
+    /// Link is not in the baseline
+    Unreviewed,
+}
+
+/// A link that needs review
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspectLink {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub link_type: String,
+    pub target: String,
+    pub reason: SuspectReason,
+    /// Target hash recorded in the baseline (None for unreviewed links)
+    pub baseline_hash: Option<String>,
+    pub current_hash: String,
+}
+
+/// Result of comparing the model against a baseline
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspectReport {
+    /// Number of tracked links checked
+    pub checked: usize,
+    pub suspect: Vec<SuspectLink>,
+    /// Baseline entries whose link no longer exists
+    pub removed: Vec<BaselineEntry>,
+}
+
+/// Stable content hashes of all elements, keyed by registry key
+pub fn fingerprint_registry(registry: &Registry) -> BTreeMap<String, String> {
+    registry
+        .iter()
+        .map(|(key, element)| {
+            let content = serde_json::json!({
+                "title": element.title(),
+                "tags": element.tags(),
+                "body": element.body(),
+            });
+            (key.clone(), content_hash(&content))
+        })
+        .collect()
+}
+
+/// Hash of a JSON value after normalisation, as 16 hex digits (FNV-1a, 64 bit)
+pub fn content_hash(value: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in canonical.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Canonical JSON: sorted object keys, whitespace in strings collapsed
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::String(s) => {
+            let normalized = s.split_whitespace().collect::<Vec<_>>().join(" ");
+            out.push_str(&Value::String(normalized).to_string());
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn tracked<'a>(links: &'a [Link], kinds: &'a [LinkKind]) -> impl Iterator<Item = &'a Link> {
+    links
+        .iter()
+        .filter(move |l| l.kind().is_some_and(|k| kinds.contains(&k)))
+}
+
+/// Record the current target hash of every tracked link (marks all links as reviewed)
+pub fn create_baseline(registry: &Registry, links: &[Link], kinds: &[LinkKind]) -> LinkBaseline {
+    let hashes = fingerprint_registry(registry);
+    let mut entries: Vec<BaselineEntry> = tracked(links, kinds)
+        .filter_map(|link| {
+            hashes.get(&link.target).map(|hash| BaselineEntry {
+                source: link.source.clone(),
+                link_type: link.link_type.clone(),
+                target: link.target.clone(),
+                target_hash: hash.clone(),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| (&a.source, &a.link_type, &a.target).cmp(&(&b.source, &b.link_type, &b.target)));
+    entries.dedup();
+
+    LinkBaseline { version: BASELINE_VERSION, links: entries }
+}
+
+/// Compare tracked links against a baseline
+///
+/// Links with a non-existent target are skipped; they are reported by the
+/// dangling link rule instead.
+pub fn detect_suspect_links(
+    registry: &Registry,
+    links: &[Link],
+    baseline: &LinkBaseline,
+    kinds: &[LinkKind],
+) -> SuspectReport {
+    let hashes = fingerprint_registry(registry);
+    let reviewed: HashMap<(&str, &str, &str), &BaselineEntry> = baseline
+        .links
+        .iter()
+        .map(|e| ((e.source.as_str(), e.link_type.as_str(), e.target.as_str()), e))
+        .collect();
+
+    let mut report = SuspectReport::default();
+    let mut present = HashSet::new();
+    for link in tracked(links, kinds) {
+        let Some(current_hash) = hashes.get(&link.target) else {
+            continue;
+        };
+        report.checked += 1;
+        let key = (link.source.as_str(), link.link_type.as_str(), link.target.as_str());
+        present.insert(key);
+        let (reason, baseline_hash) = match reviewed.get(&key) {
+            Some(entry) if &entry.target_hash == current_hash => continue,
+            Some(entry) => (SuspectReason::Changed, Some(entry.target_hash.clone())),
+            None => (SuspectReason::Unreviewed, None),
+        };
+        report.suspect.push(SuspectLink {
+            source: link.source.clone(),
+            link_type: link.link_type.clone(),
+            target: link.target.clone(),
+            reason,
+            baseline_hash,
+            current_hash: current_hash.clone(),
+        });
+    }
+
+    report.removed = baseline
+        .links
+        .iter()
+        .filter(|e| !present.contains(&(e.source.as_str(), e.link_type.as_str(), e.target.as_str())))
+        .cloned()
+        .collect();
+    report
+        .suspect
+        .sort_by(|a, b| (&a.target, &a.source, &a.link_type).cmp(&(&b.target, &b.source, &b.link_type)));
+    report.suspect.dedup();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hash_ignores_key_order_and_whitespace() {
+        let a = json!({"text": "The  user\nshall log in", "n": 1});
+        let b = json!({"n": 1, "text": "The user shall log in"});
+        assert_eq!(content_hash(&a), content_hash(&b));
+        assert_ne!(content_hash(&a), content_hash(&json!({"text": "The user shall log out", "n": 1})));
+        assert_eq!(content_hash(&a).len(), 16);
+    }
+
+    #[test]
+    fn test_changed_target_makes_links_suspect() {
+        let kinds = default_tracked_kinds();
+        let mut registry: Registry = serde_json::from_value(json!({
+            "REQ-AUTH-001": {"type": "req", "id": "REQ-AUTH-001", "title": "Login",
+                             "tags": {"safety": "QM"}, "body": {"text": "The user shall log in"}},
+            "REQ-AUTH-002": {"type": "req", "id": "REQ-AUTH-002", "title": "Logout"},
+            "BLK-AUTH": {"type": "block_definition", "id": "BLK-AUTH"},
+            "TC-AUTH": {"type": "test_case", "id": "TC-AUTH"}
+        }))
+        .unwrap();
+        let mut links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "BLK-AUTH", "type": "satisfy", "target": "REQ-AUTH-001"},
+            {"source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-001"},
+            {"source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-002"}
+        ]))
+        .unwrap();
+        let baseline = create_baseline(&registry, &links, &kinds);
+        assert_eq!(baseline.links.len(), 3);
+
+        let unchanged = detect_suspect_links(&registry, &links, &baseline, &kinds);
+        assert_eq!(unchanged.checked, 3);
+        assert!(unchanged.suspect.is_empty());
+
+        let changed = json!({"type": "req", "id": "REQ-AUTH-001", "title": "Login",
+                             "tags": {"safety": "QM"}, "body": {"text": "The user shall log in with 2FA"}});
+        registry.insert("REQ-AUTH-001".to_string(), serde_json::from_value(changed).unwrap());
+        links.retain(|l| l.target != "REQ-AUTH-002");
+        links.push(serde_json::from_value(json!({"source": "BLK-AUTH", "type": "satisfy", "target": "REQ-AUTH-002"})).unwrap());
+        let report = detect_suspect_links(&registry, &links, &baseline, &kinds);
+
+        let suspect: Vec<(&str, &str, SuspectReason)> = report
+            .suspect
+            .iter()
+            .map(|s| (s.source.as_str(), s.target.as_str(), s.reason))
+            .collect();
+        assert_eq!(
+            suspect,
+            vec![
+                ("BLK-AUTH", "REQ-AUTH-001", SuspectReason::Changed),
+                ("TC-AUTH", "REQ-AUTH-001", SuspectReason::Changed),
+                ("BLK-AUTH", "REQ-AUTH-002", SuspectReason::Unreviewed),
+            ]
+        );
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].source, "TC-AUTH");
+    }
+}
@@ -0,0 +1,217 @@
+//! Graph Export
+//!
+//! Writes the element/link graph as GraphML (yEd, Gephi) or Graphviz DOT.
+//!
+//! Nodes carry the element type, title and tags (strings as is, other values as
+//! JSON text); edges carry their link kind. In DOT, tags are written as
+//! `tag_<key>` attributes so keys like `label` or `color` do not change the
+//! rendering. Configuration filtering is done on the model before the graph is
+//! built (see `variant_filter`).
+
+use crate::model_graph::ModelGraph;
+use crate::xml::escape;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Which parts of the graph to export (empty lists export everything)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphExportOptions {
+    /// Element types to keep (`req`, `feature`, ...)
+    #[serde(default)]
+    pub element_types: Vec<String>,
+    /// Link kinds to keep (`satisfy`, `verify`, ...)
+    #[serde(default)]
+    pub link_kinds: Vec<String>,
+}
+
+/// Exported subgraph: nodes in ID order and edges (source, kind, target) between them
+struct Selection<'a> {
+    nodes: Vec<NodeIndex>,
+    edges: Vec<(&'a str, &'a str, &'a str)>,
+}
+
+fn select<'a>(graph: &'a ModelGraph, options: &GraphExportOptions) -> Selection<'a> {
+    let mut nodes: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|n| options.element_types.is_empty() || options.element_types.iter().any(|t| t == graph[*n].1.type_name()))
+        .collect();
+    nodes.sort_by(|a, b| graph[*a].0.cmp(&graph[*b].0));
+    let included: BTreeSet<NodeIndex> = nodes.iter().copied().collect();
+
+    let mut edges: Vec<(&str, &str, &str)> = graph
+        .edge_references()
+        .filter(|e| included.contains(&e.source()) && included.contains(&e.target()))
+        .filter(|e| options.link_kinds.is_empty() || options.link_kinds.contains(e.weight()))
+        .map(|e| (graph[e.source()].0.as_str(), e.weight().as_str(), graph[e.target()].0.as_str()))
+        .collect();
+    edges.sort();
+    edges.dedup();
+
+    Selection { nodes, edges }
+}
+
+fn tag_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// GraphML document of the (filtered) graph
+pub fn to_graphml(graph: &ModelGraph, options: &GraphExportOptions) -> String {
+    let selection = select(graph, options);
+    let tag_keys: BTreeSet<&str> = selection
+        .nodes
+        .iter()
+        .flat_map(|n| graph[*n].1.tags().keys().map(String::as_str))
+        .collect();
+    let tag_ids: BTreeMap<&str, String> = tag_keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (*key, format!("tag{}", i)))
+        .collect();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n");
+    for (key, id) in &tag_ids {
+        out.push_str(&format!(
+            "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+            id,
+            escape(key)
+        ));
+    }
+    out.push_str("  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n");
+    out.push_str("  <graph id=\"model\" edgedefault=\"directed\">\n");
+
+    for node in &selection.nodes {
+        let (id, element) = &graph[*node];
+        out.push_str(&format!("    <node id=\"{}\">\n", escape(id)));
+        out.push_str(&format!("      <data key=\"type\">{}</data>\n", element.type_name()));
+        out.push_str(&format!("      <data key=\"title\">{}</data>\n", escape(element.title())));
+        let tags: BTreeMap<&String, &serde_json::Value> = element.tags().iter().collect();
+        for (key, value) in tags {
+            out.push_str(&format!(
+                "      <data key=\"{}\">{}</data>\n",
+                tag_ids[key.as_str()],
+                escape(&tag_text(value))
+            ));
+        }
+        out.push_str("    </node>\n");
+    }
+    for (source, kind, target) in &selection.edges {
+        out.push_str(&format!(
+            "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"kind\">{}</data>\n    </edge>\n",
+            escape(source),
+            escape(target),
+            escape(kind)
+        ));
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+/// Quoted DOT identifier
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Graphviz DOT digraph of the (filtered) graph
+pub fn to_dot(graph: &ModelGraph, options: &GraphExportOptions) -> String {
+    let selection = select(graph, options);
+    let mut out = String::from("digraph model {\n  rankdir=BT;\n  node [shape=box];\n");
+
+    for node in &selection.nodes {
+        let (id, element) = &graph[*node];
+        let label = if element.title().is_empty() {
+            id.clone()
+        } else {
+            format!("{}\n{}", id, element.title())
+        };
+        let mut attributes = vec![
+            format!("label={}", dot_quote(&label)),
+            format!("type={}", dot_quote(element.type_name())),
+            format!("title={}", dot_quote(element.title())),
+        ];
+        let tags: BTreeMap<&String, &serde_json::Value> = element.tags().iter().collect();
+        for (key, value) in tags {
+            attributes.push(format!("{}={}", dot_quote(&format!("tag_{}", key)), dot_quote(&tag_text(value))));
+        }
+        out.push_str(&format!("  {} [{}];\n", dot_quote(id), attributes.join(", ")));
+    }
+    for (source, kind, target) in &selection.edges {
+        out.push_str(&format!(
+            "  {} -> {} [label={}, kind={}];\n",
+            dot_quote(source),
+            dot_quote(target),
+            dot_quote(kind),
+            dot_quote(kind)
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::graph_from_json;
+    use crate::xml;
+    use serde_json::json;
+
+    #[test]
+    fn test_graphml_export() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH", "title": "Auth", "parent": null},
+                "REQ-1": {"type": "req", "id": "REQ-1", "title": "Log \"in\" <now>", "parent": "F-AUTH",
+                          "tags": {"safety": "ASIL-B", "variant": ["eu"], "label": "hidden"}},
+                "TC-1": {"type": "test_case", "id": "TC-1", "title": "Login test"}
+            }),
+            json!([
+                {"source": "TC-1", "type": "verify", "target": "REQ-1"}
+            ]),
+        );
+        let text = to_graphml(&graph, &GraphExportOptions::default());
+        let root = xml::parse(&text).unwrap();
+        let graph = root.child("graph").unwrap();
+        assert_eq!(graph.children_named("node").count(), 3);
+        let kinds: Vec<String> = graph.children_named("edge").map(|e| e.text().trim().to_string()).collect();
+        assert_eq!(kinds, vec!["belongs_to", "verify"]);
+        let req = graph.children_named("node").find(|n| n.attr("id") == Some("REQ-1")).unwrap();
+        let data: Vec<String> = req.children_named("data").map(|d| d.text()).collect();
+        assert_eq!(data, vec!["req", "Log \"in\" <now>", "hidden", "ASIL-B", "[\"eu\"]"]);
+    }
+
+    #[test]
+    fn test_dot_export_with_filters() {
+        let options = GraphExportOptions {
+            element_types: vec!["req".to_string(), "test_case".to_string()],
+            link_kinds: vec!["verify".to_string()],
+        };
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH", "title": "Auth", "parent": null},
+                "REQ-1": {"type": "req", "id": "REQ-1", "title": "Log \"in\" <now>", "parent": "F-AUTH",
+                          "tags": {"safety": "ASIL-B", "variant": ["eu"], "label": "hidden"}},
+                "TC-1": {"type": "test_case", "id": "TC-1", "title": "Login test"}
+            }),
+            json!([
+                {"source": "TC-1", "type": "verify", "target": "REQ-1"}
+            ]),
+        );
+        let text = to_dot(&graph, &options);
+        assert!(!text.contains("F-AUTH"));
+        assert!(text.contains("\"REQ-1\" [label=\"REQ-1\\nLog \\\"in\\\" <now>\""));
+        assert!(text.contains("\"tag_safety\"=\"ASIL-B\""));
+        assert!(text.contains("\"tag_label\"=\"hidden\""));
+        assert!(!text.contains("\"label\"="));
+        assert!(text.contains("\"TC-1\" -> \"REQ-1\" [label=\"verify\", kind=\"verify\"];"));
+        assert_eq!(text.matches("->").count(), 1);
+    }
+}
@@ -0,0 +1,351 @@
+//! Change Impact Analysis
+//!
+//! Given a set of changed elements, collects everything that is potentially
+//! affected by following selected link kinds through the model graph. Each
+//! affected element is reported with the shortest path from a changed element
+//! explaining why it was hit.
+//!
+//! Configurations are not connected by links; they are affected when they
+//! select a feature that owns an affected requirement (via `belongs_to` on the
+//! requirement or one of the requirements it derives from) or that is itself
+//! affected.
+
+use crate::model_graph::ModelGraph;
+use crate::types::{Element, LinkKind};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Direction in which a link kind is followed from an affected element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowDirection {
+    /// From link target to link source (e.g. requirement to its verifying test)
+    Incoming,
+    /// From link source to link target
+    Outgoing,
+    Both,
+}
+
+impl FollowDirection {
+    fn directions(&self) -> &'static [Direction] {
+        match self {
+            FollowDirection::Incoming => &[Direction::Incoming],
+            FollowDirection::Outgoing => &[Direction::Outgoing],
+            FollowDirection::Both => &[Direction::Incoming, Direction::Outgoing],
+        }
+    }
+}
+
+/// A link kind to follow and its direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowRule {
+    pub kind: LinkKind,
+    #[serde(default = "default_direction")]
+    pub direction: FollowDirection,
+}
+
+fn default_direction() -> FollowDirection {
+    FollowDirection::Incoming
+}
+
+/// Default rules: derived requirements, allocated blocks, satisfying diagrams
+/// and implementations, and verifying test cases
+pub fn default_follow_rules() -> Vec<FollowRule> {
+    [LinkKind::DerivesFrom, LinkKind::Allocate, LinkKind::Satisfy, LinkKind::Verify]
+        .into_iter()
+        .map(|kind| FollowRule { kind, direction: FollowDirection::Incoming })
+        .collect()
+}
+
+/// One affected element
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactHit {
+    pub id: String,
+    /// Element IDs from the changed element to this element
+    pub path: Vec<String>,
+    /// Link kinds along the path (`selected_by` for the feature-to-configuration step)
+    pub via: Vec<String>,
+}
+
+/// Result of an impact analysis
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactReport {
+    /// Changed element IDs found in the model
+    pub changed: Vec<String>,
+    /// Changed element IDs not found in the model
+    pub unknown: Vec<String>,
+    /// Affected elements grouped by element type, each group sorted by ID
+    pub affected: BTreeMap<String, Vec<ImpactHit>>,
+    /// Number of affected elements (excluding the changed ones)
+    pub total: usize,
+}
+
+/// Compute the transitive set of elements affected by a change
+pub fn analyze_impact(
+    graph: &ModelGraph,
+    changed: &[String],
+    rules: &[FollowRule],
+    include_configs: bool,
+) -> ImpactReport {
+    let index: HashMap<&str, NodeIndex> = graph
+        .node_indices()
+        .map(|n| (graph[n].0.as_str(), n))
+        .collect();
+
+    let mut report = ImpactReport::default();
+    // node -> (previous node, link kind) on the shortest path; changed nodes have none
+    let mut reached: HashMap<NodeIndex, Option<(NodeIndex, String)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    let mut changed: Vec<&String> = changed.iter().collect();
+    changed.sort();
+    changed.dedup();
+    for id in changed {
+        match index.get(id.as_str()) {
+            Some(&node) => {
+                report.changed.push(id.clone());
+                reached.insert(node, None);
+                queue.push_back(node);
+            }
+            None => report.unknown.push(id.clone()),
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        let mut next: Vec<(NodeIndex, String)> = Vec::new();
+        for rule in rules {
+            for &direction in rule.direction.directions() {
+                for edge in graph.edges_directed(node, direction) {
+                    if edge.weight() == rule.kind.as_str() {
+                        let other = if direction == Direction::Incoming { edge.source() } else { edge.target() };
+                        next.push((other, edge.weight().clone()));
+                    }
+                }
+            }
+        }
+        next.sort();
+        for (other, kind) in next {
+            if let Entry::Vacant(entry) = reached.entry(other) {
+                entry.insert(Some((node, kind)));
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let mut hits: Vec<(NodeIndex, ImpactHit)> = reached
+        .keys()
+        .filter(|node| reached[node].is_some())
+        .map(|&node| (node, trace_back(graph, &reached, node)))
+        .collect();
+
+    if include_configs {
+        hits.extend(affected_configs(graph, &reached));
+    }
+
+    for (node, hit) in hits {
+        report
+            .affected
+            .entry(graph[node].1.type_name().to_string())
+            .or_default()
+            .push(hit);
+    }
+    for group in report.affected.values_mut() {
+        group.sort_by(|a, b| a.id.cmp(&b.id));
+    }
+    report.total = report.affected.values().map(Vec::len).sum();
+
+    report
+}
+
+/// Reconstruct the path from a changed element to `node`
+fn trace_back(
+    graph: &ModelGraph,
+    reached: &HashMap<NodeIndex, Option<(NodeIndex, String)>>,
+    node: NodeIndex,
+) -> ImpactHit {
+    let mut path = vec![graph[node].0.clone()];
+    let mut via = Vec::new();
+    let mut current = node;
+    while let Some(Some((previous, kind))) = reached.get(&current) {
+        path.push(graph[*previous].0.clone());
+        via.push(kind.clone());
+        current = *previous;
+    }
+    path.reverse();
+    via.reverse();
+    ImpactHit { id: graph[node].0.clone(), path, via }
+}
+
+/// Configurations selecting a feature that owns or is an affected element
+fn affected_configs(
+    graph: &ModelGraph,
+    reached: &HashMap<NodeIndex, Option<(NodeIndex, String)>>,
+) -> Vec<(NodeIndex, ImpactHit)> {
+    // Owning feature -> shortest explanation ending at that feature
+    let mut owners: BTreeMap<String, ImpactHit> = BTreeMap::new();
+    let mut nodes: Vec<NodeIndex> = reached.keys().copied().collect();
+    nodes.sort();
+    for node in nodes {
+        let mut hit = trace_back(graph, reached, node);
+        let feature = match &graph[node].1 {
+            Element::Feature(_) => Some(graph[node].0.clone()),
+            Element::Req(_) => owning_feature(graph, node).map(|(feature, mut chain, mut kinds)| {
+                hit.path.append(&mut chain);
+                hit.via.append(&mut kinds);
+                feature
+            }),
+            _ => None,
+        };
+        if let Some(feature) = feature {
+            let shorter = owners.get(&feature).is_none_or(|existing| hit.path.len() < existing.path.len());
+            if shorter {
+                owners.insert(feature, hit);
+            }
+        }
+    }
+
+    let mut configs = Vec::new();
+    for node in graph.node_indices() {
+        let Some(config) = graph[node].1.as_config() else {
+            continue;
+        };
+        if reached.contains_key(&node) {
+            continue;
+        }
+        let selected = config.selected.iter().filter_map(|f| owners.get(f)).min_by_key(|hit| hit.path.len());
+        if let Some(owner) = selected {
+            let mut hit = owner.clone();
+            hit.id = graph[node].0.clone();
+            hit.path.push(graph[node].0.clone());
+            hit.via.push("selected_by".to_string());
+            configs.push((node, hit));
+        }
+    }
+    configs
+}
+
+/// Feature a requirement belongs to, following derives_from up to the root
+/// requirement. Returns the feature ID and the path/link kinds leading to it.
+fn owning_feature(graph: &ModelGraph, node: NodeIndex) -> Option<(String, Vec<String>, Vec<String>)> {
+    let mut chain = Vec::new();
+    let mut kinds = Vec::new();
+    let mut current = node;
+    loop {
+        let mut targets: Vec<(NodeIndex, &str)> = graph
+            .edges_directed(current, Direction::Outgoing)
+            .filter_map(|e| match LinkKind::parse(e.weight()) {
+                Some(kind @ (LinkKind::BelongsTo | LinkKind::DerivesFrom)) => Some((e.target(), kind.as_str())),
+                _ => None,
+            })
+            .collect();
+        targets.sort();
+        if let Some(&(feature, kind)) = targets
+            .iter()
+            .find(|(t, k)| *k == "belongs_to" && matches!(graph[*t].1, Element::Feature(_)))
+        {
+            chain.push(graph[feature].0.clone());
+            kinds.push(kind.to_string());
+            return Some((graph[feature].0.clone(), chain, kinds));
+        }
+        let parent = targets
+            .iter()
+            .find(|(t, k)| *k == "derives_from" && matches!(graph[*t].1, Element::Req(_)))?;
+        if chain.contains(&graph[parent.0].0) || parent.0 == node {
+            return None;
+        }
+        chain.push(graph[parent.0].0.clone());
+        kinds.push(parent.1.to_string());
+        current = parent.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::graph_from_json;
+    use serde_json::json;
+
+    fn ids(report: &ImpactReport, element_type: &str) -> Vec<String> {
+        report
+            .affected
+            .get(element_type)
+            .map(|hits| hits.iter().map(|h| h.id.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_default_rules_reach_design_tests_and_configs() {
+        let graph = graph_from_json(
+            json!({
+                "F-ROOT": {"type": "feature", "id": "F-ROOT"},
+                "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": "F-ROOT"},
+                "F-LOG": {"type": "feature", "id": "F-LOG", "parent": "F-ROOT"},
+                "REQ-AUTH-001": {"type": "req", "id": "REQ-AUTH-001"},
+                "REQ-AUTH-002": {"type": "req", "id": "REQ-AUTH-002"},
+                "REQ-LOG-001": {"type": "req", "id": "REQ-LOG-001"},
+                "BLK-AUTH": {"type": "block_definition", "id": "BLK-AUTH"},
+                "IMPL-AUTH": {"type": "implementation", "id": "IMPL-AUTH"},
+                "TC-AUTH": {"type": "test_case", "id": "TC-AUTH"},
+                "CONFIG:BASIC": {"type": "config", "id": "BASIC", "root": "F-ROOT", "selected": ["F-ROOT", "F-LOG"]},
+                "CONFIG:FULL": {"type": "config", "id": "FULL", "root": "F-ROOT", "selected": ["F-ROOT", "F-AUTH", "F-LOG"]}
+            }),
+            json!([
+                {"source": "REQ-AUTH-001", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "REQ-LOG-001", "type": "belongs_to", "target": "F-LOG"},
+                {"source": "REQ-AUTH-002", "type": "derives_from", "target": "REQ-AUTH-001"},
+                {"source": "BLK-AUTH", "type": "allocate", "target": "REQ-AUTH-002"},
+                {"source": "IMPL-AUTH", "type": "satisfy", "target": "BLK-AUTH"},
+                {"source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-002"}
+            ]),
+        );
+        let changed = vec!["REQ-AUTH-001".to_string(), "REQ-MISSING".to_string()];
+        let report = analyze_impact(&graph, &changed, &default_follow_rules(), true);
+
+        assert_eq!(report.changed, vec!["REQ-AUTH-001"]);
+        assert_eq!(report.unknown, vec!["REQ-MISSING"]);
+        assert_eq!(ids(&report, "req"), vec!["REQ-AUTH-002"]);
+        assert_eq!(ids(&report, "block_definition"), vec!["BLK-AUTH"]);
+        assert_eq!(ids(&report, "implementation"), vec!["IMPL-AUTH"]);
+        assert_eq!(ids(&report, "test_case"), vec!["TC-AUTH"]);
+        assert_eq!(ids(&report, "config"), vec!["CONFIG:FULL"]);
+        assert_eq!(report.total, 5);
+
+        let implementation = &report.affected["implementation"][0];
+        assert_eq!(implementation.path, vec!["REQ-AUTH-001", "REQ-AUTH-002", "BLK-AUTH", "IMPL-AUTH"]);
+        assert_eq!(implementation.via, vec!["derives_from", "allocate", "satisfy"]);
+
+        let config = &report.affected["config"][0];
+        assert_eq!(config.path, vec!["REQ-AUTH-001", "F-AUTH", "CONFIG:FULL"]);
+        assert_eq!(config.via, vec!["belongs_to", "selected_by"]);
+    }
+
+    #[test]
+    fn test_follow_rules_restrict_kinds_and_direction() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH"},
+                "REQ-AUTH-001": {"type": "req", "id": "REQ-AUTH-001"},
+                "REQ-AUTH-002": {"type": "req", "id": "REQ-AUTH-002"},
+                "BLK-AUTH": {"type": "block_definition", "id": "BLK-AUTH"},
+                "TC-AUTH": {"type": "test_case", "id": "TC-AUTH"},
+                "CONFIG:FULL": {"type": "config", "id": "FULL", "root": "F-AUTH", "selected": ["F-AUTH"]}
+            }),
+            json!([
+                {"source": "REQ-AUTH-001", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "REQ-AUTH-002", "type": "derives_from", "target": "REQ-AUTH-001"},
+                {"source": "BLK-AUTH", "type": "allocate", "target": "REQ-AUTH-002"},
+                {"source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-002"}
+            ]),
+        );
+        let rules = vec![FollowRule { kind: LinkKind::Verify, direction: FollowDirection::Outgoing }];
+        let report = analyze_impact(&graph, &["TC-AUTH".to_string()], &rules, false);
+
+        assert_eq!(ids(&report, "req"), vec!["REQ-AUTH-002"]);
+        assert_eq!(report.total, 1);
+        assert!(!report.affected.contains_key("config"));
+    }
+}
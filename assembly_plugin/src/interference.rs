@@ -0,0 +1,362 @@
+//! Freedom-from-Interference Analysis
+//!
+//! Looks at every connector of block definitions (`sysml-connectors`) and
+//! internal block diagrams (`ibd-connectors`) and resolves both ends to the
+//! block that owns them: `"port"` is a boundary port of the enclosing block
+//! (or IBD), `"part.port"` a port of the part's block type. A flow from a
+//! lower-ASIL block into a higher-ASIL block is a mixed-criticality flow; it
+//! is acceptable only if a safety mechanism is documented with a
+//! `mitigation` tag on the connector or on the receiving port. Blocks without
+//! an `asil` tag count as QM.
+//!
+//! Flow direction follows the connector (`from` → `to`) unless the port
+//! directions say otherwise (boundary ports are seen from inside the block,
+//! so an `in` boundary port sends to the parts); `inout`/`bidirectional`
+//! ports, and connectors whose ends have the same direction (two `out` or two
+//! `in` ports), are checked in both directions.
+
+use crate::asil::Asil;
+use crate::diagnostics::Diagnostic;
+use crate::types::{Element, SysmlConnector, SysmlPart, SysmlPort};
+use crate::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Tag key documenting a safety mechanism on a connector or receiving port
+pub const MITIGATION_TAG: &str = "mitigation";
+
+/// A flow from a lower-ASIL to a higher-ASIL block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterferenceFlow {
+    /// Block definition or IBD owning the connector
+    pub context: String,
+    /// Sending connector end (as written on the connector)
+    pub from: String,
+    /// Receiving connector end
+    pub to: String,
+    pub flow: String,
+    pub from_block: String,
+    pub to_block: String,
+    pub from_asil: String,
+    pub to_asil: String,
+    /// Documented safety mechanism, None if the flow is unmitigated
+    pub mitigation: Option<String>,
+}
+
+/// One resolved connector end
+#[derive(Clone)]
+struct End {
+    name: String,
+    block: String,
+    asil: Asil,
+    port: Option<SysmlPort>,
+    /// Boundary port of the enclosing block (its direction is seen from outside)
+    boundary: bool,
+}
+
+/// Find all mixed-criticality flows, mitigated or not, in context ID order
+pub fn analyze_interference(registry: &Registry) -> Vec<InterferenceFlow> {
+    let mut flows = Vec::new();
+    let mut keys: Vec<&String> = registry.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let element = &registry[key];
+        let (parts, ports, connectors) = match element {
+            Element::BlockDefinition(block) => (block.parts(), block.ports(), block.connectors()),
+            Element::InternalBlockDiagram(ibd) => (ibd.parts(), ibd.ports(), ibd.connectors()),
+            _ => continue,
+        };
+        let boundary_asil = block_asil(element);
+
+        for connector in &connectors {
+            let resolve = |end: &str| resolve_end(registry, element.id(), boundary_asil, &parts, &ports, end);
+            let (Some(a), Some(b)) = (resolve(&connector.from), resolve(&connector.to)) else {
+                continue;
+            };
+            for (sender, receiver) in flow_directions(a, b) {
+                if sender.asil.level >= receiver.asil.level {
+                    continue;
+                }
+                flows.push(InterferenceFlow {
+                    context: element.id().to_string(),
+                    from: sender.name.clone(),
+                    to: receiver.name.clone(),
+                    flow: connector.flow.clone(),
+                    from_block: sender.block.clone(),
+                    to_block: receiver.block.clone(),
+                    from_asil: sender.asil.to_string(),
+                    to_asil: receiver.asil.to_string(),
+                    mitigation: mitigation(connector, receiver.port.as_ref()),
+                });
+            }
+        }
+    }
+
+    flows
+}
+
+/// Diagnostics (AL035) for unmitigated mixed-criticality flows
+pub fn validate_interference(registry: &Registry) -> Vec<Diagnostic> {
+    analyze_interference(registry)
+        .into_iter()
+        .filter(|flow| flow.mitigation.is_none())
+        .map(|flow| {
+            Diagnostic::new(
+                "AL035",
+                format!(
+                    "{} flow '{}' from {} ({}, {}) to {} ({}, {}) has no documented safety mechanism",
+                    flow.context,
+                    flow.flow,
+                    flow.from,
+                    flow.from_block,
+                    flow.from_asil,
+                    flow.to,
+                    flow.to_block,
+                    flow.to_asil
+                ),
+            )
+            .on(&flow.context)
+            .related([flow.from_block.clone(), flow.to_block.clone()])
+            .hint(format!(
+                "Add tags: ({}: \"...\") to the connector or to the receiving port {}",
+                MITIGATION_TAG, flow.to
+            ))
+        })
+        .collect()
+}
+
+/// ASIL of a block: QM if untagged, `None` if the tag cannot be parsed
+fn block_asil(element: &Element) -> Option<Asil> {
+    match element.safety_tag() {
+        Some(tag) => Asil::parse(tag),
+        None => Some(Asil { level: 0, decomposed_from: None }),
+    }
+}
+
+/// Resolve a connector end to its block and ASIL
+fn resolve_end(
+    registry: &Registry,
+    context: &str,
+    boundary_asil: Option<Asil>,
+    parts: &[SysmlPart],
+    ports: &[SysmlPort],
+    end: &str,
+) -> Option<End> {
+    match end.split_once('.') {
+        Some((part_name, port_name)) => {
+            let part = parts.iter().find(|p| p.name == part_name)?;
+            let element = registry.get(&part.part_type)?;
+            let asil = block_asil(element)?;
+            let port = element.as_block_definition().and_then(|b| b.find_port(port_name));
+            Some(End { name: end.to_string(), block: part.part_type.clone(), asil, port, boundary: false })
+        }
+        None => Some(End {
+            name: end.to_string(),
+            block: context.to_string(),
+            asil: boundary_asil?,
+            port: ports.iter().find(|p| p.name == end).cloned(),
+            boundary: true,
+        }),
+    }
+}
+
+/// Port direction as seen from inside the enclosing block
+fn direction(end: &End) -> &str {
+    match (end.port.as_ref().map(|p| p.direction.as_str()), end.boundary) {
+        (Some("in"), true) => "out",
+        (Some("out"), true) => "in",
+        (Some("bidirectional"), _) => "inout",
+        (Some(direction), _) => direction,
+        (None, _) => "",
+    }
+}
+
+/// (sender, receiver) pairs for a connector between `a` (from) and `b` (to)
+fn flow_directions(a: End, b: End) -> Vec<(End, End)> {
+    let (from, to) = (direction(&a), direction(&b));
+    // Two senders or two receivers cannot tell the direction apart
+    let bidirectional = from == "inout" || to == "inout" || (from == to && matches!(from, "in" | "out"));
+    let reversed = from == "in" || to == "out";
+    if bidirectional {
+        vec![(a.clone(), b.clone()), (b, a)]
+    } else if reversed {
+        vec![(b, a)]
+    } else {
+        vec![(a, b)]
+    }
+}
+
+/// Mitigation documented on the connector or the receiving port
+fn mitigation(connector: &SysmlConnector, receiving_port: Option<&SysmlPort>) -> Option<String> {
+    let text = |tags: &HashMap<String, serde_json::Value>| {
+        tags.get(MITIGATION_TAG)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    text(&connector.tags).or_else(|| receiving_port.and_then(|p| text(&p.tags)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unmitigated_flow_into_higher_asil_is_reported() {
+        let registry: Registry = serde_json::from_value(json!({
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "tags": {
+                "asil": "D",
+                "sysml-parts": [
+                    {"name": "infotainment", "type": "BLK-INFO", "multiplicity": "1"},
+                    {"name": "brake", "type": "BLK-BRAKE", "multiplicity": "1"}
+                ],
+                "sysml-connectors": [
+                    {"from": "infotainment.speedOut", "to": "brake.speedIn", "flow": "Speed"},
+                    {"from": "brake.speedOut", "to": "infotainment.speedIn", "flow": "Status"},
+                    {"from": "infotainment.speedOut", "to": "brake.guarded", "flow": "Speed"},
+                    {"from": "infotainment.speedOut", "to": "brake.speedIn", "flow": "Mode", "tags": {"mitigation": "Plausibility check"}}
+                ]
+            }},
+            "BLK-INFO": {"type": "block_definition", "id": "BLK-INFO", "tags": {
+                "asil": "QM",
+                "sysml-ports": [
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"},
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"}
+                ]
+            }},
+            "BLK-BRAKE": {"type": "block_definition", "id": "BLK-BRAKE", "tags": {
+                "asil": "ASIL-D",
+                "sysml-ports": [
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"},
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"},
+                    {"name": "guarded", "direction": "in", "protocol": "CAN", "tags": {"mitigation": "E2E profile 5"}}
+                ]
+            }}
+        }))
+        .unwrap();
+        let flows = analyze_interference(&registry);
+        assert_eq!(flows.len(), 3);
+        assert_eq!(flows[0].from_block, "BLK-INFO");
+        assert_eq!(flows[0].to_block, "BLK-BRAKE");
+        assert_eq!(flows[0].from_asil, "QM");
+        assert_eq!(flows[0].to_asil, "ASIL-D");
+        assert_eq!(flows[1].mitigation.as_deref(), Some("E2E profile 5"));
+        assert_eq!(flows[2].mitigation.as_deref(), Some("Plausibility check"));
+
+        let diagnostics = validate_interference(&registry);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "AL035");
+        assert_eq!(diagnostics[0].element_id.as_deref(), Some("BLK-ECU"));
+    }
+
+    #[test]
+    fn test_port_directions_decide_flow_direction() {
+        // Connectors written against the data flow, and a delegation from the
+        // ASIL-D boundary port into the QM part
+        let registry: Registry = serde_json::from_value(json!({
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "tags": {
+                "asil": "D",
+                "sysml-ports": [{"name": "diag", "direction": "in", "protocol": "UDS"}],
+                "sysml-parts": [
+                    {"name": "infotainment", "type": "BLK-INFO", "multiplicity": "1"},
+                    {"name": "brake", "type": "BLK-BRAKE", "multiplicity": "1"}
+                ],
+                "sysml-connectors": [
+                    {"from": "infotainment.speedIn", "to": "brake.speedOut", "flow": "Status"},
+                    {"from": "brake.speedIn", "to": "infotainment.speedOut", "flow": "Speed"},
+                    {"from": "diag", "to": "infotainment.speedIn", "flow": "Diag"}
+                ]
+            }},
+            "BLK-INFO": {"type": "block_definition", "id": "BLK-INFO", "tags": {
+                "asil": "QM",
+                "sysml-ports": [
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"},
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"}
+                ]
+            }},
+            "BLK-BRAKE": {"type": "block_definition", "id": "BLK-BRAKE", "tags": {
+                "asil": "ASIL-D",
+                "sysml-ports": [
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"},
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"}
+                ]
+            }}
+        }))
+        .unwrap();
+        let flows = analyze_interference(&registry);
+        assert_eq!(flows.len(), 1);
+        assert_eq!(flows[0].from, "infotainment.speedOut");
+        assert_eq!(flows[0].to, "brake.speedIn");
+        assert_eq!(flows[0].flow, "Speed");
+    }
+
+    #[test]
+    fn test_ends_with_the_same_direction_are_checked_both_ways() {
+        let registry: Registry = serde_json::from_value(json!({
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "tags": {
+                "asil": "D",
+                "sysml-parts": [
+                    {"name": "infotainment", "type": "BLK-INFO", "multiplicity": "1"},
+                    {"name": "brake", "type": "BLK-BRAKE", "multiplicity": "1"}
+                ],
+                "sysml-connectors": [
+                    {"from": "brake.speedOut", "to": "infotainment.speedOut", "flow": "Speed"},
+                    {"from": "brake.speedIn", "to": "infotainment.speedIn", "flow": "Status"}
+                ]
+            }},
+            "BLK-INFO": {"type": "block_definition", "id": "BLK-INFO", "tags": {
+                "asil": "QM",
+                "sysml-ports": [
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"},
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"}
+                ]
+            }},
+            "BLK-BRAKE": {"type": "block_definition", "id": "BLK-BRAKE", "tags": {
+                "asil": "ASIL-D",
+                "sysml-ports": [
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"},
+                    {"name": "speedOut", "direction": "out", "protocol": "CAN"}
+                ]
+            }}
+        }))
+        .unwrap();
+        let flows = analyze_interference(&registry);
+        let found: Vec<(&str, &str)> = flows.iter().map(|f| (f.from.as_str(), f.to.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![("infotainment.speedOut", "brake.speedOut"), ("infotainment.speedIn", "brake.speedIn")]
+        );
+    }
+
+    #[test]
+    fn test_untagged_block_counts_as_qm() {
+        let registry: Registry = serde_json::from_value(json!({
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "tags": {
+                "asil": "D",
+                "sysml-parts": [
+                    {"name": "sensor", "type": "BLK-SENSOR", "multiplicity": "1"},
+                    {"name": "brake", "type": "BLK-BRAKE", "multiplicity": "1"}
+                ],
+                "sysml-connectors": [
+                    {"from": "sensor.speedOut", "to": "brake.speedIn", "flow": "Speed"}
+                ]
+            }},
+            "BLK-SENSOR": {"type": "block_definition", "id": "BLK-SENSOR", "tags": {
+                "sysml-ports": [{"name": "speedOut", "direction": "out", "protocol": "CAN"}]
+            }},
+            "BLK-BRAKE": {"type": "block_definition", "id": "BLK-BRAKE", "tags": {
+                "asil": "ASIL-D",
+                "sysml-ports": [{"name": "speedIn", "direction": "in", "protocol": "CAN"}]
+            }}
+        }))
+        .unwrap();
+        let flows = analyze_interference(&registry);
+        assert_eq!(flows.len(), 1);
+        assert_eq!(flows[0].from_block, "BLK-SENSOR");
+        assert_eq!(flows[0].from_asil, "QM");
+        assert_eq!(validate_interference(&registry).len(), 1);
+    }
+}
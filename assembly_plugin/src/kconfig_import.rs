@@ -0,0 +1,1113 @@
+//! Kconfig Import Module
+//!
+//! Parses Linux/Zephyr Kconfig trees and translates them into feature elements.
+//!
+//! Supported constructs: config, menuconfig, choice/endchoice, bool, int, string,
+//! prompt, default, depends on, select, imply, range, help, mainmenu, plus
+//! def_bool/def_int/def_string shorthands. `menu` and `if` blocks are flattened
+//! (their conditions are pushed down onto the contained entries). Everything else
+//! is skipped with a line-numbered warning.
+//!
+//! Translation rules:
+//! - bool symbols become concrete features tagged with `kconfig: SYMBOL`
+//! - choices become abstract XOR group features, their members become children
+//! - entries depending on a preceding menuconfig are nested below it
+//! - `depends on A && B` and `select X` become `requires`, `depends on !X` becomes `excludes`
+//! - int/string symbols become Integer/String parameters of the feature they depend on
+
+use crate::types::{FeatureElement, ParameterSchema};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Kconfig symbol type (tristate is read as bool)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KconfigType {
+    Bool,
+    Int,
+    String,
+}
+
+/// A parsed `config`/`menuconfig` entry
+#[derive(Debug, Clone)]
+pub struct KconfigSymbol {
+    pub name: String,
+    pub line: usize,
+    pub is_menuconfig: bool,
+    pub symbol_type: Option<KconfigType>,
+    pub prompt: Option<String>,
+    /// First unconditional default expression
+    pub default: Option<String>,
+    /// Raw `depends on` expressions (including inherited menu/if conditions)
+    pub depends_on: Vec<String>,
+    pub selects: Vec<String>,
+    pub implies: Vec<String>,
+    /// Raw (min, max) bounds
+    pub range: Option<(String, String)>,
+    pub help: Option<String>,
+    /// Index into `KconfigModel::choices` if declared inside a choice block
+    pub choice: Option<usize>,
+}
+
+/// A parsed `choice` block
+#[derive(Debug, Clone)]
+pub struct KconfigChoice {
+    pub name: Option<String>,
+    pub line: usize,
+    pub prompt: Option<String>,
+    pub default: Option<String>,
+    pub depends_on: Vec<String>,
+    pub help: Option<String>,
+}
+
+/// Result of parsing a Kconfig source
+#[derive(Debug, Clone, Default)]
+pub struct KconfigModel {
+    pub mainmenu: Option<String>,
+    pub symbols: Vec<KconfigSymbol>,
+    pub choices: Vec<KconfigChoice>,
+    pub warnings: Vec<String>,
+}
+
+/// Options controlling the Kconfig → feature translation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KconfigImportOptions {
+    /// ID of the feature created for the Kconfig tree itself
+    #[serde(default = "default_root_id")]
+    pub root_id: String,
+    /// Optional parent for the root feature (to graft the tree into an existing model)
+    #[serde(default)]
+    pub root_parent: Option<String>,
+    /// Prefix prepended to symbol names to form feature IDs
+    #[serde(default = "default_id_prefix")]
+    pub id_prefix: String,
+}
+
+fn default_root_id() -> String {
+    "ROOT".to_string()
+}
+
+fn default_id_prefix() -> String {
+    "F-".to_string()
+}
+
+impl Default for KconfigImportOptions {
+    fn default() -> Self {
+        KconfigImportOptions {
+            root_id: default_root_id(),
+            root_parent: None,
+            id_prefix: default_id_prefix(),
+        }
+    }
+}
+
+/// Result of a Kconfig import
+#[derive(Debug, Clone)]
+pub struct KconfigImport {
+    pub features: Vec<FeatureElement>,
+    pub warnings: Vec<String>,
+}
+
+/// Entry currently receiving attribute lines
+#[derive(Debug, Clone, Copy)]
+enum Current {
+    None,
+    Symbol(usize),
+    Choice(usize),
+    Menu,
+}
+
+/// Parse Kconfig source text into a `KconfigModel`
+pub fn parse_kconfig(source: &str) -> KconfigModel {
+    let mut model = KconfigModel::default();
+    let raw_lines: Vec<&str> = source.lines().collect();
+
+    let mut current = Current::None;
+    let mut choice_stack: Vec<usize> = Vec::new();
+    // Conditions inherited from enclosing `menu ... depends on` and `if` blocks
+    let mut menu_conditions: Vec<Vec<String>> = Vec::new();
+    let mut if_conditions: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < raw_lines.len() {
+        let line_no = i + 1;
+
+        // Join backslash continuations
+        let mut text = raw_lines[i].to_string();
+        while text.trim_end().ends_with('\\') && i + 1 < raw_lines.len() {
+            let trimmed = text.trim_end();
+            text = format!("{} {}", &trimmed[..trimmed.len() - 1], raw_lines[i + 1].trim());
+            i += 1;
+        }
+        i += 1;
+
+        let line = strip_comment(&text);
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (keyword, rest) = split_keyword(line);
+        let inherited = || -> Vec<String> {
+            menu_conditions
+                .iter()
+                .flatten()
+                .chain(if_conditions.iter())
+                .cloned()
+                .collect()
+        };
+
+        match keyword {
+            "mainmenu" => {
+                model.mainmenu = parse_quoted(rest).map(|(s, _)| s);
+                current = Current::None;
+            }
+            "config" | "menuconfig" => {
+                let name = rest.trim().to_string();
+                if name.is_empty() {
+                    model
+                        .warnings
+                        .push(format!("line {}: '{}' without a symbol name", line_no, keyword));
+                    current = Current::None;
+                    continue;
+                }
+                model.symbols.push(KconfigSymbol {
+                    name,
+                    line: line_no,
+                    is_menuconfig: keyword == "menuconfig",
+                    symbol_type: None,
+                    prompt: None,
+                    default: None,
+                    depends_on: inherited(),
+                    selects: Vec::new(),
+                    implies: Vec::new(),
+                    range: None,
+                    help: None,
+                    choice: choice_stack.last().copied(),
+                });
+                current = Current::Symbol(model.symbols.len() - 1);
+            }
+            "choice" => {
+                if !choice_stack.is_empty() {
+                    model.warnings.push(format!(
+                        "line {}: nested choice is not supported; it is imported as a separate XOR group",
+                        line_no
+                    ));
+                }
+                let name = rest.trim();
+                model.choices.push(KconfigChoice {
+                    name: if name.is_empty() { None } else { Some(name.to_string()) },
+                    line: line_no,
+                    prompt: None,
+                    default: None,
+                    depends_on: inherited(),
+                    help: None,
+                });
+                choice_stack.push(model.choices.len() - 1);
+                current = Current::Choice(model.choices.len() - 1);
+            }
+            "endchoice" => {
+                if choice_stack.pop().is_none() {
+                    model
+                        .warnings
+                        .push(format!("line {}: 'endchoice' without matching 'choice'", line_no));
+                }
+                current = Current::None;
+            }
+            "menu" => {
+                model.warnings.push(format!(
+                    "line {}: menu {} is flattened; its entries are imported without a menu feature",
+                    line_no,
+                    rest.trim()
+                ));
+                menu_conditions.push(Vec::new());
+                current = Current::Menu;
+            }
+            "endmenu" => {
+                if menu_conditions.pop().is_none() {
+                    model
+                        .warnings
+                        .push(format!("line {}: 'endmenu' without matching 'menu'", line_no));
+                }
+                current = Current::None;
+            }
+            "if" => {
+                if_conditions.push(rest.trim().to_string());
+                current = Current::None;
+            }
+            "endif" => {
+                if if_conditions.pop().is_none() {
+                    model
+                        .warnings
+                        .push(format!("line {}: 'endif' without matching 'if'", line_no));
+                }
+                current = Current::None;
+            }
+            "help" | "---help---" => {
+                let (help, next) = read_help(&raw_lines, i, raw_lines[line_no - 1]);
+                i = next;
+                match current {
+                    Current::Symbol(idx) => model.symbols[idx].help = Some(help),
+                    Current::Choice(idx) => model.choices[idx].help = Some(help),
+                    _ => model
+                        .warnings
+                        .push(format!("line {}: help text outside of an entry is ignored", line_no)),
+                }
+            }
+            "depends" => {
+                let expr = match rest.trim().strip_prefix("on") {
+                    Some(expr) => expr.trim().to_string(),
+                    None => {
+                        model
+                            .warnings
+                            .push(format!("line {}: expected 'depends on'", line_no));
+                        continue;
+                    }
+                };
+                match current {
+                    Current::Symbol(idx) => model.symbols[idx].depends_on.push(expr),
+                    Current::Choice(idx) => model.choices[idx].depends_on.push(expr),
+                    Current::Menu => {
+                        if let Some(frame) = menu_conditions.last_mut() {
+                            frame.push(expr);
+                        }
+                    }
+                    Current::None => model
+                        .warnings
+                        .push(format!("line {}: 'depends on' outside of an entry is ignored", line_no)),
+                }
+            }
+            "bool" | "tristate" | "int" | "string" | "def_bool" | "def_tristate" | "def_int"
+            | "def_string" | "hex" | "def_hex" => {
+                let symbol_type = match keyword {
+                    "bool" | "tristate" | "def_bool" | "def_tristate" => KconfigType::Bool,
+                    "int" | "def_int" => KconfigType::Int,
+                    "string" | "def_string" => KconfigType::String,
+                    _ => {
+                        model.warnings.push(format!(
+                            "line {}: '{}' symbols are not supported; entry skipped",
+                            line_no, keyword
+                        ));
+                        if let Current::Symbol(idx) = current {
+                            model.symbols.truncate(idx);
+                        }
+                        current = Current::None;
+                        continue;
+                    }
+                };
+                if keyword.ends_with("tristate") {
+                    model.warnings.push(format!(
+                        "line {}: tristate is imported as bool (module state 'm' is not modelled)",
+                        line_no
+                    ));
+                }
+                let idx = match current {
+                    Current::Symbol(idx) => idx,
+                    Current::Choice(c) => {
+                        // `bool "prompt"` on a choice only declares its type
+                        if let Some((prompt, _)) = parse_quoted(rest) {
+                            model.choices[c].prompt = Some(prompt);
+                        }
+                        continue;
+                    }
+                    _ => {
+                        model.warnings.push(format!(
+                            "line {}: type '{}' outside of a config entry is ignored",
+                            line_no, keyword
+                        ));
+                        continue;
+                    }
+                };
+                let symbol = &mut model.symbols[idx];
+                symbol.symbol_type = Some(symbol_type);
+                if keyword.starts_with("def_") {
+                    let (value, condition) = split_if(rest);
+                    if condition.is_some() {
+                        model.warnings.push(format!(
+                            "line {}: conditional default of {} is ignored",
+                            line_no, symbol.name
+                        ));
+                    } else if symbol.default.is_none() {
+                        symbol.default = Some(value.trim().to_string());
+                    }
+                } else if let Some((prompt, _)) = parse_quoted(rest) {
+                    symbol.prompt = Some(prompt);
+                }
+            }
+            "prompt" => {
+                let prompt = parse_quoted(rest).map(|(s, _)| s);
+                match current {
+                    Current::Symbol(idx) => model.symbols[idx].prompt = prompt,
+                    Current::Choice(idx) => model.choices[idx].prompt = prompt,
+                    _ => model
+                        .warnings
+                        .push(format!("line {}: 'prompt' outside of an entry is ignored", line_no)),
+                }
+            }
+            "default" => {
+                let (value, condition) = split_if(rest);
+                let value = value.trim().to_string();
+                let (name, slot) = match current {
+                    Current::Symbol(idx) => {
+                        let s = &mut model.symbols[idx];
+                        (s.name.clone(), &mut s.default)
+                    }
+                    Current::Choice(idx) => {
+                        let c = &mut model.choices[idx];
+                        (c.name.clone().unwrap_or_else(|| "choice".to_string()), &mut c.default)
+                    }
+                    _ => {
+                        model
+                            .warnings
+                            .push(format!("line {}: 'default' outside of an entry is ignored", line_no));
+                        continue;
+                    }
+                };
+                if condition.is_some() {
+                    model.warnings.push(format!(
+                        "line {}: conditional default of {} is ignored",
+                        line_no, name
+                    ));
+                } else if slot.is_none() {
+                    *slot = Some(value);
+                }
+            }
+            "select" | "imply" => {
+                let (target, condition) = split_if(rest);
+                let Current::Symbol(idx) = current else {
+                    model.warnings.push(format!(
+                        "line {}: '{}' outside of a config entry is ignored",
+                        line_no, keyword
+                    ));
+                    continue;
+                };
+                let symbol = &mut model.symbols[idx];
+                if condition.is_some() {
+                    model.warnings.push(format!(
+                        "line {}: condition on '{} {}' is ignored; imported unconditionally",
+                        line_no,
+                        keyword,
+                        target.trim()
+                    ));
+                }
+                let target = target.trim().to_string();
+                if keyword == "select" {
+                    symbol.selects.push(target);
+                } else {
+                    symbol.implies.push(target);
+                }
+            }
+            "range" => {
+                let (bounds, condition) = split_if(rest);
+                let Current::Symbol(idx) = current else {
+                    model
+                        .warnings
+                        .push(format!("line {}: 'range' outside of a config entry is ignored", line_no));
+                    continue;
+                };
+                let symbol = &mut model.symbols[idx];
+                if condition.is_some() {
+                    model.warnings.push(format!(
+                        "line {}: conditional range of {} is ignored",
+                        line_no, symbol.name
+                    ));
+                    continue;
+                }
+                let parts: Vec<&str> = bounds.split_whitespace().collect();
+                if parts.len() == 2 {
+                    if symbol.range.is_none() {
+                        symbol.range = Some((parts[0].to_string(), parts[1].to_string()));
+                    }
+                } else {
+                    model.warnings.push(format!(
+                        "line {}: malformed range '{}' for {}",
+                        line_no,
+                        bounds.trim(),
+                        symbol.name
+                    ));
+                }
+            }
+            other => {
+                model.warnings.push(format!(
+                    "line {}: unsupported construct '{}' is ignored",
+                    line_no, other
+                ));
+            }
+        }
+    }
+
+    if !choice_stack.is_empty() {
+        model
+            .warnings
+            .push("unterminated 'choice' block at end of input".to_string());
+    }
+
+    model
+}
+
+/// Parse Kconfig source and translate it into feature elements
+pub fn import_kconfig(source: &str, options: &KconfigImportOptions) -> KconfigImport {
+    let model = parse_kconfig(source);
+    kconfig_to_features(&model, options)
+}
+
+/// One conjunct of a `depends on` expression
+enum Conjunct {
+    Requires(String),
+    Excludes(String),
+    Unsupported(String),
+}
+
+/// Translate a parsed Kconfig model into feature elements
+pub fn kconfig_to_features(model: &KconfigModel, options: &KconfigImportOptions) -> KconfigImport {
+    let mut warnings = model.warnings.clone();
+    let feature_id = |name: &str| format!("{}{}", options.id_prefix, name);
+
+    // Symbol name -> index (first definition wins, later ones are merged as warnings)
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    for (idx, symbol) in model.symbols.iter().enumerate() {
+        if by_name.contains_key(symbol.name.as_str()) {
+            warnings.push(format!(
+                "line {}: symbol {} is defined more than once; later definition ignored",
+                symbol.line, symbol.name
+            ));
+        } else {
+            by_name.insert(&symbol.name, idx);
+        }
+    }
+    let is_bool = |name: &str| {
+        by_name
+            .get(name)
+            .map(|&i| model.symbols[i].symbol_type == Some(KconfigType::Bool))
+            .unwrap_or(false)
+    };
+
+    let choice_ids: Vec<String> = model
+        .choices
+        .iter()
+        .enumerate()
+        .map(|(n, c)| match &c.name {
+            Some(name) => feature_id(name),
+            None => feature_id(&format!("CHOICE-{}", n + 1)),
+        })
+        .collect();
+
+    let mut features: Vec<FeatureElement> = Vec::new();
+    let mut index_of: HashMap<String, usize> = HashMap::new();
+
+    features.push(new_feature(
+        &options.root_id,
+        model.mainmenu.as_deref().unwrap_or("Kconfig"),
+        options.root_parent.clone(),
+    ));
+    index_of.insert(options.root_id.clone(), 0);
+
+    // Resolve parent and constraints for an entry from its dependencies
+    let resolve = |depends_on: &[String],
+                   line: usize,
+                   defined_before: usize,
+                   warnings: &mut Vec<String>|
+     -> (Option<String>, Vec<String>, Vec<String>) {
+        let mut parent = None;
+        let mut requires = Vec::new();
+        let mut excludes = Vec::new();
+        for conjunct in depends_on.iter().flat_map(|e| split_conjuncts(e)) {
+            match conjunct {
+                Conjunct::Requires(name) => {
+                    let menu_parent = by_name.get(name.as_str()).is_some_and(|&i| {
+                        i < defined_before && model.symbols[i].is_menuconfig && is_bool(&name)
+                    });
+                    if parent.is_none() && menu_parent {
+                        parent = Some(feature_id(&name));
+                    } else if is_bool(&name) {
+                        requires.push(feature_id(&name));
+                    } else {
+                        warnings.push(format!(
+                            "line {}: dependency on {} dropped (not a bool symbol of this Kconfig)",
+                            line, name
+                        ));
+                    }
+                }
+                Conjunct::Excludes(name) => {
+                    if is_bool(&name) {
+                        excludes.push(feature_id(&name));
+                    } else {
+                        warnings.push(format!(
+                            "line {}: dependency on !{} dropped (not a bool symbol of this Kconfig)",
+                            line, name
+                        ));
+                    }
+                }
+                Conjunct::Unsupported(expr) => warnings.push(format!(
+                    "line {}: dependency expression '{}' cannot be expressed as requires/excludes; dropped",
+                    line, expr
+                )),
+            }
+        }
+        (parent, requires, excludes)
+    };
+
+    // Choices become abstract XOR groups
+    for (n, choice) in model.choices.iter().enumerate() {
+        let first_member = model
+            .symbols
+            .iter()
+            .position(|s| s.choice == Some(n))
+            .unwrap_or(model.symbols.len());
+        let (parent, requires, excludes) =
+            resolve(&choice.depends_on, choice.line, first_member, &mut warnings);
+        let mut feature = new_feature(
+            &choice_ids[n],
+            choice.prompt.as_deref().unwrap_or(&choice_ids[n]),
+            Some(parent.unwrap_or_else(|| options.root_id.clone())),
+        );
+        feature.concrete = Some(false);
+        feature.group = Some("XOR".to_string());
+        if let Some(name) = &choice.name {
+            feature.tags.insert("kconfig".to_string(), serde_json::json!(name));
+        }
+        if let Some(default) = &choice.default {
+            feature
+                .tags
+                .insert("kconfig-default".to_string(), serde_json::json!(feature_id(default)));
+        }
+        if let Some(help) = &choice.help {
+            feature.body = serde_json::json!(help);
+        }
+        set_list_tag(&mut feature, "requires", requires);
+        set_list_tag(&mut feature, "excludes", excludes);
+
+        let members = model.symbols.iter().filter(|s| s.choice == Some(n)).count();
+        if members < 2 {
+            warnings.push(format!(
+                "line {}: choice {} has {} member(s); XOR groups need at least 2",
+                choice.line, choice_ids[n], members
+            ));
+        }
+        index_of.insert(feature.id.clone(), features.len());
+        features.push(feature);
+    }
+
+    // Bool symbols become features
+    for (idx, symbol) in model.symbols.iter().enumerate() {
+        if by_name.get(symbol.name.as_str()) != Some(&idx) {
+            continue;
+        }
+        let Some(KconfigType::Bool) = symbol.symbol_type else {
+            continue;
+        };
+        let (parent, mut requires, excludes) =
+            resolve(&symbol.depends_on, symbol.line, idx, &mut warnings);
+        let parent = match symbol.choice {
+            Some(c) => {
+                // The choice is the parent; a menu dependency stays a constraint
+                requires.extend(parent);
+                choice_ids[c].clone()
+            }
+            None => parent.unwrap_or_else(|| options.root_id.clone()),
+        };
+
+        let id = feature_id(&symbol.name);
+        let mut feature = new_feature(&id, symbol.prompt.as_deref().unwrap_or(&symbol.name), Some(parent));
+        feature
+            .tags
+            .insert("kconfig".to_string(), serde_json::json!(symbol.name));
+
+        for target in &symbol.selects {
+            if is_bool(target) {
+                requires.push(feature_id(target));
+            } else {
+                warnings.push(format!(
+                    "line {}: {} selects {} which is not a bool symbol of this Kconfig; dropped",
+                    symbol.line, symbol.name, target
+                ));
+            }
+        }
+        let implies: Vec<String> = symbol
+            .implies
+            .iter()
+            .filter(|t| is_bool(t))
+            .map(|t| feature_id(t))
+            .collect();
+
+        if let Some(default) = &symbol.default {
+            match default.as_str() {
+                "y" | "n" => {
+                    feature
+                        .tags
+                        .insert("kconfig-default".to_string(), serde_json::json!(default));
+                }
+                other => warnings.push(format!(
+                    "line {}: default '{}' of {} is an expression; ignored",
+                    symbol.line, other, symbol.name
+                )),
+            }
+        }
+        if let Some(help) = &symbol.help {
+            feature.body = serde_json::json!(help);
+        }
+        set_list_tag(&mut feature, "requires", requires);
+        set_list_tag(&mut feature, "excludes", excludes);
+        set_list_tag(&mut feature, "kconfig-imply", implies);
+
+        index_of.insert(id, features.len());
+        features.push(feature);
+    }
+
+    // Int/string symbols become parameters of the feature they depend on
+    for (idx, symbol) in model.symbols.iter().enumerate() {
+        if by_name.get(symbol.name.as_str()) != Some(&idx) {
+            continue;
+        }
+        let param_type = match symbol.symbol_type {
+            Some(KconfigType::Int) => "Integer",
+            Some(KconfigType::String) => "String",
+            Some(KconfigType::Bool) => continue,
+            None => {
+                warnings.push(format!(
+                    "line {}: symbol {} has no type; skipped",
+                    symbol.line, symbol.name
+                ));
+                continue;
+            }
+        };
+        if symbol.choice.is_some() {
+            warnings.push(format!(
+                "line {}: non-bool symbol {} inside a choice; imported as a parameter of the root",
+                symbol.line, symbol.name
+            ));
+        }
+        if !symbol.selects.is_empty() || !symbol.implies.is_empty() {
+            warnings.push(format!(
+                "line {}: select/imply on non-bool symbol {} is ignored",
+                symbol.line, symbol.name
+            ));
+        }
+
+        // Owner: first bool symbol in the dependencies, otherwise the root
+        let mut owner = None;
+        for conjunct in symbol.depends_on.iter().flat_map(|e| split_conjuncts(e)) {
+            match conjunct {
+                Conjunct::Requires(name) if owner.is_none() && is_bool(&name) => {
+                    owner = Some(feature_id(&name));
+                }
+                Conjunct::Requires(name) | Conjunct::Excludes(name) => warnings.push(format!(
+                    "line {}: dependency of parameter {} on {} is not represented",
+                    symbol.line, symbol.name, name
+                )),
+                Conjunct::Unsupported(expr) => warnings.push(format!(
+                    "line {}: dependency of parameter {} on '{}' is not represented",
+                    symbol.line, symbol.name, expr
+                )),
+            }
+        }
+        let owner = owner.unwrap_or_else(|| options.root_id.clone());
+
+        let range = match (&symbol.range, symbol.symbol_type) {
+            (Some((min, max)), Some(KconfigType::Int)) => {
+                match (min.parse::<i64>(), max.parse::<i64>()) {
+                    (Ok(min), Ok(max)) => Some((min, max)),
+                    _ => {
+                        warnings.push(format!(
+                            "line {}: range of {} uses non-literal bounds; ignored",
+                            symbol.line, symbol.name
+                        ));
+                        None
+                    }
+                }
+            }
+            (Some(_), _) => {
+                warnings.push(format!(
+                    "line {}: range on string symbol {} is ignored",
+                    symbol.line, symbol.name
+                ));
+                None
+            }
+            (None, _) => None,
+        };
+
+        let default = match (&symbol.default, symbol.symbol_type) {
+            (None, _) => serde_json::Value::Null,
+            (Some(value), Some(KconfigType::Int)) => match value.parse::<i64>() {
+                Ok(n) => serde_json::json!(n),
+                Err(_) => {
+                    warnings.push(format!(
+                        "line {}: default '{}' of {} is not an integer literal; ignored",
+                        symbol.line, value, symbol.name
+                    ));
+                    serde_json::Value::Null
+                }
+            },
+            (Some(value), _) => match parse_quoted(value) {
+                Some((s, _)) => serde_json::json!(s),
+                None => {
+                    warnings.push(format!(
+                        "line {}: default '{}' of {} is not a string literal; ignored",
+                        symbol.line, value, symbol.name
+                    ));
+                    serde_json::Value::Null
+                }
+            },
+        };
+
+        let schema = ParameterSchema {
+            param_type: param_type.to_string(),
+            range,
+            values: None,
+            unit: None,
+            default,
+            description: symbol.prompt.clone().or_else(|| symbol.help.clone()),
+        };
+        let owner_feature = &mut features[index_of[&owner]];
+        owner_feature
+            .parameters
+            .get_or_insert_with(HashMap::new)
+            .insert(symbol.name.clone(), schema);
+    }
+
+    KconfigImport { features, warnings }
+}
+
+fn new_feature(id: &str, title: &str, parent: Option<String>) -> FeatureElement {
+    FeatureElement {
+        id: id.to_string(),
+        title: title.to_string(),
+        tags: HashMap::new(),
+        parent,
+        concrete: Some(true),
+        group: None,
+        body: serde_json::Value::Null,
+        parameters: None,
+        constraints: None,
+        requires: None,
+    }
+}
+
+/// Store a non-empty ID list as a tag (single IDs as a plain string, like Typst does)
+fn set_list_tag(feature: &mut FeatureElement, key: &str, ids: Vec<String>) {
+    match ids.len() {
+        0 => {}
+        1 => {
+            feature.tags.insert(key.to_string(), serde_json::json!(ids[0]));
+        }
+        _ => {
+            feature.tags.insert(key.to_string(), serde_json::json!(ids));
+        }
+    }
+}
+
+/// Split a `depends on` expression on top-level `&&`
+fn split_conjuncts(expr: &str) -> Vec<Conjunct> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let bytes = expr.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b'&' if depth == 0 && bytes.get(i + 1) == Some(&b'&') => {
+                parts.push(&expr[start..i]);
+                start = i + 2;
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(&expr[start..]);
+
+    parts
+        .into_iter()
+        .map(|p| {
+            let p = p.trim();
+            let p = strip_parens(p);
+            if is_symbol(p) {
+                Conjunct::Requires(p.to_string())
+            } else if let Some(rest) = p.strip_prefix('!')
+                && is_symbol(strip_parens(rest.trim()))
+            {
+                Conjunct::Excludes(strip_parens(rest.trim()).to_string())
+            } else {
+                Conjunct::Unsupported(p.to_string())
+            }
+        })
+        .collect()
+}
+
+fn strip_parens(s: &str) -> &str {
+    let mut s = s.trim();
+    while s.starts_with('(') && s.ends_with(')') && balanced(&s[1..s.len() - 1]) {
+        s = s[1..s.len() - 1].trim();
+    }
+    s
+}
+
+fn balanced(s: &str) -> bool {
+    let mut depth = 0i32;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+fn is_symbol(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(s, "y" | "n" | "m")
+}
+
+/// Split the leading keyword from the rest of the line
+fn split_keyword(line: &str) -> (&str, &str) {
+    match line.find(char::is_whitespace) {
+        Some(pos) => (&line[..pos], &line[pos..]),
+        None => (line, ""),
+    }
+}
+
+/// Remove a trailing `#` comment, ignoring `#` inside quoted strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = None;
+    let mut escaped = false;
+    for (pos, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (c, in_string) {
+            ('\\', Some(_)) => escaped = true,
+            ('"' | '\'', None) => in_string = Some(c),
+            (q, Some(open)) if q == open => in_string = None,
+            ('#', None) => return &line[..pos],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a leading quoted string, returning its unescaped value and the remainder
+fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let s = s.trim_start();
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let mut value = String::new();
+    let mut escaped = false;
+    for (pos, c) in s.char_indices().skip(1) {
+        if escaped {
+            value.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some((value, &s[pos + 1..]));
+        } else {
+            value.push(c);
+        }
+    }
+    None
+}
+
+/// Split `VALUE if CONDITION` on the first top-level `if` outside quotes
+fn split_if(s: &str) -> (&str, Option<&str>) {
+    let mut in_string = None;
+    let bytes = s.as_bytes();
+    for (pos, c) in s.char_indices() {
+        match (c, in_string) {
+            ('"' | '\'', None) => in_string = Some(c),
+            (q, Some(open)) if q == open => in_string = None,
+            ('i', None)
+                if s[pos..].starts_with("if")
+                    && (pos == 0 || bytes[pos - 1].is_ascii_whitespace())
+                    && bytes.get(pos + 2).is_none_or(|b| b.is_ascii_whitespace()) =>
+            {
+                return (&s[..pos], Some(s[pos + 2..].trim()));
+            }
+            _ => {}
+        }
+    }
+    (s, None)
+}
+
+/// Read an indented help block starting at `start`, returning the text and the next line index
+fn read_help(lines: &[&str], start: usize, help_line: &str) -> (String, usize) {
+    let help_indent = indentation(help_line);
+    let mut block_indent = None;
+    let mut text: Vec<&str> = Vec::new();
+    let mut i = start;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() {
+            text.push("");
+            i += 1;
+            continue;
+        }
+        let indent = indentation(line);
+        let required = *block_indent.get_or_insert(indent);
+        if indent <= help_indent || indent < required {
+            break;
+        }
+        text.push(line.trim());
+        i += 1;
+    }
+    while text.last() == Some(&"") {
+        text.pop();
+    }
+    (text.join("\n"), i)
+}
+
+/// Indentation width with tabs counted as 8 columns
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 8 } else { 1 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+mainmenu "Sensor Node"
+
+menuconfig NETWORKING
+	bool "Networking support"
+	default y
+	help
+	  Enables the network stack.
+
+	  Second paragraph.
+
+config NET_IPV6
+	bool "IPv6"
+	depends on NETWORKING
+	select NET_ICMP
+
+config NET_ICMP
+	bool
+
+config NET_BUF_COUNT
+	int "Number of network buffers"
+	depends on NETWORKING
+	range 4 256
+	default 16
+
+config DEVICE_NAME
+	string "Device name"
+	default "node"
+
+choice LOG_BACKEND
+	prompt "Log backend"
+	default LOG_UART
+
+config LOG_UART
+	bool "UART"
+
+config LOG_RTT
+	bool "RTT"
+	depends on !NET_IPV6
+
+endchoice
+
+source "drivers/Kconfig"
+"#;
+
+    fn find<'a>(import: &'a KconfigImport, id: &str) -> &'a FeatureElement {
+        import.features.iter().find(|f| f.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_parse_symbols_and_help() {
+        let model = parse_kconfig(SAMPLE);
+        assert_eq!(model.mainmenu.as_deref(), Some("Sensor Node"));
+        assert_eq!(model.symbols.len(), 7);
+        assert_eq!(model.choices.len(), 1);
+
+        let net = &model.symbols[0];
+        assert!(net.is_menuconfig);
+        assert_eq!(net.default.as_deref(), Some("y"));
+        assert_eq!(
+            net.help.as_deref(),
+            Some("Enables the network stack.\n\nSecond paragraph.")
+        );
+        assert_eq!(model.symbols[3].range, Some(("4".to_string(), "256".to_string())));
+    }
+
+    #[test]
+    fn test_menuconfig_nesting_and_select() {
+        let import = import_kconfig(SAMPLE, &KconfigImportOptions::default());
+
+        let root = find(&import, "ROOT");
+        assert_eq!(root.title, "Sensor Node");
+
+        let ipv6 = find(&import, "F-NET_IPV6");
+        assert_eq!(ipv6.parent.as_deref(), Some("F-NETWORKING"));
+        assert_eq!(ipv6.tags.get("requires"), Some(&serde_json::json!("F-NET_ICMP")));
+        assert_eq!(ipv6.tags.get("kconfig"), Some(&serde_json::json!("NET_IPV6")));
+
+        let icmp = find(&import, "F-NET_ICMP");
+        assert_eq!(icmp.parent.as_deref(), Some("ROOT"));
+    }
+
+    #[test]
+    fn test_choice_becomes_xor_group() {
+        let import = import_kconfig(SAMPLE, &KconfigImportOptions::default());
+
+        let choice = find(&import, "F-LOG_BACKEND");
+        assert_eq!(choice.group.as_deref(), Some("XOR"));
+        assert!(choice.is_abstract());
+        assert_eq!(choice.tags.get("kconfig-default"), Some(&serde_json::json!("F-LOG_UART")));
+
+        let rtt = find(&import, "F-LOG_RTT");
+        assert_eq!(rtt.parent.as_deref(), Some("F-LOG_BACKEND"));
+        assert_eq!(rtt.tags.get("excludes"), Some(&serde_json::json!("F-NET_IPV6")));
+    }
+
+    #[test]
+    fn test_choice_member_keeps_menu_dependency() {
+        let source = "menuconfig NETWORKING\n\tbool \"Networking\"\n\n\
+                      choice NET_STACK\n\tprompt \"Stack\"\n\n\
+                      config NET_LWIP\n\tbool \"lwIP\"\n\tdepends on NETWORKING\n\n\
+                      config NET_NATIVE\n\tbool \"Native\"\n\n\
+                      endchoice\n";
+        let import = import_kconfig(source, &KconfigImportOptions::default());
+
+        let lwip = find(&import, "F-NET_LWIP");
+        assert_eq!(lwip.parent.as_deref(), Some("F-NET_STACK"));
+        assert_eq!(lwip.tags.get("requires"), Some(&serde_json::json!("F-NETWORKING")));
+        assert_eq!(find(&import, "F-NET_NATIVE").tags.get("requires"), None);
+    }
+
+    #[test]
+    fn test_int_and_string_become_parameters() {
+        let import = import_kconfig(SAMPLE, &KconfigImportOptions::default());
+
+        let net = find(&import, "F-NETWORKING");
+        let count = &net.parameters.as_ref().unwrap()["NET_BUF_COUNT"];
+        assert_eq!(count.param_type, "Integer");
+        assert_eq!(count.range, Some((4, 256)));
+        assert_eq!(count.default, serde_json::json!(16));
+
+        let root = find(&import, "ROOT");
+        let name = &root.parameters.as_ref().unwrap()["DEVICE_NAME"];
+        assert_eq!(name.param_type, "String");
+        assert_eq!(name.default, serde_json::json!("node"));
+    }
+
+    #[test]
+    fn test_unsupported_constructs_warn() {
+        let source = "config A\n\tbool \"A\"\n\tdepends on B || C\n\toption modules\nsource \"x\"\n";
+        let import = import_kconfig(source, &KconfigImportOptions::default());
+        assert!(import.warnings.iter().any(|w| w.contains("line 5") && w.contains("source")));
+        assert!(import.warnings.iter().any(|w| w.contains("line 4") && w.contains("option")));
+        assert!(import.warnings.iter().any(|w| w.contains("B || C")));
+    }
+}
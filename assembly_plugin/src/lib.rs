@@ -6,8 +6,33 @@ use petgraph::prelude::*;
 initiate_protocol!();
 
 // Import our typed element system
-mod types;
-use types::{Element, FeatureElement};
+pub mod types;
+use types::{Element, FeatureElement, LinkKind};
+
+// Import structured diagnostics shared by all validators
+mod diagnostics;
+mod rule_config;
+mod rule_language;
+mod model_graph;
+mod query;
+mod coverage;
+mod impact;
+mod variant_filter;
+mod fingerprint;
+mod model_diff;
+mod tag_schema;
+mod asil;
+mod interference;
+pub mod verification;
+mod xml;
+mod graph_export;
+mod sysml_export;
+mod sysml_import;
+use diagnostics::{has_errors, sort_diagnostics, Diagnostic};
+use rule_config::{apply_rule_config, collect_suppressions, rule_enabled, AppliedSuppression, RuleConfig, Suppression};
+use rule_language::{evaluate_user_rules, UserRule};
+use model_graph::{build_model_graph, ModelGraph};
+use tag_schema::{validate_tags, TagSchemas};
 
 // Import SAT solver and feature model validation
 mod sat_solver;
@@ -15,6 +40,12 @@ mod feature_validation;
 mod parameter_validation;
 mod constraint_parser;
 mod constraint_evaluator;
+mod kconfig_import;
+mod codegen;
+pub mod cargo_import;
+mod reqif;
+pub mod source_trace;
+mod requirements_csv;
 use feature_validation::{validate_feature_model, validate_configuration};
 use parameter_validation::validate_parameter_bindings;
 
@@ -30,6 +61,13 @@ pub struct Link {
     pub target: String,  // Target element ID
 }
 
+impl Link {
+    /// Typed link kind, `None` if the type string is not part of the metamodel
+    pub fn kind(&self) -> Option<LinkKind> {
+        LinkKind::parse(&self.link_type)
+    }
+}
+
 pub type Links = Vec<Link>;
 
 /// Active configuration ID
@@ -41,6 +79,18 @@ pub struct ValidationInput {
     pub registry: Registry,
     pub links: Links,
     pub active_config: ActiveConfig,
+    /// Per-rule enable/severity overrides, keyed by rule code
+    #[serde(default)]
+    pub rules: RuleConfig,
+    /// Suppressions in addition to those in element `tags.suppress`
+    #[serde(default)]
+    pub suppressions: Vec<Suppression>,
+    /// Project-specific rules in the rule language (see `rule_language`)
+    #[serde(default)]
+    pub user_rules: Vec<UserRule>,
+    /// Tag schemas keyed by element type (see `tag_schema`)
+    #[serde(default)]
+    pub tag_schemas: TagSchemas,
 }
 
 /// Validation result returned to Typst
@@ -49,6 +99,12 @@ pub struct ValidationResult {
     pub passed: bool,
     pub total_elements: usize,
     pub message: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Suppressions that hid at least one finding
+    pub suppressions: Vec<AppliedSuppression>,
+    /// Elements excluded because they are not part of the active configuration
+    #[serde(default)]
+    pub filtered_elements: usize,
 }
 
 // ============================================================================
@@ -58,41 +114,31 @@ pub struct ValidationResult {
 #[wasm_func]
 pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
     // Parse input JSON
-    let input: ValidationInput = match serde_json::from_slice(input_bytes) {
+    let mut input: ValidationInput = match serde_json::from_slice(input_bytes) {
         Ok(data) => data,
         Err(e) => {
             let error_result = ValidationResult {
                 passed: false,
                 total_elements: 0,
                 message: format!("Failed to parse input: {}", e),
+                diagnostics: Vec::new(),
+                suppressions: Vec::new(),
+                filtered_elements: 0,
             };
             return serde_json::to_vec(&error_result).unwrap_or_default();
         }
     };
 
-    let mut violations = Vec::new();
+    // Reduce the model to the product of the active configuration
+    let (filtered_elements, config_violation) =
+        match variant_filter::apply(&mut input.registry, &mut input.links, input.active_config.as_deref()) {
+            Ok(excluded) => (excluded, None),
+            Err(diagnostic) => (0, Some(*diagnostic)),
+        };
 
     // Lets convert our data to a graph
-    let mut graph = petgraph::Graph::new();
-
-    // Add nodes to the graph
-    let mut node_indices = HashMap::new();
-    for (id, element) in &input.registry {
-        let node = graph.add_node((id.clone(), element.clone()));
-        node_indices.insert(id.clone(), node);
-    }
-
-    // Add edges to the graph
-    for link in &input.links {
-        if let (Some(&source), Some(&target)) = (node_indices.get(&link.source), node_indices.get(&link.target)) {
-            graph.add_edge(source, target, link.link_type.clone());
-        } else {
-            violations.push(format!(
-                "Link references non-existent element: {} -> {}",
-                link.source, link.target
-            ));
-        }
-    }
+    let (graph, _node_indices, mut violations) = build_model_graph(&input.registry, &input.links);
+    violations.extend(config_violation);
 
     // All usecase must be traced to one or more requirements. Iterate over all use cases 
     // and check for trace links to requirements.
@@ -113,10 +159,14 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
                     }
                 }
                 if !traced {
-                    violations.push(format!(
-                        "Use case {} is not traced to any requirement",
-                        use_case.id
-                    ));
+                    violations.push(
+                        Diagnostic::new(
+                            "AL002",
+                            format!("Use case {} is not traced to any requirement", use_case.id),
+                        )
+                        .on(&use_case.id)
+                        .hint("Add a trace link from the use case to the requirements it motivates"),
+                    );
                 }
             }
         }
@@ -126,7 +176,9 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
     let mut seen_ids = HashSet::new();
     for (key, element) in &input.registry {
         if !seen_ids.insert(element.id()) {
-            violations.push(format!("Duplicate ID: {}", element.id()));
+            violations.push(
+                Diagnostic::new("AL003", format!("Duplicate ID: {}", element.id())).on(element.id()),
+            );
         }
         // Check that key matches element ID (configs use "CONFIG:" prefix)
         let expected_key = if matches!(element, Element::Config(_)) {
@@ -135,10 +187,16 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
             element.id().to_string()
         };
         if key != &expected_key {
-            violations.push(format!(
-                "Registry key mismatch: key='{}' but expected '{}'",
-                key, expected_key
-            ));
+            violations.push(
+                Diagnostic::new(
+                    "AL004",
+                    format!(
+                        "Registry key mismatch: key='{}' but expected '{}'",
+                        key, expected_key
+                    ),
+                )
+                .on(element.id()),
+            );
         }
     }
 
@@ -147,13 +205,21 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
         // Pattern match on Element enum - type-safe!
         if let Element::Feature(feature) = element {
             // Type-safe access to feature-specific fields
-            if let Some(parent_id) = &feature.parent {
-                if parent_id != "ROOT" && !input.registry.contains_key(parent_id) {
-                    violations.push(format!(
-                        "Feature {} references non-existent parent: {}",
-                        feature.id, parent_id
-                    ));
-                }
+            if let Some(parent_id) = &feature.parent
+                && parent_id != "ROOT"
+                && !input.registry.contains_key(parent_id)
+            {
+                violations.push(
+                    Diagnostic::new(
+                        "AL005",
+                        format!(
+                            "Feature {} references non-existent parent: {}",
+                            feature.id, parent_id
+                        ),
+                    )
+                    .on(&feature.id)
+                    .related([parent_id.as_str()]),
+                );
             }
 
             // Check XOR/OR group constraints
@@ -166,12 +232,20 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
                     .collect();
 
                 if children.len() < 2 {
-                    violations.push(format!(
-                        "Feature {} has {:?} group but only {} children (need >= 2)",
-                        feature.id,
-                        feature.group.as_ref().unwrap(),
-                        children.len()
-                    ));
+                    violations.push(
+                        Diagnostic::new(
+                            "AL006",
+                            format!(
+                                "Feature {} has {:?} group but only {} children (need >= 2)",
+                                feature.id,
+                                feature.group.as_ref().unwrap(),
+                                children.len()
+                            ),
+                        )
+                        .on(&feature.id)
+                        .related(children.iter().map(|c| c.id.as_str()))
+                        .hint("Add alternatives to the group or remove the group attribute"),
+                    );
                 }
             }
         }
@@ -183,10 +257,17 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
             // Type-safe access to SysML data via typed accessors
             for part in block.parts() {
                 if !input.registry.contains_key(&part.part_type) {
-                    violations.push(format!(
-                        "Block {} references non-existent part type: {}",
-                        block.id, part.part_type
-                    ));
+                    violations.push(
+                        Diagnostic::new(
+                            "AL007",
+                            format!(
+                                "Block {} references non-existent part type: {}",
+                                block.id, part.part_type
+                            ),
+                        )
+                        .on(&block.id)
+                        .related([part.part_type.as_str()]),
+                    );
                 }
             }
         }
@@ -200,25 +281,47 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
                     Some(Element::Feature(feature)) => {
                         // Check if feature is concrete
                         if feature.is_abstract() {
-                            violations.push(format!(
-                                "Config {} selects abstract feature: {}",
-                                config.id, feature_id
-                            ));
+                            violations.push(
+                                Diagnostic::new(
+                                    "AL008",
+                                    format!(
+                                        "Config {} selects abstract feature: {}",
+                                        config.id, feature_id
+                                    ),
+                                )
+                                .on(&config.id)
+                                .related([feature_id.as_str()])
+                                .hint("Select one of the feature's concrete children instead"),
+                            );
                         }
                     }
                     Some(other) => {
-                        violations.push(format!(
-                            "Config {} selects non-feature element: {} (type: {})",
-                            config.id,
-                            feature_id,
-                            other.type_name()
-                        ));
+                        violations.push(
+                            Diagnostic::new(
+                                "AL009",
+                                format!(
+                                    "Config {} selects non-feature element: {} (type: {})",
+                                    config.id,
+                                    feature_id,
+                                    other.type_name()
+                                ),
+                            )
+                            .on(&config.id)
+                            .related([feature_id.as_str()]),
+                        );
                     }
                     None => {
-                        violations.push(format!(
-                            "Config {} selects non-existent feature: {}",
-                            config.id, feature_id
-                        ));
+                        violations.push(
+                            Diagnostic::new(
+                                "AL010",
+                                format!(
+                                    "Config {} selects non-existent feature: {}",
+                                    config.id, feature_id
+                                ),
+                            )
+                            .on(&config.id)
+                            .related([feature_id.as_str()]),
+                        );
                     }
                 }
             }
@@ -234,41 +337,345 @@ pub fn validate_rules(input_bytes: &[u8]) -> Vec<u8> {
         .filter(|f| f.parent.is_none() || f.parent.as_deref() == Some("ROOT"))
         .collect();
 
-    if !root_features.is_empty() {
+    if !root_features.is_empty() && rule_enabled(&input.rules, "AL202") {
         // Use first root feature found (typically there should be only one)
         let root_id = root_features[0].id.clone();
         let fm_validation = validate_feature_model(&input.registry, &root_id);
 
-        if !fm_validation.is_consistent {
-            violations.push(format!(
-                "Feature model is INCONSISTENT (SAT check failed): {}",
-                fm_validation.message
-            ));
+        violations.extend(fm_validation.diagnostics);
+    }
+
+    // Rule 6: Check link kinds and endpoint types against the metamodel
+    for link in &input.links {
+        let (Some(source), Some(target)) =
+            (input.registry.get(&link.source), input.registry.get(&link.target))
+        else {
+            continue; // Already reported as non-existent
+        };
+        let Some(kind) = link.kind() else {
+            violations.push(
+                Diagnostic::new(
+                    "AL011",
+                    format!(
+                        "Link {} -> {} has unknown type '{}' (known types: {})",
+                        link.source,
+                        link.target,
+                        link.link_type,
+                        LinkKind::ALL.map(|k| k.as_str()).join(", ")
+                    ),
+                )
+                .on(&link.source)
+                .related([link.target.as_str()]),
+            );
+            continue;
+        };
+        let targets = kind.allowed_targets(source.type_name());
+        if targets.is_empty() {
+            violations.push(
+                Diagnostic::new(
+                    "AL012",
+                    format!(
+                        "Link {} -{}-> {}: '{}' links cannot originate from {} elements (allowed sources: {})",
+                        link.source,
+                        kind,
+                        link.target,
+                        kind,
+                        source.type_name(),
+                        kind.allowed_sources().join(", ")
+                    ),
+                )
+                .on(&link.source)
+                .related([link.target.as_str()]),
+            );
+        } else if !targets.contains(&target.type_name()) {
+            violations.push(
+                Diagnostic::new(
+                    "AL013",
+                    format!(
+                        "Link {} -{}-> {}: '{}' links from {} elements must target {}, not {}",
+                        link.source,
+                        kind,
+                        link.target,
+                        kind,
+                        source.type_name(),
+                        targets.join(" or "),
+                        target.type_name()
+                    ),
+                )
+                .on(&link.source)
+                .related([link.target.as_str()]),
+            );
+        }
+    }
+
+    // Rule 7: Requirement decomposition isolation (user guide Rule 2) - a requirement
+    // with derived children must not receive any incoming link except derives_from
+    for node_idx in graph.node_indices() {
+        let (req_id, element) = &graph[node_idx];
+        if !matches!(element, Element::Req(_)) {
+            continue;
+        }
+
+        let mut children = Vec::new();
+        let mut offending = Vec::new();
+        for edge in graph.edges_directed(node_idx, Direction::Incoming) {
+            let (source_id, _) = &graph[edge.source()];
+            if edge.weight() == LinkKind::DerivesFrom.as_str() {
+                children.push(source_id.as_str());
+            } else {
+                offending.push(format!("{} -{}->", source_id, edge.weight()));
+            }
+        }
+
+        if !children.is_empty() && !offending.is_empty() {
+            children.sort();
+            offending.sort();
+            let offending_sources: Vec<&str> = graph
+                .edges_directed(node_idx, Direction::Incoming)
+                .filter(|e| e.weight() != LinkKind::DerivesFrom.as_str())
+                .map(|e| graph[e.source()].0.as_str())
+                .collect();
+            violations.push(
+                Diagnostic::new(
+                    "AL014",
+                    format!(
+                        "Requirement {} is decomposed into [{}] but still has incoming links [{}]",
+                        req_id,
+                        children.join(", "),
+                        offending.join(", ")
+                    ),
+                )
+                .on(req_id)
+                .related(children.iter().copied().chain(offending_sources))
+                .hint("Move these links to the leaf requirements"),
+            );
+        }
+    }
+
+    // Rule 8: Requirement parentage (user guide Rule 1) - every requirement has exactly
+    // one belongs_to (feature) or derives_from (parent requirement)
+    let mut req_ids: Vec<&String> = input
+        .registry
+        .values()
+        .filter_map(|e| match e {
+            Element::Req(req) => Some(&req.id),
+            _ => None,
+        })
+        .collect();
+    req_ids.sort();
+    for req_id in req_ids {
+        let mut parents: Vec<(LinkKind, &str)> = input
+            .links
+            .iter()
+            .filter(|l| &l.source == req_id)
+            .filter_map(|l| match l.kind() {
+                Some(kind @ (LinkKind::BelongsTo | LinkKind::DerivesFrom)) => {
+                    Some((kind, l.target.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        if let Some(Element::Req(req)) = input.registry.get(req_id) {
+            parents.extend(req.parent.as_deref().map(|p| (LinkKind::BelongsTo, p)));
+            parents.extend(req.derives_from.as_deref().map(|p| (LinkKind::DerivesFrom, p)));
+        }
+        parents.sort();
+        parents.dedup();
+
+        match parents.len() {
+            1 => {}
+            0 => violations.push(
+                Diagnostic::new(
+                    "AL015",
+                    format!("Requirement {} has neither belongs_to nor derives_from", req_id),
+                )
+                .on(req_id)
+                .hint("Make it belong to a feature or derive from a parent requirement"),
+            ),
+            _ => violations.push(
+                Diagnostic::new(
+                    "AL016",
+                    format!(
+                        "Requirement {} has {} parents [{}]",
+                        req_id,
+                        parents.len(),
+                        parents
+                            .iter()
+                            .map(|(kind, target)| format!("{} {}", kind, target))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                )
+                .on(req_id)
+                .related(parents.iter().map(|(_, target)| *target))
+                .hint("Keep exactly one belongs_to or derives_from"),
+            ),
+        }
+    }
+
+    // Rule 9: Single feature root and acyclic trees (user guide Rule 3) - feature parent
+    // chains and derives_from chains must not loop and must end at the single root
+    // A root has no parent, or a parent that is not a feature (e.g. the "ROOT" sentinel)
+    let mut feature_ids: Vec<&str> = input
+        .registry
+        .values()
+        .filter_map(|e| e.as_feature())
+        .map(|f| f.id.as_str())
+        .collect();
+    feature_ids.sort();
+    let roots: Vec<&str> = feature_ids
+        .iter()
+        .copied()
+        .filter(|id| {
+            let parent = input.registry[*id].as_feature().and_then(|f| f.parent.as_deref());
+            parent.is_none_or(|p| !matches!(input.registry.get(p), Some(Element::Feature(_))))
+        })
+        .collect();
+    if let Some(&anchor) = roots.first().or(feature_ids.first()).filter(|_| roots.len() != 1) {
+        violations.push(
+            Diagnostic::new(
+                "AL017",
+                format!(
+                    "Feature model must have exactly one root feature, found {}{}",
+                    roots.len(),
+                    if roots.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" [{}]", roots.join(", "))
+                    }
+                ),
+            )
+            .on(anchor)
+            .related(roots.iter().copied().filter(|id| *id != anchor)),
+        );
+    }
+
+    for (kind, code, label) in [
+        (LinkKind::ChildOf, "AL018", "Feature parent cycle"),
+        (LinkKind::DerivesFrom, "AL019", "Requirement derives_from cycle"),
+    ] {
+        for cycle in find_cycles(&graph, kind) {
+            violations.push(
+                Diagnostic::new(code, format!("{}: {}", label, cycle.join(" -> ")))
+                    .on(&cycle[0])
+                    .related(cycle[1..cycle.len() - 1].iter().cloned()),
+            );
         }
     }
 
+    // Rule 10: Project-specific user rules
+    let user_rules: Vec<UserRule> = input
+        .user_rules
+        .iter()
+        .filter(|rule| rule_enabled(&input.rules, &rule.id))
+        .cloned()
+        .collect();
+    violations.extend(evaluate_user_rules(&user_rules, &graph));
+
+    // Rule 11: Tags conform to the tag schemas of their element type
+    violations.extend(validate_tags(&input.registry, &input.tag_schemas));
+
+    // Rule 12: ISO 26262 ASIL inheritance, decomposition and block allocation
+    violations.extend(asil::validate_asil(&graph));
+
+    // Rule 13: Freedom from interference on architecture connectors
+    violations.extend(interference::validate_interference(&input.registry));
+
+    // Apply rule configuration and per-element suppressions
+    let (suppressions, suppression_diagnostics) =
+        collect_suppressions(&input.registry, &input.suppressions);
+    violations.extend(suppression_diagnostics);
+    let custom_codes: Vec<&str> = input.user_rules.iter().map(|r| r.id.as_str()).collect();
+    let outcome = apply_rule_config(violations, &input.rules, &suppressions, &custom_codes);
+    let mut violations = outcome.diagnostics;
+    sort_diagnostics(&mut violations);
+
+    let suppressed: usize = outcome.suppressions.iter().map(|s| s.suppressed).sum();
+    let mut note = if suppressed > 0 {
+        format!(" ({} findings suppressed)", suppressed)
+    } else {
+        String::new()
+    };
+    if filtered_elements > 0 {
+        note.push_str(&format!(
+            " ({} elements not in configuration {} filtered out)",
+            filtered_elements,
+            input.active_config.as_deref().unwrap_or_default()
+        ));
+    }
     let result = ValidationResult {
-        passed: violations.is_empty(),
+        passed: !has_errors(&violations),
         total_elements: input.registry.len(),
         message: if violations.is_empty() {
             format!(
-                "✓ All validation rules passed! Validated {} elements, {} links",
+                "✓ All validation rules passed! Validated {} elements, {} links{}",
                 input.registry.len(),
-                input.links.len()
+                input.links.len(),
+                note
             )
         } else {
             format!(
-                "✗ Validation failed with {} violations:\n{}",
+                "{} Validation {} with {} findings{}:\n{}",
+                if has_errors(&violations) { "✗" } else { "✓" },
+                if has_errors(&violations) { "failed" } else { "passed" },
                 violations.len(),
-                violations.join("\n  - ")
+                note,
+                violations
+                    .iter()
+                    .map(Diagnostic::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n  - ")
             )
         },
+        diagnostics: violations,
+        suppressions: outcome.suppressions,
+        filtered_elements,
     };
 
     // Serialize result to JSON
     serde_json::to_vec(&result).unwrap_or_default()
 }
 
+/// Find cycles among edges of one link kind, each reported as a closed ID path
+/// (first ID repeated at the end), starting from the smallest ID in the cycle
+fn find_cycles(graph: &ModelGraph, kind: LinkKind) -> Vec<Vec<String>> {
+    let kind_graph = graph.filter_map(
+        |_, node| Some(node.0.clone()),
+        |_, weight| (weight == kind.as_str()).then_some(()),
+    );
+
+    let mut cycles = Vec::new();
+    for scc in petgraph::algo::tarjan_scc(&kind_graph) {
+        let is_cycle = scc.len() > 1 || kind_graph.contains_edge(scc[0], scc[0]);
+        if !is_cycle {
+            continue;
+        }
+        let members: HashSet<NodeIndex> = scc.iter().copied().collect();
+
+        // Walk the component along the smallest successor until a node repeats
+        let mut current = *scc.iter().min_by_key(|&&n| &kind_graph[n]).unwrap();
+        let mut walk = vec![current];
+        loop {
+            current = kind_graph
+                .neighbors(current)
+                .filter(|n| members.contains(n))
+                .min_by_key(|&n| &kind_graph[n])
+                .unwrap();
+            if let Some(pos) = walk.iter().position(|&n| n == current) {
+                walk.drain(..pos);
+                break;
+            }
+            walk.push(current);
+        }
+        let start = (0..walk.len()).min_by_key(|&i| &kind_graph[walk[i]]).unwrap();
+        walk.rotate_left(start);
+        walk.push(walk[0]);
+        cycles.push(walk.into_iter().map(|n| kind_graph[n].clone()).collect());
+    }
+    cycles.sort();
+    cycles
+}
+
 #[wasm_func]
 pub fn hello() -> Vec<u8> {
     b"Hello from AssemblyLine WASM validator!".to_vec()
@@ -286,6 +693,7 @@ pub struct FeatureModelValidationResult {
     pub num_features: usize,
     pub num_clauses: usize,
     pub details: String,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 /// Validate feature model consistency using SAT solver
@@ -309,7 +717,8 @@ pub struct FeatureModelValidationResult {
 ///   "message": "Feature model is CONSISTENT",
 ///   "num_features": 10,
 ///   "num_clauses": 25,
-///   "details": "..."
+///   "details": "...",
+///   "diagnostics": [{ "code": "AL202", "severity": "error", ... }]
 /// }
 /// ```
 #[wasm_func]
@@ -335,6 +744,7 @@ pub fn validate_feature_model_sat(input_bytes: &[u8]) -> Vec<u8> {
                 num_features: 0,
                 num_clauses: 0,
                 details: String::new(),
+                diagnostics: Vec::new(),
             };
             return serde_json::to_vec(&error_result).unwrap_or_default();
         }
@@ -348,6 +758,7 @@ pub fn validate_feature_model_sat(input_bytes: &[u8]) -> Vec<u8> {
         message: validation.message.clone(),
         num_features: validation.num_features,
         num_clauses: validation.num_clauses,
+        diagnostics: validation.diagnostics,
         details: if validation.is_consistent {
             format!(
                 "✓ Feature model is consistent - at least one valid configuration exists\n\
@@ -411,23 +822,26 @@ pub fn validate_configuration_sat(input_bytes: &[u8]) -> Vec<u8> {
                 num_features: 0,
                 num_clauses: 0,
                 details: String::new(),
+                diagnostics: Vec::new(),
             };
             return serde_json::to_vec(&error_result).unwrap_or_default();
         }
     };
 
     // Validate configuration
-    let (is_valid, message) = validate_configuration(
+    let validation = validate_configuration(
         &input.registry,
         &input.root_feature_id,
         &input.selected_features,
     );
+    let is_valid = validation.is_consistent;
 
     let result = FeatureModelValidationResult {
         is_consistent: is_valid,
-        message,
+        message: validation.message,
         num_features: input.selected_features.len(),
         num_clauses: 0,
+        diagnostics: validation.diagnostics,
         details: if is_valid {
             format!(
                 "✓ Configuration is valid\n\
@@ -472,7 +886,7 @@ pub fn validate_parameters(input_bytes: &[u8]) -> Vec<u8> {
             let error_result = parameter_validation::ParameterValidationResult {
                 is_valid: false,
                 message: format!("Failed to parse input: {}", e),
-                errors: vec![format!("JSON parse error: {}", e)],
+                diagnostics: Vec::new(),
                 num_features_checked: 0,
                 num_parameters_checked: 0,
             };
@@ -485,3 +899,1624 @@ pub fn validate_parameters(input_bytes: &[u8]) -> Vec<u8> {
 
     serde_json::to_vec(&result).unwrap_or_default()
 }
+
+// ============================================================================
+// Kconfig Import (WASM Export)
+// ============================================================================
+
+/// Result of a model import (Kconfig, Cargo, ...)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub success: bool,
+    pub message: String,
+    /// Imported elements keyed by ID (same shape as the registry)
+    pub registry: Registry,
+    /// Imported link records (formats that carry trace links)
+    #[serde(default)]
+    pub links: Vec<Link>,
+    pub warnings: Vec<String>,
+}
+
+/// Import a Kconfig tree as feature elements
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "source": "config NETWORKING\n\tbool \"Networking\"\n...",
+///   "root_id": "ROOT",
+///   "root_parent": null,
+///   "id_prefix": "F-"
+/// }
+/// ```
+#[wasm_func]
+pub fn import_kconfig(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        source: String,
+        #[serde(flatten)]
+        options: kconfig_import::KconfigImportOptions,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_result = ImportResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                registry: Registry::new(),
+                links: Vec::new(),
+                warnings: Vec::new(),
+            };
+            return serde_json::to_vec(&error_result).unwrap_or_default();
+        }
+    };
+
+    let import = kconfig_import::import_kconfig(&input.source, &input.options);
+    let registry: Registry = import
+        .features
+        .into_iter()
+        .map(|f| (f.id.clone(), Element::Feature(f)))
+        .collect();
+
+    let result = ImportResult {
+        success: true,
+        message: format!(
+            "Imported {} features from Kconfig ({} warnings)",
+            registry.len(),
+            import.warnings.len()
+        ),
+        registry,
+        links: Vec::new(),
+        warnings: import.warnings,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Build Artefact Generation (WASM Export)
+// ============================================================================
+
+/// Input for build artefact generation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildArtefactsInput {
+    pub registry: Registry,
+    pub config_id: String,
+}
+
+/// Result of build artefact generation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildArtefactsResult {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artefacts: Option<codegen::BuildArtefacts>,
+}
+
+/// Generate C header, Kconfig fragment, Cargo features and CMake cache for a configuration
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "config_id": "CFG-EU"
+/// }
+/// ```
+#[wasm_func]
+pub fn generate_build_artefacts(input_bytes: &[u8]) -> Vec<u8> {
+    let input: BuildArtefactsInput = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_result = BuildArtefactsResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                artefacts: None,
+            };
+            return serde_json::to_vec(&error_result).unwrap_or_default();
+        }
+    };
+
+    let result = match codegen::generate_build_artefacts(&input.registry, &input.config_id) {
+        Ok(artefacts) => BuildArtefactsResult {
+            success: true,
+            message: format!(
+                "Generated build artefacts for configuration '{}' ({} warnings)",
+                input.config_id,
+                artefacts.warnings.len()
+            ),
+            artefacts: Some(artefacts),
+        },
+        Err(e) => BuildArtefactsResult {
+            success: false,
+            message: e,
+            artefacts: None,
+        },
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Cargo Feature Import (WASM Export)
+// ============================================================================
+
+/// Import Cargo manifests (one package, or several workspace members) as feature elements
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "manifests": ["[package]\nname = \"netstack\"\n...", "..."],
+///   "root_id": "ROOT",
+///   "root_parent": null,
+///   "id_prefix": "F-"
+/// }
+/// ```
+#[wasm_func]
+pub fn import_cargo_manifest(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        manifests: Vec<String>,
+        #[serde(flatten)]
+        options: cargo_import::CargoImportOptions,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_result = ImportResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                registry: Registry::new(),
+                links: Vec::new(),
+                warnings: Vec::new(),
+            };
+            return serde_json::to_vec(&error_result).unwrap_or_default();
+        }
+    };
+
+    let import = cargo_import::import_cargo_manifests(&input.manifests, &input.options);
+    let registry: Registry = import
+        .features
+        .into_iter()
+        .map(|f| (f.id.clone(), Element::Feature(f)))
+        .collect();
+
+    let result = ImportResult {
+        success: true,
+        message: format!(
+            "Imported {} features from {} Cargo manifest(s) ({} warnings)",
+            registry.len(),
+            input.manifests.len(),
+            import.warnings.len()
+        ),
+        registry,
+        links: Vec::new(),
+        warnings: import.warnings,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Result of checking configurations against imported Cargo features
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CargoCheckResult {
+    pub passed: bool,
+    pub message: String,
+    pub checks: Vec<cargo_import::CargoConfigCheck>,
+}
+
+/// Check that every configuration maps to a feature set Cargo would accept
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... }
+/// }
+/// ```
+#[wasm_func]
+pub fn check_cargo_configurations(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_result = CargoCheckResult {
+                passed: false,
+                message: format!("Failed to parse input: {}", e),
+                checks: Vec::new(),
+            };
+            return serde_json::to_vec(&error_result).unwrap_or_default();
+        }
+    };
+
+    let checks = cargo_import::check_cargo_configurations(&input.registry);
+    let rejected = checks.iter().filter(|c| !c.accepted).count();
+
+    let result = CargoCheckResult {
+        passed: rejected == 0,
+        message: if rejected == 0 {
+            format!("✓ All {} configurations map to valid Cargo feature sets", checks.len())
+        } else {
+            format!(
+                "✗ {} of {} configurations map to feature sets Cargo would reject",
+                rejected,
+                checks.len()
+            )
+        },
+        checks,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Model Query (WASM Export)
+// ============================================================================
+
+/// Result of a model query
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub success: bool,
+    pub message: String,
+    /// Distinct matched element IDs, sorted
+    pub ids: Vec<String>,
+    /// Matches with the path from the start element
+    pub matches: Vec<query::QueryMatch>,
+}
+
+/// Run a path-pattern query over the model graph (see `query` module for the syntax)
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "query": "\"F-AUTH\" <-belongs_to|derives_from-+ req"
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "3 matches",
+///   "ids": ["REQ-1", "REQ-1.1", "REQ-1.2"],
+///   "matches": [{ "id": "REQ-1", "type": "req", "path": ["F-AUTH", "REQ-1"] }]
+/// }
+/// ```
+#[wasm_func]
+pub fn query(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        query: String,
+    }
+
+    let error_result = |message: String| QueryResult {
+        success: false,
+        message,
+        ids: Vec::new(),
+        matches: Vec::new(),
+    };
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = error_result(format!("Failed to parse input: {}", e));
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let parsed = match query::parse_query(&input.query) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let result = error_result(format!("Invalid query '{}': {}", input.query, e));
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let matches = query::run_query(&graph, &parsed);
+
+    let result = QueryResult {
+        success: true,
+        message: format!(
+            "{} matches ({} distinct elements)",
+            matches.matches.len(),
+            matches.ids.len()
+        ),
+        ids: matches.ids,
+        matches: matches.matches,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Coverage Report (WASM Export)
+// ============================================================================
+
+/// Result of a coverage analysis
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageResult {
+    pub success: bool,
+    pub message: String,
+    pub report: Option<coverage::CoverageReport>,
+}
+
+/// Compute per-requirement design, verification and trace coverage
+///
+/// With `active_config` set, only the elements of that configuration's product
+/// are analysed (see `variant_filter`).
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "active_config": "CFG-BASIC"
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "3/4 requirements covered (75.0%)",
+///   "report": {
+///     "requirements": [{ "id": "REQ-1", "designed": true, "verified": false, "traced": true,
+///                        "leaf": true, "covered": false, "missing": ["verification"], ... }],
+///     "totals": { "total": 4, "designed": 4, "verified": 3, "traced": 4, "covered": 3, "percent": 75.0 },
+///     "by_feature": { "F-AUTH": { ... } },
+///     "by_type": { "functional": { ... } },
+///     "by_safety": { "ASIL-B": { ... } },
+///     "uncovered": { "undesigned": [], "unverified": ["REQ-1"], "untraced": [] }
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn coverage_report(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        #[serde(default)]
+        active_config: ActiveConfig,
+    }
+
+    let mut input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = CoverageResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                report: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    if let Err(diagnostic) = variant_filter::apply(&mut input.registry, &mut input.links, input.active_config.as_deref()) {
+        let result = CoverageResult {
+            success: false,
+            message: diagnostic.message,
+            report: None,
+        };
+        return serde_json::to_vec(&result).unwrap_or_default();
+    }
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let report = coverage::coverage_report(&graph);
+
+    let result = CoverageResult {
+        success: true,
+        message: format!(
+            "{}/{} requirements covered ({:.1}%)",
+            report.totals.covered, report.totals.total, report.totals.percent
+        ),
+        report: Some(report),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Impact Analysis (WASM Export)
+// ============================================================================
+
+/// Result of an impact analysis
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImpactResult {
+    pub success: bool,
+    pub message: String,
+    pub report: Option<impact::ImpactReport>,
+}
+
+/// Compute the elements potentially affected by changing a set of elements
+///
+/// `follow` lists the link kinds to traverse and the direction (`incoming`
+/// = from link target to link source, `outgoing`, `both`). By default derived
+/// requirements, allocating blocks, satisfying diagrams/implementations and
+/// verifying test cases are followed. Configurations selecting an owning
+/// feature are included unless `include_configs` is false.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "changed": ["REQ-AUTH-001"],
+///   "follow": [{ "kind": "derives_from", "direction": "incoming" }],
+///   "include_configs": true
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "2 elements affected by 1 changed element",
+///   "report": {
+///     "changed": ["REQ-AUTH-001"],
+///     "unknown": [],
+///     "affected": {
+///       "test_case": [{ "id": "TC-AUTH", "path": ["REQ-AUTH-001", "TC-AUTH"], "via": ["verify"] }],
+///       "config": [{ "id": "CONFIG:FULL", "path": ["REQ-AUTH-001", "F-AUTH", "CONFIG:FULL"],
+///                    "via": ["belongs_to", "selected_by"] }]
+///     },
+///     "total": 2
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn impact_analysis(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        changed: Vec<String>,
+        #[serde(default = "impact::default_follow_rules")]
+        follow: Vec<impact::FollowRule>,
+        #[serde(default = "default_include_configs")]
+        include_configs: bool,
+    }
+
+    fn default_include_configs() -> bool {
+        true
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = ImpactResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                report: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let report = impact::analyze_impact(&graph, &input.changed, &input.follow, input.include_configs);
+
+    let mut message = format!(
+        "{} elements affected by {} changed elements",
+        report.total,
+        report.changed.len()
+    );
+    if !report.unknown.is_empty() {
+        message.push_str(&format!(" (unknown: {})", report.unknown.join(", ")));
+    }
+
+    let result = ImpactResult {
+        success: report.unknown.is_empty(),
+        message,
+        report: Some(report),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Suspect Links (WASM Export)
+// ============================================================================
+
+/// Result of creating a link baseline
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinkBaselineResult {
+    pub success: bool,
+    pub message: String,
+    pub baseline: Option<fingerprint::LinkBaseline>,
+}
+
+/// Record the current target content hash of every tracked link
+///
+/// The returned baseline is saved as JSON and passed to `detect_suspect_links`
+/// later. `kinds` defaults to `["satisfy", "verify"]`.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "kinds": ["satisfy", "verify"]
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Baseline records 2 links",
+///   "baseline": {
+///     "version": 1,
+///     "links": [{ "source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-001", "target_hash": "3f2a9c0d5e6b7a81" }]
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn create_link_baseline(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        #[serde(default = "fingerprint::default_tracked_kinds")]
+        kinds: Vec<LinkKind>,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = LinkBaselineResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                baseline: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let baseline = fingerprint::create_baseline(&input.registry, &input.links, &input.kinds);
+    let result = LinkBaselineResult {
+        success: true,
+        message: format!("Baseline records {} links", baseline.links.len()),
+        baseline: Some(baseline),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Result of suspect link detection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuspectLinksResult {
+    pub success: bool,
+    pub message: String,
+    pub report: Option<fingerprint::SuspectReport>,
+}
+
+/// List tracked links whose target changed since the link was last reviewed
+///
+/// `success` is false if the input cannot be parsed or any link is suspect.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "baseline": { "version": 1, "links": [ ... ] },
+///   "kinds": ["satisfy", "verify"]
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": false,
+///   "message": "1 of 2 links suspect",
+///   "report": {
+///     "checked": 2,
+///     "suspect": [{ "source": "TC-AUTH", "type": "verify", "target": "REQ-AUTH-001",
+///                   "reason": "changed", "baseline_hash": "3f2a9c0d5e6b7a81",
+///                   "current_hash": "9b1e44c2a07d3f65" }],
+///     "removed": []
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn detect_suspect_links(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        baseline: fingerprint::LinkBaseline,
+        #[serde(default = "fingerprint::default_tracked_kinds")]
+        kinds: Vec<LinkKind>,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = SuspectLinksResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                report: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    if input.baseline.version != fingerprint::BASELINE_VERSION {
+        let result = SuspectLinksResult {
+            success: false,
+            message: format!(
+                "Unsupported baseline version {} (expected {})",
+                input.baseline.version,
+                fingerprint::BASELINE_VERSION
+            ),
+            report: None,
+        };
+        return serde_json::to_vec(&result).unwrap_or_default();
+    }
+
+    let report = fingerprint::detect_suspect_links(&input.registry, &input.links, &input.baseline, &input.kinds);
+    let result = SuspectLinksResult {
+        success: report.suspect.is_empty(),
+        message: format!("{} of {} links suspect", report.suspect.len(), report.checked),
+        report: Some(report),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Model Diff (WASM Export)
+// ============================================================================
+
+/// Result of comparing two model snapshots
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelDiffResult {
+    pub success: bool,
+    pub message: String,
+    pub diff: Option<model_diff::ModelDiff>,
+}
+
+/// Compare two model snapshots (e.g. a released baseline and the current document)
+///
+/// Both snapshots have the shape of the `validate_rules` input; fields other
+/// than `registry` and `links` are ignored.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "old": { "registry": { ... }, "links": [ ... ] },
+///   "new": { "registry": { ... }, "links": [ ... ] }
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "1 added, 0 removed, 1 modified elements; 1 added, 0 removed links",
+///   "diff": {
+///     "added": [{ "id": "REQ-3", "type": "req", "title": "Lockout" }],
+///     "removed": [],
+///     "modified": [{ "id": "REQ-1", "type": "req", "title": "Login",
+///                    "changes": [{ "field": "tags.safety", "old": "QM", "new": "ASIL-B" }] }],
+///     "added_links": [{ "source": "REQ-3", "type": "derives_from", "target": "REQ-1" }],
+///     "removed_links": []
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn diff_models(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        old: model_diff::Snapshot,
+        new: model_diff::Snapshot,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = ModelDiffResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                diff: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let diff = model_diff::diff_models(&input.old, &input.new);
+    let result = ModelDiffResult {
+        success: true,
+        message: format!(
+            "{} added, {} removed, {} modified elements; {} added, {} removed links",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.modified.len(),
+            diff.added_links.len(),
+            diff.removed_links.len()
+        ),
+        diff: Some(diff),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Freedom from Interference (WASM Export)
+// ============================================================================
+
+/// Result of the freedom-from-interference analysis
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InterferenceResult {
+    pub success: bool,
+    pub message: String,
+    /// Mixed-criticality flows, with the documented mitigation if any
+    pub flows: Vec<interference::InterferenceFlow>,
+}
+
+/// List all flows from lower-ASIL into higher-ASIL blocks
+///
+/// `success` is false if any flow has no documented mitigation.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... }
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": false,
+///   "message": "2 mixed-criticality flows, 1 without safety mechanism",
+///   "flows": [{ "context": "BLK-ECU", "from": "infotainment.speedOut", "to": "brake.speedIn",
+///               "flow": "Speed", "from_block": "BLK-INFO", "to_block": "BLK-BRAKE",
+///               "from_asil": "QM", "to_asil": "ASIL-D", "mitigation": null }]
+/// }
+/// ```
+#[wasm_func]
+pub fn interference_analysis(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = InterferenceResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                flows: Vec::new(),
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let flows = interference::analyze_interference(&input.registry);
+    let unmitigated = flows.iter().filter(|f| f.mitigation.is_none()).count();
+    let result = InterferenceResult {
+        success: unmitigated == 0,
+        message: format!(
+            "{} mixed-criticality flows, {} without safety mechanism",
+            flows.len(),
+            unmitigated
+        ),
+        flows,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Verification Status (WASM Export)
+// ============================================================================
+
+/// Result of the verification status roll-up
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerificationStatusResult {
+    pub success: bool,
+    pub message: String,
+    pub report: Option<verification::VerificationReport>,
+}
+
+/// Compute verification status of test cases, requirements and features
+///
+/// Results can be passed already matched (`results`) and/or as JUnit XML
+/// report texts (`junit`), which are matched to `test_case` IDs using
+/// `match` (default: the `name` attribute equals the ID). `success` is
+/// false if any test case failed. Report files can be read natively with
+/// `assemblyline verify <report.xml>...`.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "results": [{ "test_case": "TC-1", "status": "passed" }],
+///   "junit": ["<testsuites>...</testsuites>"],
+///   "match": { "attribute": "name", "pattern": "test_{id}" }
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": false,
+///   "message": "12 test cases: 9 passed, 1 failed, 0 skipped, 2 not run",
+///   "report": {
+///     "test_cases": { "TC-1": { "status": "passed", "runs": 1, "message": null } },
+///     "requirements": { "REQ-1": { "status": "failed", "tests": { "passed": 3, "failed": 1 } } },
+///     "features": { "F-AUTH": { "status": "not_run", "tests": { "passed": 4, "not_run": 2 } } },
+///     "unmatched": ["auth::test_helper"]
+///   }
+/// }
+/// ```
+#[wasm_func]
+pub fn verification_status(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Vec<Link>,
+        #[serde(default)]
+        results: Vec<verification::TestResult>,
+        #[serde(default)]
+        junit: Vec<String>,
+        #[serde(default, rename = "match")]
+        match_options: verification::JUnitMatch,
+    }
+
+    let failure = |message: String| {
+        let result = VerificationStatusResult { success: false, message, report: None };
+        serde_json::to_vec(&result).unwrap_or_default()
+    };
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => return failure(format!("Failed to parse input: {}", e)),
+    };
+
+    let mut cases = Vec::new();
+    for (i, xml) in input.junit.iter().enumerate() {
+        match verification::parse_junit(xml) {
+            Ok(parsed) => cases.extend(parsed),
+            Err(e) => return failure(format!("Invalid JUnit report {}: {}", i + 1, e)),
+        }
+    }
+    let report = verification::junit_report(&input.registry, &input.links, &cases, input.results, &input.match_options);
+
+    let count = |status| report.test_cases.values().filter(|t| t.status == status).count();
+    let failed = count(verification::VerificationStatus::Failed);
+    let result = VerificationStatusResult {
+        success: failed == 0,
+        message: format!(
+            "{} test cases: {} passed, {} failed, {} skipped, {} not run",
+            report.test_cases.len(),
+            count(verification::VerificationStatus::Passed),
+            failed,
+            count(verification::VerificationStatus::Skipped),
+            count(verification::VerificationStatus::NotRun)
+        ),
+        report: Some(report),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Source Trace Scan (WASM Export)
+// ============================================================================
+
+/// Result of a source annotation scan
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceTraceResult {
+    pub success: bool,
+    pub message: String,
+    /// Implementation elements keyed by ID (same shape as the registry)
+    pub registry: Registry,
+    /// `satisfy` links from implementation elements to requirements
+    pub links: Vec<Link>,
+    /// Annotations referencing IDs that are not requirements in the registry
+    pub unknown: Vec<source_trace::TraceAnnotation>,
+}
+
+/// Scan source files for trace annotations (`// @satisfies REQ-...`)
+///
+/// Only files whose extension is listed in `extensions` are scanned. Local
+/// source trees can be scanned natively with `assemblyline trace <dir>`.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "files": { "src/auth.rs": "// @satisfies REQ-AUTH-001a\nfn login() {}" },
+///   "keywords": ["satisfies", "implements"],
+///   "id_prefix": "IMPL-"
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Scanned 1 files: 1 implementation elements, 1 satisfy links, 0 unknown references",
+///   "registry": { "IMPL-src/auth.rs": { "type": "implementation", ... } },
+///   "links": [{ "source": "IMPL-src/auth.rs", "type": "satisfy", "target": "REQ-AUTH-001a" }],
+///   "unknown": []
+/// }
+/// ```
+#[wasm_func]
+pub fn scan_source_annotations(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        files: std::collections::BTreeMap<String, String>,
+        #[serde(flatten)]
+        options: source_trace::SourceTraceOptions,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_result = SourceTraceResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                registry: Registry::new(),
+                links: Vec::new(),
+                unknown: Vec::new(),
+            };
+            return serde_json::to_vec(&error_result).unwrap_or_default();
+        }
+    };
+
+    let extensions = &input.options.extensions;
+    let files = input
+        .files
+        .into_iter()
+        .filter(|(path, _)| path.rsplit_once('.').is_some_and(|(_, e)| extensions.iter().any(|x| x == e)))
+        .collect();
+    let scan = source_trace::scan_sources(&files, Some(&input.registry), &input.options);
+
+    let result = SourceTraceResult {
+        success: true,
+        message: format!(
+            "Scanned {} files: {} implementation elements, {} satisfy links, {} unknown references",
+            scan.files_scanned,
+            scan.registry.len(),
+            scan.links.len(),
+            scan.unknown.len()
+        ),
+        registry: scan.registry.into_iter().collect(),
+        links: scan.links,
+        unknown: scan.unknown,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// ReqIF Exchange (WASM Export)
+// ============================================================================
+
+/// Import a ReqIF 1.2 document as requirement and feature elements and links
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "reqif": "<?xml version=\"1.0\"?><REQ-IF ...>...</REQ-IF>",
+///   "relation_types": { "refines": "derives_from" },
+///   "default_link_type": "trace",
+///   "root_parent": null
+/// }
+/// ```
+#[wasm_func]
+pub fn import_reqif(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        reqif: String,
+        #[serde(flatten)]
+        options: reqif::ReqifOptions,
+    }
+
+    let failure = |message: String| {
+        let error_result = ImportResult {
+            success: false,
+            message,
+            registry: Registry::new(),
+            links: Vec::new(),
+            warnings: Vec::new(),
+        };
+        serde_json::to_vec(&error_result).unwrap_or_default()
+    };
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => return failure(format!("Failed to parse input: {}", e)),
+    };
+
+    let import = match reqif::import_reqif(&input.reqif, &input.options) {
+        Ok(import) => import,
+        Err(e) => return failure(format!("Invalid ReqIF document: {}", e)),
+    };
+
+    let result = ImportResult {
+        success: true,
+        message: format!(
+            "Imported {} elements and {} links from ReqIF ({} warnings)",
+            import.registry.len(),
+            import.links.len(),
+            import.warnings.len()
+        ),
+        registry: import.registry.into_iter().collect(),
+        links: import.links,
+        warnings: import.warnings,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Result of a ReqIF export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReqifExportResult {
+    pub success: bool,
+    pub message: String,
+    pub reqif: Option<String>,
+}
+
+/// Export requirements, their features and the links between them as ReqIF 1.2
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "relation_types": { "refines": "derives_from" },
+///   "last_change": "2025-01-31T12:00:00Z",
+///   "title": "Door ECU requirements"
+/// }
+/// ```
+#[wasm_func]
+pub fn export_reqif(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Vec<Link>,
+        #[serde(flatten)]
+        options: reqif::ReqifOptions,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = ReqifExportResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                reqif: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let requirements = input.registry.values().filter(|e| e.as_req().is_some()).count();
+    let result = ReqifExportResult {
+        success: true,
+        message: format!("Exported {} requirements to ReqIF", requirements),
+        reqif: Some(reqif::export_reqif(&input.registry, &input.links, &input.options)),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Graph Export (WASM Export)
+// ============================================================================
+
+/// Result of a graph export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphExportResult {
+    pub success: bool,
+    pub message: String,
+    pub text: Option<String>,
+}
+
+/// Input shared by the graph exporters
+#[derive(Deserialize)]
+struct GraphExportInput {
+    registry: Registry,
+    #[serde(default)]
+    links: Links,
+    #[serde(default)]
+    active_config: ActiveConfig,
+    #[serde(flatten)]
+    options: graph_export::GraphExportOptions,
+}
+
+/// Build the (configuration-filtered) graph and render it with `render`
+fn export_graph(
+    input_bytes: &[u8],
+    format: &str,
+    render: fn(&ModelGraph, &graph_export::GraphExportOptions) -> String,
+) -> Vec<u8> {
+    let failure = |message: String| {
+        let result = GraphExportResult { success: false, message, text: None };
+        serde_json::to_vec(&result).unwrap_or_default()
+    };
+
+    let mut input: GraphExportInput = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => return failure(format!("Failed to parse input: {}", e)),
+    };
+
+    if let Err(diagnostic) = variant_filter::apply(&mut input.registry, &mut input.links, input.active_config.as_deref()) {
+        return failure(diagnostic.message);
+    }
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let result = GraphExportResult {
+        success: true,
+        message: format!(
+            "Exported {} graph ({} nodes, {} edges before filtering)",
+            format,
+            graph.node_count(),
+            graph.edge_count()
+        ),
+        text: Some(render(&graph, &input.options)),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Export the element/link graph as GraphML (for yEd, Gephi)
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "active_config": "CFG-BASIC",
+///   "element_types": ["req", "test_case"],
+///   "link_kinds": ["verify"]
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Exported GraphML graph (42 nodes, 57 edges before filtering)",
+///   "text": "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml ...>...</graphml>\n"
+/// }
+/// ```
+#[wasm_func]
+pub fn export_graphml(input_bytes: &[u8]) -> Vec<u8> {
+    export_graph(input_bytes, "GraphML", graph_export::to_graphml)
+}
+
+/// Export the element/link graph as a Graphviz DOT digraph
+///
+/// Input as for `export_graphml`; `text` holds the DOT source.
+#[wasm_func]
+pub fn export_dot(input_bytes: &[u8]) -> Vec<u8> {
+    export_graph(input_bytes, "DOT", graph_export::to_dot)
+}
+
+// ============================================================================
+// SysML v2 Exchange (WASM Export)
+// ============================================================================
+
+/// Result of the SysML v2 export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SysmlExportResult {
+    pub success: bool,
+    pub message: String,
+    pub text: Option<String>,
+}
+
+/// Export block definitions and internal block diagrams as SysML v2 textual notation
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "package": "AssemblyLine"
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Exported 12 block definitions and 3 internal block diagrams",
+///   "text": "package AssemblyLine {\n    private import ScalarValues::*;\n..."
+/// }
+/// ```
+#[wasm_func]
+pub fn export_sysml_v2(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        #[serde(flatten)]
+        options: sysml_export::SysmlExportOptions,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = SysmlExportResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                text: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let count = |type_name: &str| input.registry.values().filter(|e| e.type_name() == type_name).count();
+    let result = SysmlExportResult {
+        success: true,
+        message: format!(
+            "Exported {} block definitions and {} internal block diagrams",
+            count("block_definition"),
+            count("internal_block_diagram")
+        ),
+        text: Some(sysml_export::to_sysml_v2(&input.registry, &input.links, &input.options)),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Import the structural subset of SysML v2 textual notation as block definitions
+///
+/// Unsupported statements are skipped and listed in `warnings` with their line
+/// numbers.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "text": "package Supplier {\n    part def Pump { ... }\n}",
+///   "id_prefix": "SUP-"
+/// }
+/// ```
+#[wasm_func]
+pub fn import_sysml_v2(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        text: String,
+        #[serde(flatten)]
+        options: sysml_import::SysmlImportOptions,
+    }
+
+    let failure = |message: String| {
+        let error_result = ImportResult {
+            success: false,
+            message,
+            registry: Registry::new(),
+            links: Vec::new(),
+            warnings: Vec::new(),
+        };
+        serde_json::to_vec(&error_result).unwrap_or_default()
+    };
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => return failure(format!("Failed to parse input: {}", e)),
+    };
+
+    let import = match sysml_import::import_sysml_v2(&input.text, &input.options) {
+        Ok(import) => import,
+        Err(e) => return failure(format!("Invalid SysML v2 text: {}", e)),
+    };
+
+    let result = ImportResult {
+        success: true,
+        message: format!(
+            "Imported {} elements and {} satisfy links from SysML v2 ({} unsupported statements)",
+            import.registry.len(),
+            import.links.len(),
+            import.warnings.len()
+        ),
+        registry: import.registry.into_iter().collect(),
+        links: import.links,
+        warnings: import.warnings,
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+// ============================================================================
+// Requirements CSV Review (WASM Export)
+// ============================================================================
+
+/// Result of the requirements CSV export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequirementsCsvExportResult {
+    pub success: bool,
+    pub message: String,
+    pub csv: Option<String>,
+}
+
+/// Export the requirement set as a CSV review spreadsheet
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ]
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Exported 42 requirements to CSV",
+///   "csv": "id,title,body,type,safety,security,belongs_to,derives_from,satisfied_by,verified_by,comment\n..."
+/// }
+/// ```
+#[wasm_func]
+pub fn export_requirements_csv(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+    }
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => {
+            let result = RequirementsCsvExportResult {
+                success: false,
+                message: format!("Failed to parse input: {}", e),
+                csv: None,
+            };
+            return serde_json::to_vec(&result).unwrap_or_default();
+        }
+    };
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let requirements = input.registry.values().filter(|e| e.as_req().is_some()).count();
+    let result = RequirementsCsvExportResult {
+        success: true,
+        message: format!("Exported {} requirements to CSV", requirements),
+        csv: Some(requirements_csv::export_requirements_csv(&graph)),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+/// Result of reading an edited requirements CSV
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequirementsCsvImportResult {
+    pub success: bool,
+    pub message: String,
+    /// Changes per requirement, in sheet order
+    pub patches: Vec<requirements_csv::RequirementPatch>,
+    /// Registry with the patches applied (absent when the sheet is rejected)
+    pub registry: Option<Registry>,
+    /// Links with the patched `belongs_to`/`derives_from` records (absent when the sheet is rejected)
+    pub links: Option<Links>,
+    /// Rejected rows, e.g. `row 7: unknown requirement ID 'REQ-99'`
+    pub errors: Vec<String>,
+}
+
+/// Read an edited requirements CSV back as a patch against the registry
+///
+/// The whole sheet is rejected if any row names an unknown requirement or a
+/// broken `belongs_to`/`derives_from` reference, or leaves a requirement with
+/// both or neither of them.
+///
+/// # Input JSON Format
+/// ```json
+/// {
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "csv": "id,title,safety,comment\nREQ-1,Login,ASIL-C,Raise to C?\n"
+/// }
+/// ```
+///
+/// # Output JSON Format
+/// ```json
+/// {
+///   "success": true,
+///   "message": "1 requirements changed, 1 review comments",
+///   "patches": [{"id": "REQ-1", "row": 2, "tags": {"safety": "ASIL-C"}, "comment": "Raise to C?"}],
+///   "registry": { ... },
+///   "links": [ ... ],
+///   "errors": []
+/// }
+/// ```
+#[wasm_func]
+pub fn import_requirements_csv(input_bytes: &[u8]) -> Vec<u8> {
+    #[derive(Deserialize)]
+    struct Input {
+        registry: Registry,
+        #[serde(default)]
+        links: Links,
+        csv: String,
+    }
+
+    let failure = |message: String, errors: Vec<String>| {
+        let error_result = RequirementsCsvImportResult {
+            success: false,
+            message,
+            patches: Vec::new(),
+            registry: None,
+            links: None,
+            errors,
+        };
+        serde_json::to_vec(&error_result).unwrap_or_default()
+    };
+
+    let input: Input = match serde_json::from_slice(input_bytes) {
+        Ok(data) => data,
+        Err(e) => return failure(format!("Failed to parse input: {}", e), Vec::new()),
+    };
+
+    let (graph, _, _) = build_model_graph(&input.registry, &input.links);
+    let patches = match requirements_csv::import_requirements_csv(&input.csv, &graph) {
+        Ok(patches) => patches,
+        Err(errors) => return failure(format!("Rejected CSV: {} invalid rows", errors.len()), errors),
+    };
+
+    let (mut registry, mut links) = (input.registry, input.links);
+    requirements_csv::apply_requirements_patch(&mut registry, &mut links, &patches);
+    let result = RequirementsCsvImportResult {
+        success: true,
+        message: format!(
+            "{} requirements changed, {} review comments",
+            patches.len(),
+            patches.iter().filter(|p| p.comment.is_some()).count()
+        ),
+        patches,
+        registry: Some(registry),
+        links: Some(links),
+        errors: Vec::new(),
+    };
+
+    serde_json::to_vec(&result).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run_validation(input: serde_json::Value) -> ValidationResult {
+        let output = validate_rules(&serde_json::to_vec(&input).unwrap());
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    fn findings<'a>(result: &'a ValidationResult, code: &str) -> Vec<&'a Diagnostic> {
+        result.diagnostics.iter().filter(|d| d.code == code).collect()
+    }
+
+    fn decomposition_model(extra_links: serde_json::Value) -> serde_json::Value {
+        let mut links = vec![
+            json!({"source": "REQ-1", "type": "belongs_to", "target": "ROOT"}),
+            json!({"source": "REQ-1.1", "type": "derives_from", "target": "REQ-1"}),
+            json!({"source": "REQ-1.2", "type": "derives_from", "target": "REQ-1"}),
+            json!({"source": "TC-1", "type": "verify", "target": "REQ-1.1"}),
+        ];
+        links.extend(extra_links.as_array().unwrap().iter().cloned());
+        json!({
+            "registry": {
+                "ROOT": {"type": "feature", "id": "ROOT", "parent": null},
+                "REQ-1": {"type": "req", "id": "REQ-1"},
+                "REQ-1.1": {"type": "req", "id": "REQ-1.1"},
+                "REQ-1.2": {"type": "req", "id": "REQ-1.2"},
+                "TC-1": {"type": "test_case", "id": "TC-1"},
+                "TC-2": {"type": "test_case", "id": "TC-2"}
+            },
+            "links": links
+        })
+    }
+
+    #[test]
+    fn test_rule7_links_on_leaf_requirements_pass() {
+        let result = run_validation(decomposition_model(json!([
+            {"source": "TC-2", "type": "verify", "target": "REQ-1.2"}
+        ])));
+        assert!(findings(&result, "AL014").is_empty(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn test_rule7_links_on_decomposed_requirement_fail() {
+        let result = run_validation(decomposition_model(json!([
+            {"source": "TC-2", "type": "verify", "target": "REQ-1"}
+        ])));
+        let found = findings(&result, "AL014");
+        assert_eq!(found.len(), 1, "{:?}", result.diagnostics);
+        assert_eq!(found[0].element_id.as_deref(), Some("REQ-1"));
+        assert_eq!(found[0].related_ids, vec!["REQ-1.1", "REQ-1.2", "TC-2"]);
+        assert!(found[0].message.contains("[TC-2 -verify->]"), "{}", found[0].message);
+        assert!(!result.passed);
+    }
+
+    fn feature(id: &str, parent: Option<&str>) -> serde_json::Value {
+        json!({"type": "feature", "id": id, "parent": parent})
+    }
+
+    #[test]
+    fn test_rule8_requirement_parentage() {
+        let result = run_validation(json!({
+            "registry": {
+                "ROOT": feature("ROOT", None),
+                "REQ-1": {"type": "req", "id": "REQ-1"},
+                "REQ-2": {"type": "req", "id": "REQ-2"},
+                "REQ-3": {"type": "req", "id": "REQ-3"}
+            },
+            "links": [
+                {"source": "REQ-1", "type": "belongs_to", "target": "ROOT"},
+                {"source": "REQ-3", "type": "belongs_to", "target": "ROOT"},
+                {"source": "REQ-3", "type": "derives_from", "target": "REQ-1"}
+            ]
+        }));
+
+        let orphans = findings(&result, "AL015");
+        assert_eq!(orphans.len(), 1, "{:?}", result.diagnostics);
+        assert_eq!(orphans[0].element_id.as_deref(), Some("REQ-2"));
+
+        let two_parents = findings(&result, "AL016");
+        assert_eq!(two_parents.len(), 1, "{:?}", result.diagnostics);
+        assert_eq!(two_parents[0].element_id.as_deref(), Some("REQ-3"));
+        assert_eq!(two_parents[0].related_ids, vec!["REQ-1", "ROOT"]);
+    }
+
+    #[test]
+    fn test_rule9_root_with_children_passes() {
+        let result = run_validation(json!({
+            "registry": {
+                "ROOT": feature("ROOT", None),
+                "F-A": feature("F-A", Some("ROOT")),
+                "F-B": feature("F-B", Some("ROOT"))
+            },
+            "links": []
+        }));
+        assert!(result.passed, "{:?}", result.diagnostics);
+        assert!(findings(&result, "AL017").is_empty());
+    }
+
+    #[test]
+    fn test_rule9_multiple_roots_and_cycles() {
+        let result = run_validation(json!({
+            "registry": {
+                "ROOT": feature("ROOT", None),
+                "F-OTHER": feature("F-OTHER", None),
+                "F-A": feature("F-A", Some("F-B")),
+                "F-B": feature("F-B", Some("F-A"))
+            },
+            "links": []
+        }));
+
+        let roots = findings(&result, "AL017");
+        assert_eq!(roots.len(), 1, "{:?}", result.diagnostics);
+        assert_eq!(roots[0].element_id.as_deref(), Some("F-OTHER"));
+        assert_eq!(roots[0].related_ids, vec!["ROOT"]);
+        assert!(roots[0].message.contains("found 2 [F-OTHER, ROOT]"), "{}", roots[0].message);
+
+        let cycles = findings(&result, "AL018");
+        assert_eq!(cycles.len(), 1, "{:?}", result.diagnostics);
+        assert!(cycles[0].message.contains("F-A -> F-B -> F-A"), "{}", cycles[0].message);
+    }
+}
@@ -0,0 +1,277 @@
+//! Model Diff
+//!
+//! Compares two model snapshots (registry + links, e.g. the JSON passed to
+//! `validate_rules` for baseline 2.3 and for the current document) and reports
+//! added, removed and modified elements with field-level detail, plus added and
+//! removed links. Output is sorted so a change-log chapter renders stably.
+//!
+//! Fields are compared after the same normalisation as content fingerprints,
+//! so whitespace-only edits of a body do not show up as modifications.
+
+use crate::fingerprint::content_hash;
+use crate::types::Element;
+use crate::{Link, Registry};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Model snapshot; accepts any `ValidationInput`-shaped JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub registry: Registry,
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+/// Element identity as shown in a change log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementSummary {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub element_type: String,
+    pub title: String,
+}
+
+/// Change of one field
+///
+/// `field` is a top-level field name (`title`, `parent`, `derives_from`,
+/// `selected`, `body`, ...) or a dotted path for tags and bindings
+/// (`tags.safety`, `bindings.F-CACHE.size`). `old`/`new` are None when the
+/// field was added or removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+    /// For list fields such as `selected`: entries added
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    /// For list fields such as `selected`: entries removed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
+
+/// A modified element
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementChange {
+    #[serde(flatten)]
+    pub element: ElementSummary,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A link record in the diff
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LinkChange {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub link_type: String,
+    pub target: String,
+}
+
+/// Differences between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDiff {
+    pub added: Vec<ElementSummary>,
+    pub removed: Vec<ElementSummary>,
+    pub modified: Vec<ElementChange>,
+    pub added_links: Vec<LinkChange>,
+    pub removed_links: Vec<LinkChange>,
+}
+
+impl ModelDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.added_links.is_empty()
+            && self.removed_links.is_empty()
+    }
+}
+
+fn summary(id: &str, element: &Element) -> ElementSummary {
+    ElementSummary {
+        id: id.to_string(),
+        element_type: element.type_name().to_string(),
+        title: element.title().to_string(),
+    }
+}
+
+/// Compare two snapshots
+pub fn diff_models(old: &Snapshot, new: &Snapshot) -> ModelDiff {
+    let mut diff = ModelDiff::default();
+
+    let old_ids: BTreeSet<&String> = old.registry.keys().collect();
+    let new_ids: BTreeSet<&String> = new.registry.keys().collect();
+    for id in new_ids.difference(&old_ids) {
+        diff.added.push(summary(id, &new.registry[*id]));
+    }
+    for id in old_ids.difference(&new_ids) {
+        diff.removed.push(summary(id, &old.registry[*id]));
+    }
+    for id in old_ids.intersection(&new_ids) {
+        let changes = diff_elements(&old.registry[*id], &new.registry[*id]);
+        if !changes.is_empty() {
+            diff.modified.push(ElementChange {
+                element: summary(id, &new.registry[*id]),
+                changes,
+            });
+        }
+    }
+
+    let link_set = |links: &[Link]| -> BTreeSet<LinkChange> {
+        links
+            .iter()
+            .map(|l| LinkChange {
+                source: l.source.clone(),
+                link_type: l.link_type.clone(),
+                target: l.target.clone(),
+            })
+            .collect()
+    };
+    let old_links = link_set(&old.links);
+    let new_links = link_set(&new.links);
+    diff.added_links = new_links.difference(&old_links).cloned().collect();
+    diff.removed_links = old_links.difference(&new_links).cloned().collect();
+
+    diff
+}
+
+/// Field-level changes between two versions of an element
+pub fn diff_elements(old: &Element, new: &Element) -> Vec<FieldChange> {
+    let as_object = |element: &Element| match serde_json::to_value(element) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let old = as_object(old);
+    let new = as_object(new);
+
+    let mut changes = Vec::new();
+    let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for field in fields {
+        let (a, b) = (old.get(field), new.get(field));
+        match field.as_str() {
+            "tags" => diff_nested("tags", a, b, 1, &mut changes),
+            "bindings" => diff_nested("bindings", a, b, 2, &mut changes),
+            _ => push_change(field, a, b, &mut changes),
+        }
+    }
+    changes
+}
+
+/// Compare objects key by key, descending `depth` levels
+fn diff_nested(prefix: &str, old: Option<&Value>, new: Option<&Value>, depth: usize, changes: &mut Vec<FieldChange>) {
+    let object = |value: Option<&Value>| match value {
+        Some(Value::Object(map)) => Some(map.clone()),
+        None | Some(Value::Null) => Some(Map::new()),
+        Some(_) => None,
+    };
+    let (Some(a), Some(b)) = (object(old), object(new)) else {
+        return push_change(prefix, old, new, changes);
+    };
+    if depth == 0 {
+        return push_change(prefix, old, new, changes);
+    }
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    for key in keys {
+        diff_nested(&format!("{}.{}", prefix, key), a.get(key), b.get(key), depth - 1, changes);
+    }
+}
+
+/// Record a change unless both values are equal after normalisation
+fn push_change(field: &str, old: Option<&Value>, new: Option<&Value>, changes: &mut Vec<FieldChange>) {
+    let old = old.filter(|v| !v.is_null());
+    let new = new.filter(|v| !v.is_null());
+    if old.map(content_hash) == new.map(content_hash) {
+        return;
+    }
+
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    if let (Some(Value::Array(a)), Some(Value::Array(b))) = (old, new) {
+        let a: BTreeSet<&str> = a.iter().filter_map(Value::as_str).collect();
+        let b: BTreeSet<&str> = b.iter().filter_map(Value::as_str).collect();
+        added = b.difference(&a).map(|s| s.to_string()).collect();
+        removed = a.difference(&b).map(|s| s.to_string()).collect();
+    }
+    changes.push(FieldChange {
+        field: field.to_string(),
+        old: old.cloned(),
+        new: new.cloned(),
+        added,
+        removed,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_added_removed_and_link_changes() {
+        let old: Snapshot = serde_json::from_value(json!({
+            "registry": {
+                "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login"},
+                "REQ-2": {"type": "req", "id": "REQ-2", "title": "Logout"}
+            },
+            "links": [{"source": "REQ-2", "type": "derives_from", "target": "REQ-1"}],
+            "active_config": null
+        }))
+        .unwrap();
+        let new: Snapshot = serde_json::from_value(json!({
+            "registry": {
+                "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login", "body": "  The user shall\nlog in "},
+                "REQ-3": {"type": "req", "id": "REQ-3", "title": "Lockout"}
+            },
+            "links": [{"source": "REQ-3", "type": "derives_from", "target": "REQ-1"}]
+        }))
+        .unwrap();
+        let diff = diff_models(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id, "REQ-3");
+        assert_eq!(diff.removed[0].id, "REQ-2");
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].changes[0].field, "body");
+        assert_eq!(diff.added_links[0].source, "REQ-3");
+        assert_eq!(diff.removed_links[0].source, "REQ-2");
+        assert!(diff_models(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_field_level_changes_on_tags_selection_and_bindings() {
+        let old: Snapshot = serde_json::from_value(json!({"registry": {
+            "REQ-1": {"type": "req", "id": "REQ-1", "tags": {"safety": "QM", "type": "functional"}},
+            "CONFIG:A": {"type": "config", "id": "A", "root": "F-ROOT", "selected": ["F-ROOT", "F-LOG"],
+                         "bindings": {"F-CACHE": {"size": 64, "mode": "lru"}}}
+        }}))
+        .unwrap();
+        let new: Snapshot = serde_json::from_value(json!({"registry": {
+            "REQ-1": {"type": "req", "id": "REQ-1", "tags": {"safety": "ASIL-B", "type": "functional"},
+                      "derives_from": "REQ-0"},
+            "CONFIG:A": {"type": "config", "id": "A", "root": "F-ROOT", "selected": ["F-ROOT", "F-AUTH"],
+                         "bindings": {"F-CACHE": {"size": 128, "mode": "lru"}}}
+        }}))
+        .unwrap();
+        let diff = diff_models(&old, &new);
+
+        let fields = |id: &str| -> Vec<String> {
+            diff.modified
+                .iter()
+                .find(|m| m.element.id == id)
+                .unwrap()
+                .changes
+                .iter()
+                .map(|c| c.field.clone())
+                .collect()
+        };
+        assert_eq!(fields("REQ-1"), vec!["derives_from", "tags.safety"]);
+        assert_eq!(fields("CONFIG:A"), vec!["bindings.F-CACHE.size", "selected"]);
+
+        let selected = diff.modified[0].changes.iter().find(|c| c.field == "selected").unwrap();
+        assert_eq!(selected.added, vec!["F-AUTH"]);
+        assert_eq!(selected.removed, vec!["F-LOG"]);
+        let derives = diff.modified[1].changes.iter().find(|c| c.field == "derives_from").unwrap();
+        assert_eq!(derives.old, None);
+        assert_eq!(derives.new, Some(json!("REQ-0")));
+    }
+}
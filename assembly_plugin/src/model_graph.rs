@@ -0,0 +1,94 @@
+//! Model Graph
+//!
+//! Builds the petgraph view of the registry and link records that validation,
+//! user rules and queries are evaluated on. Node weights are `(id, element)`,
+//! edge weights the link type string.
+
+use crate::diagnostics::Diagnostic;
+use crate::types::{Element, LinkKind};
+use crate::{Link, Registry};
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::HashMap;
+
+/// Graph of elements (node weight `(id, element)`) and links (edge weight link type)
+pub type ModelGraph = Graph<(String, Element), String>;
+
+/// Build the model graph
+///
+/// Nodes are added in ID order so that traversals are deterministic. Besides
+/// the link records, edges implied by element fields are added (requirement
+/// `parent`/`derives_from`, feature `parent`) unless an equivalent link record
+/// already exists. Links with a missing endpoint are skipped and reported as AL001.
+pub fn build_model_graph(
+    registry: &Registry,
+    links: &[Link],
+) -> (ModelGraph, HashMap<String, NodeIndex>, Vec<Diagnostic>) {
+    let mut graph = ModelGraph::new();
+    let mut diagnostics = Vec::new();
+
+    // Add nodes to the graph
+    let mut ids: Vec<&String> = registry.keys().collect();
+    ids.sort();
+    let mut node_indices = HashMap::new();
+    for id in ids {
+        let node = graph.add_node((id.clone(), registry[id].clone()));
+        node_indices.insert(id.clone(), node);
+    }
+
+    // Add edges to the graph
+    for link in links {
+        if let (Some(&source), Some(&target)) = (node_indices.get(&link.source), node_indices.get(&link.target)) {
+            graph.add_edge(source, target, link.link_type.clone());
+        } else {
+            let missing: Vec<&str> = [&link.source, &link.target]
+                .into_iter()
+                .filter(|id| !registry.contains_key(*id))
+                .map(String::as_str)
+                .collect();
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL001",
+                    format!(
+                        "Link references non-existent element: {} -> {}",
+                        link.source, link.target
+                    ),
+                )
+                .on(&link.source)
+                .related(missing)
+                .hint("Fix the link target ID or define the missing element"),
+            );
+        }
+    }
+
+    // Add edges implied by element fields (requirement parent/derives_from, feature parent),
+    // unless an equivalent link record already exists
+    for (id, &source) in &node_indices {
+        let implied = match &registry[id] {
+            Element::Req(req) => vec![
+                (req.parent.as_deref(), LinkKind::BelongsTo),
+                (req.derives_from.as_deref(), LinkKind::DerivesFrom),
+            ],
+            Element::Feature(feature) => vec![(feature.parent.as_deref(), LinkKind::ChildOf)],
+            _ => Vec::new(),
+        };
+        for (target_id, kind) in implied {
+            if let Some(&target) = target_id.and_then(|t| node_indices.get(t))
+                && !graph
+                    .edges_connecting(source, target)
+                    .any(|e| e.weight() == kind.as_str())
+            {
+                graph.add_edge(source, target, kind.as_str().to_string());
+            }
+        }
+    }
+
+    (graph, node_indices, diagnostics)
+}
+
+/// Model graph from registry and link JSON (test fixtures)
+#[cfg(test)]
+pub(crate) fn graph_from_json(registry: serde_json::Value, links: serde_json::Value) -> ModelGraph {
+    let registry: Registry = serde_json::from_value(registry).unwrap();
+    let links: Vec<Link> = serde_json::from_value(links).unwrap();
+    build_model_graph(&registry, &links).0
+}
@@ -1,9 +1,10 @@
-/// Parameter Validation Module
-/// Validates parameter bindings for configurations against feature parameter schemas
+//! Parameter Validation Module
+//! Validates parameter bindings for configurations against feature parameter schemas
 
 use crate::constraint_evaluator::evaluate_constraint;
 use crate::constraint_parser::parse_constraint;
-use crate::types::{ConfigElement, Element, FeatureElement, ParameterSchema};
+use crate::diagnostics::{has_errors, sort_diagnostics, Diagnostic};
+use crate::types::{Element, ParameterSchema};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -12,7 +13,7 @@ use std::collections::HashMap;
 pub struct ParameterValidationResult {
     pub is_valid: bool,
     pub message: String,
-    pub errors: Vec<String>,
+    pub diagnostics: Vec<Diagnostic>,
     pub num_features_checked: usize,
     pub num_parameters_checked: usize,
 }
@@ -22,7 +23,7 @@ pub fn validate_parameter_bindings(
     registry: &HashMap<String, Element>,
     config_id: &str,
 ) -> ParameterValidationResult {
-    let mut errors = Vec::new();
+    let mut diagnostics = Vec::new();
     let mut num_features_checked = 0;
     let mut num_parameters_checked = 0;
 
@@ -34,7 +35,11 @@ pub fn validate_parameter_bindings(
             return ParameterValidationResult {
                 is_valid: false,
                 message: format!("Configuration '{}' not found", config_id),
-                errors: vec![format!("Configuration '{}' does not exist", config_id)],
+                diagnostics: vec![Diagnostic::new(
+                    "AL101",
+                    format!("Configuration '{}' does not exist", config_id),
+                )
+                .on(config_key)],
                 num_features_checked: 0,
                 num_parameters_checked: 0,
             };
@@ -50,10 +55,17 @@ pub fn validate_parameter_bindings(
         let feature = match registry.get(feature_id) {
             Some(Element::Feature(f)) => f,
             _ => {
-                errors.push(format!(
-                    "Configuration '{}' selects non-existent feature '{}'",
-                    config_id, feature_id
-                ));
+                diagnostics.push(
+                    Diagnostic::new(
+                        "AL010",
+                        format!(
+                            "Configuration '{}' selects non-existent feature '{}'",
+                            config_id, feature_id
+                        ),
+                    )
+                    .on(&config_key)
+                    .related([feature_id.as_str()]),
+                );
                 continue;
             }
         };
@@ -80,10 +92,18 @@ pub fn validate_parameter_bindings(
                 None => {
                     // No binding - check if there's a default
                     if param_schema.default.is_null() {
-                        errors.push(format!(
-                            "Feature '{}', parameter '{}': No binding provided and no default value defined",
-                            feature_id, param_name
-                        ));
+                        diagnostics.push(
+                            Diagnostic::new(
+                                "AL102",
+                                format!(
+                                    "Feature '{}', parameter '{}': No binding provided and no default value defined",
+                                    feature_id, param_name
+                                ),
+                            )
+                            .on(feature_id)
+                            .related([config_key.as_str()])
+                            .hint(format!("Bind '{}' in configuration '{}' or give it a default", param_name, config_id)),
+                        );
                         continue;
                     }
                     param_schema.default.clone()
@@ -92,14 +112,22 @@ pub fn validate_parameter_bindings(
 
             // Validate based on type
             match param_schema.param_type.as_str() {
-                "Integer" => validate_integer(feature_id, param_name, &value, param_schema, &mut errors),
-                "Boolean" => validate_boolean(feature_id, param_name, &value, &mut errors),
-                "Enum" => validate_enum(feature_id, param_name, &value, param_schema, &mut errors),
+                "Integer" => validate_integer(feature_id, param_name, &value, param_schema, &mut diagnostics),
+                "Boolean" => validate_boolean(feature_id, param_name, &value, &mut diagnostics),
+                "Enum" => validate_enum(feature_id, param_name, &value, param_schema, &mut diagnostics),
+                "String" => validate_string(feature_id, param_name, &value, &mut diagnostics),
                 unknown_type => {
-                    errors.push(format!(
-                        "Feature '{}', parameter '{}': Unknown parameter type '{}'",
-                        feature_id, param_name, unknown_type
-                    ));
+                    diagnostics.push(
+                        Diagnostic::new(
+                            "AL106",
+                            format!(
+                                "Feature '{}', parameter '{}': Unknown parameter type '{}'",
+                                feature_id, param_name, unknown_type
+                            ),
+                        )
+                        .on(feature_id)
+                        .hint("Use Integer, Boolean, Enum or String"),
+                    );
                 }
             }
         }
@@ -119,10 +147,16 @@ pub fn validate_parameter_bindings(
                 let expr = match parse_constraint(constraint_str) {
                     Ok(e) => e,
                     Err(e) => {
-                        errors.push(format!(
-                            "Feature '{}': Failed to parse constraint '{}': {}",
-                            feature_id, constraint_str, e
-                        ));
+                        diagnostics.push(
+                            Diagnostic::new(
+                                "AL107",
+                                format!(
+                                    "Feature '{}': Failed to parse constraint '{}': {}",
+                                    feature_id, constraint_str, e
+                                ),
+                            )
+                            .on(feature_id),
+                        );
                         continue;
                     }
                 };
@@ -133,16 +167,29 @@ pub fn validate_parameter_bindings(
                         // Constraint satisfied
                     }
                     Ok(false) => {
-                        errors.push(format!(
-                            "Feature '{}': Constraint '{}' violated",
-                            feature_id, constraint_str
-                        ));
+                        diagnostics.push(
+                            Diagnostic::new(
+                                "AL108",
+                                format!(
+                                    "Feature '{}': Constraint '{}' violated",
+                                    feature_id, constraint_str
+                                ),
+                            )
+                            .on(feature_id)
+                            .related([config_key.as_str()]),
+                        );
                     }
                     Err(e) => {
-                        errors.push(format!(
-                            "Feature '{}': Failed to evaluate constraint '{}': {}",
-                            feature_id, constraint_str, e
-                        ));
+                        diagnostics.push(
+                            Diagnostic::new(
+                                "AL109",
+                                format!(
+                                    "Feature '{}': Failed to evaluate constraint '{}': {}",
+                                    feature_id, constraint_str, e
+                                ),
+                            )
+                            .on(feature_id),
+                        );
                     }
                 }
             }
@@ -150,7 +197,8 @@ pub fn validate_parameter_bindings(
     }
 
     // Generate result
-    let is_valid = errors.is_empty();
+    sort_diagnostics(&mut diagnostics);
+    let is_valid = !has_errors(&diagnostics);
     let message = if is_valid {
         format!(
             "All parameter bindings and constraints are valid ({} features, {} parameters checked)",
@@ -159,14 +207,14 @@ pub fn validate_parameter_bindings(
     } else {
         format!(
             "Parameter validation failed with {} error(s)",
-            errors.len()
+            diagnostics.len()
         )
     };
 
     ParameterValidationResult {
         is_valid,
         message,
-        errors,
+        diagnostics,
         num_features_checked,
         num_parameters_checked,
     }
@@ -178,28 +226,40 @@ fn validate_integer(
     param_name: &str,
     value: &serde_json::Value,
     schema: &ParameterSchema,
-    errors: &mut Vec<String>,
+    diagnostics: &mut Vec<Diagnostic>,
 ) {
     // Check type
     let int_value = match value.as_i64() {
         Some(v) => v,
         None => {
-            errors.push(format!(
-                "Feature '{}', parameter '{}': Expected type Integer, got {:?}",
-                feature_id, param_name, value
-            ));
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL103",
+                    format!(
+                        "Feature '{}', parameter '{}': Expected type Integer, got {:?}",
+                        feature_id, param_name, value
+                    ),
+                )
+                .on(feature_id),
+            );
             return;
         }
     };
 
     // Check range if specified
-    if let Some((min, max)) = schema.range {
-        if int_value < min || int_value > max {
-            errors.push(format!(
-                "Feature '{}', parameter '{}': Value {} out of range [{}, {}]",
-                feature_id, param_name, int_value, min, max
-            ));
-        }
+    if let Some((min, max)) = schema.range
+        && (int_value < min || int_value > max)
+    {
+        diagnostics.push(
+            Diagnostic::new(
+                "AL104",
+                format!(
+                    "Feature '{}', parameter '{}': Value {} out of range [{}, {}]",
+                    feature_id, param_name, int_value, min, max
+                ),
+            )
+            .on(feature_id),
+        );
     }
 }
 
@@ -208,13 +268,40 @@ fn validate_boolean(
     feature_id: &str,
     param_name: &str,
     value: &serde_json::Value,
-    errors: &mut Vec<String>,
+    diagnostics: &mut Vec<Diagnostic>,
 ) {
     if !value.is_boolean() {
-        errors.push(format!(
-            "Feature '{}', parameter '{}': Expected type Boolean, got {:?}",
-            feature_id, param_name, value
-        ));
+        diagnostics.push(
+            Diagnostic::new(
+                "AL103",
+                format!(
+                    "Feature '{}', parameter '{}': Expected type Boolean, got {:?}",
+                    feature_id, param_name, value
+                ),
+            )
+            .on(feature_id),
+        );
+    }
+}
+
+/// Validate string parameter
+fn validate_string(
+    feature_id: &str,
+    param_name: &str,
+    value: &serde_json::Value,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if !value.is_string() {
+        diagnostics.push(
+            Diagnostic::new(
+                "AL103",
+                format!(
+                    "Feature '{}', parameter '{}': Expected type String, got {:?}",
+                    feature_id, param_name, value
+                ),
+            )
+            .on(feature_id),
+        );
     }
 }
 
@@ -224,16 +311,22 @@ fn validate_enum(
     param_name: &str,
     value: &serde_json::Value,
     schema: &ParameterSchema,
-    errors: &mut Vec<String>,
+    diagnostics: &mut Vec<Diagnostic>,
 ) {
     // Check type (must be string)
     let str_value = match value.as_str() {
         Some(s) => s,
         None => {
-            errors.push(format!(
-                "Feature '{}', parameter '{}': Expected type String (for Enum), got {:?}",
-                feature_id, param_name, value
-            ));
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL103",
+                    format!(
+                        "Feature '{}', parameter '{}': Expected type String (for Enum), got {:?}",
+                        feature_id, param_name, value
+                    ),
+                )
+                .on(feature_id),
+            );
             return;
         }
     };
@@ -242,10 +335,16 @@ fn validate_enum(
     if let Some(values) = &schema.values {
         let str_value_owned = str_value.to_string();
         if !values.contains(&str_value_owned) {
-            errors.push(format!(
-                "Feature '{}', parameter '{}': Value '{}' not in enum {:?}",
-                feature_id, param_name, str_value, values
-            ));
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL105",
+                    format!(
+                        "Feature '{}', parameter '{}': Value '{}' not in enum {:?}",
+                        feature_id, param_name, str_value, values
+                    ),
+                )
+                .on(feature_id),
+            );
         }
     }
 }
@@ -253,6 +352,7 @@ fn validate_enum(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{ConfigElement, FeatureElement};
     use serde_json::json;
 
     fn create_test_feature(id: &str, params: HashMap<String, ParameterSchema>) -> Element {
@@ -319,7 +419,7 @@ mod tests {
         // Validate
         let result = validate_parameter_bindings(&registry, "CFG-TEST");
 
-        assert!(result.is_valid, "Expected valid result, got errors: {:?}", result.errors);
+        assert!(result.is_valid, "Expected valid result, got errors: {:?}", result.diagnostics);
         assert_eq!(result.num_features_checked, 1);
         assert_eq!(result.num_parameters_checked, 1);
     }
@@ -358,8 +458,9 @@ mod tests {
         let result = validate_parameter_bindings(&registry, "CFG-TEST");
 
         assert!(!result.is_valid, "Expected invalid result");
-        assert_eq!(result.errors.len(), 1);
-        assert!(result.errors[0].contains("out of range"));
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].code, "AL104");
+        assert!(result.diagnostics[0].message.contains("out of range"));
     }
 
     #[test]
@@ -395,7 +496,7 @@ mod tests {
         // Validate
         let result = validate_parameter_bindings(&registry, "CFG-TEST");
 
-        assert!(result.is_valid, "Expected valid result, got errors: {:?}", result.errors);
+        assert!(result.is_valid, "Expected valid result, got errors: {:?}", result.diagnostics);
     }
 
     #[test]
@@ -432,8 +533,9 @@ mod tests {
         let result = validate_parameter_bindings(&registry, "CFG-TEST");
 
         assert!(!result.is_valid);
-        assert_eq!(result.errors.len(), 1);
-        assert!(result.errors[0].contains("not in enum"));
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].code, "AL105");
+        assert!(result.diagnostics[0].message.contains("not in enum"));
     }
 
     #[test]
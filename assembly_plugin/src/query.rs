@@ -0,0 +1,382 @@
+//! Model Query Engine
+//!
+//! Path-pattern queries over the model graph, for reports and scripts.
+//!
+//! ```text
+//! "F-AUTH" <-belongs_to|derives_from-+ req            requirements under F-AUTH
+//! "REQ-SYS-001" <-derives_from-+ req <-satisfy- block_definition
+//! shortest "UC-LOGIN" to test_case                    shortest trace path, any direction
+//! shortest use_case to test_case via trace|verify
+//! ```
+//!
+//! Grammar:
+//! - `PATTERN := NODE (STEP NODE)*`
+//! - `STEP := -KINDS-> | <-KINDS-`, optionally followed by `+` (one or more hops)
+//! - `KINDS := * | kind(|kind)*`
+//! - `NODE := * | TYPE | "ELEMENT-ID"`
+//! - `shortest NODE to NODE [via KINDS]` ignores link direction.
+//!
+//! Each match is reported once per (start, end) pair with the shortest path
+//! between them that follows the pattern.
+
+use crate::model_graph::ModelGraph;
+use crate::rule_language::{tokenize, Token};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Node selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeSpec {
+    Any,
+    Type(String),
+    Id(String),
+}
+
+/// Link step between two node selectors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryStep {
+    /// Allowed link kinds, empty for any
+    pub kinds: Vec<String>,
+    pub direction: Direction,
+    pub transitive: bool,
+}
+
+/// Parsed query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Pattern { start: NodeSpec, steps: Vec<(QueryStep, NodeSpec)> },
+    Shortest { from: NodeSpec, to: NodeSpec, kinds: Vec<String> },
+}
+
+/// One query match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryMatch {
+    /// Matched (end) element ID
+    pub id: String,
+    #[serde(rename = "type")]
+    pub element_type: String,
+    /// Element IDs from the start element to the matched element
+    pub path: Vec<String>,
+}
+
+/// Query result
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryMatches {
+    /// Distinct matched element IDs, sorted
+    pub ids: Vec<String>,
+    /// All matches, sorted by path
+    pub matches: Vec<QueryMatch>,
+}
+
+/// Parse a query string
+pub fn parse_query(input: &str) -> Result<Query, String> {
+    let mut parser = QueryParser { tokens: tokenize(input)?, pos: 0 };
+    let query = if parser.eat_ident("shortest") {
+        let from = parser.parse_node()?;
+        if !parser.eat_ident("to") {
+            return Err(parser.error("'to'"));
+        }
+        let to = parser.parse_node()?;
+        let kinds = if parser.eat_ident("via") { parser.parse_kinds()? } else { Vec::new() };
+        Query::Shortest { from, to, kinds }
+    } else {
+        let start = parser.parse_node()?;
+        let mut steps = Vec::new();
+        while parser.pos < parser.tokens.len() {
+            let step = parser.parse_step()?;
+            steps.push((step, parser.parse_node()?));
+        }
+        Query::Pattern { start, steps }
+    };
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.error("end of query"));
+    }
+    Ok(query)
+}
+
+struct QueryParser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn error(&self, expected: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((token, col)) => format!("Expected {} at column {}, found {:?}", expected, col + 1, token),
+            None => format!("Expected {} at end of query", expected),
+        }
+    }
+
+    fn eat_ident(&mut self, word: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some((Token::Ident(s), _)) if s == word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some((Token::Sym(s), _)) if *s == sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn parse_node(&mut self) -> Result<NodeSpec, String> {
+        let spec = match self.tokens.get(self.pos) {
+            Some((Token::Sym("*"), _)) => NodeSpec::Any,
+            Some((Token::Ident(s), _)) => NodeSpec::Type(s.clone()),
+            Some((Token::Str(s), _)) => NodeSpec::Id(s.clone()),
+            _ => return Err(self.error("element type, quoted element ID or '*'")),
+        };
+        self.pos += 1;
+        Ok(spec)
+    }
+
+    fn parse_kinds(&mut self) -> Result<Vec<String>, String> {
+        if self.eat_sym("*") {
+            return Ok(Vec::new());
+        }
+        let mut kinds = Vec::new();
+        loop {
+            match self.tokens.get(self.pos) {
+                Some((Token::Ident(s), _)) => kinds.push(s.clone()),
+                _ => return Err(self.error("link kind")),
+            }
+            self.pos += 1;
+            if !self.eat_sym("|") {
+                return Ok(kinds);
+            }
+        }
+    }
+
+    fn parse_step(&mut self) -> Result<QueryStep, String> {
+        let direction = if self.eat_sym("-") {
+            Direction::Outgoing
+        } else if self.eat_sym("<-") {
+            Direction::Incoming
+        } else {
+            return Err(self.error("'-kind->' or '<-kind-'"));
+        };
+        let kinds = self.parse_kinds()?;
+        let close = match direction {
+            Direction::Outgoing => "->",
+            Direction::Incoming => "-",
+        };
+        if !self.eat_sym(close) {
+            return Err(self.error(&format!("'{}'", close)));
+        }
+        let transitive = self.eat_sym("+");
+        Ok(QueryStep { kinds, direction, transitive })
+    }
+}
+
+fn node_matches(graph: &ModelGraph, node: NodeIndex, spec: &NodeSpec) -> bool {
+    let (id, element) = &graph[node];
+    match spec {
+        NodeSpec::Any => true,
+        NodeSpec::Type(t) => element.type_name() == t,
+        NodeSpec::Id(i) => id == i,
+    }
+}
+
+fn kind_allowed(kinds: &[String], kind: &str) -> bool {
+    kinds.is_empty() || kinds.iter().any(|k| k == kind)
+}
+
+/// Neighbours of a node over allowed kinds, in node (ID) order
+fn neighbours(graph: &ModelGraph, node: NodeIndex, kinds: &[String], directions: &[Direction]) -> Vec<NodeIndex> {
+    let mut result: BTreeSet<NodeIndex> = BTreeSet::new();
+    for &direction in directions {
+        for edge in graph.edges_directed(node, direction) {
+            if kind_allowed(kinds, edge.weight()) {
+                result.insert(match direction {
+                    Direction::Outgoing => edge.target(),
+                    Direction::Incoming => edge.source(),
+                });
+            }
+        }
+    }
+    result.into_iter().collect()
+}
+
+/// Breadth-first search from `start`; returns the shortest path (excluding `start`)
+/// to every node reachable in one or more hops
+fn bfs_paths(
+    graph: &ModelGraph,
+    start: NodeIndex,
+    kinds: &[String],
+    directions: &[Direction],
+) -> Vec<(NodeIndex, Vec<NodeIndex>)> {
+    let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for next in neighbours(graph, node, kinds, directions) {
+            if next != start && !previous.contains_key(&next) {
+                previous.insert(next, node);
+                order.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|target| {
+            let mut path = vec![target];
+            let mut current = target;
+            while let Some(&prev) = previous.get(&current) {
+                if prev == start {
+                    break;
+                }
+                path.push(prev);
+                current = prev;
+            }
+            path.reverse();
+            (target, path)
+        })
+        .collect()
+}
+
+/// Run a parsed query on the model graph
+pub fn run_query(graph: &ModelGraph, query: &Query) -> QueryMatches {
+    let mut paths: Vec<Vec<NodeIndex>> = Vec::new();
+
+    match query {
+        Query::Pattern { start, steps } => {
+            paths = graph
+                .node_indices()
+                .filter(|&n| node_matches(graph, n, start))
+                .map(|n| vec![n])
+                .collect();
+            for (step, spec) in steps {
+                let mut seen: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+                let mut next_paths = Vec::new();
+                for path in &paths {
+                    let last = *path.last().unwrap();
+                    let segments = if step.transitive {
+                        bfs_paths(graph, last, &step.kinds, &[step.direction])
+                    } else {
+                        neighbours(graph, last, &step.kinds, &[step.direction])
+                            .into_iter()
+                            .map(|n| (n, vec![n]))
+                            .collect()
+                    };
+                    for (target, segment) in segments {
+                        if node_matches(graph, target, spec) && seen.insert((path[0], target)) {
+                            let mut extended = path.clone();
+                            extended.extend(segment);
+                            next_paths.push(extended);
+                        }
+                    }
+                }
+                paths = next_paths;
+            }
+        }
+        Query::Shortest { from, to, kinds } => {
+            for start in graph.node_indices().filter(|&n| node_matches(graph, n, from)) {
+                let nearest = bfs_paths(graph, start, kinds, &[Direction::Outgoing, Direction::Incoming])
+                    .into_iter()
+                    .find(|(target, _)| node_matches(graph, *target, to));
+                if let Some((_, segment)) = nearest {
+                    let mut path = vec![start];
+                    path.extend(segment);
+                    paths.push(path);
+                }
+            }
+        }
+    }
+
+    let mut matches: Vec<QueryMatch> = paths
+        .into_iter()
+        .map(|path| {
+            let last = *path.last().unwrap();
+            QueryMatch {
+                id: graph[last].0.clone(),
+                element_type: graph[last].1.type_name().to_string(),
+                path: path.iter().map(|&n| graph[n].0.clone()).collect(),
+            }
+        })
+        .collect();
+    matches.sort_by(|a, b| a.path.cmp(&b.path));
+    let ids: BTreeSet<String> = matches.iter().map(|m| m.id.clone()).collect();
+
+    QueryMatches { ids: ids.into_iter().collect(), matches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::graph_from_json;
+    use serde_json::json;
+
+    fn run(graph: &ModelGraph, text: &str) -> QueryMatches {
+        run_query(graph, &parse_query(text).unwrap())
+    }
+
+    #[test]
+    fn test_reachable_requirements_and_satisfying_blocks() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH"},
+                "REQ-SYS-001": {"type": "req", "id": "REQ-SYS-001"},
+                "REQ-SYS-001.1": {"type": "req", "id": "REQ-SYS-001.1"},
+                "REQ-SYS-001.1.1": {"type": "req", "id": "REQ-SYS-001.1.1"},
+                "BLK-1": {"type": "block_definition", "id": "BLK-1"}
+            }),
+            json!([
+                {"source": "REQ-SYS-001", "type": "belongs_to", "target": "F-AUTH"},
+                {"source": "REQ-SYS-001.1", "type": "derives_from", "target": "REQ-SYS-001"},
+                {"source": "REQ-SYS-001.1.1", "type": "derives_from", "target": "REQ-SYS-001.1"},
+                {"source": "BLK-1", "type": "satisfy", "target": "REQ-SYS-001.1.1"}
+            ]),
+        );
+
+        let reqs = run(&graph, "\"F-AUTH\" <-belongs_to|derives_from-+ req");
+        assert_eq!(reqs.ids, vec!["REQ-SYS-001", "REQ-SYS-001.1", "REQ-SYS-001.1.1"]);
+        let deepest = reqs.matches.iter().find(|m| m.id == "REQ-SYS-001.1.1").unwrap();
+        assert_eq!(deepest.path, vec!["F-AUTH", "REQ-SYS-001", "REQ-SYS-001.1", "REQ-SYS-001.1.1"]);
+
+        let blocks = run(&graph, "\"REQ-SYS-001\" <-derives_from-+ req <-satisfy- block_definition");
+        assert_eq!(blocks.ids, vec!["BLK-1"]);
+        assert_eq!(blocks.matches.len(), 1);
+    }
+
+    #[test]
+    fn test_shortest_path_ignores_direction() {
+        let graph = graph_from_json(
+            json!({
+                "REQ-SYS-001": {"type": "req", "id": "REQ-SYS-001"},
+                "REQ-SYS-001.1": {"type": "req", "id": "REQ-SYS-001.1"},
+                "REQ-SYS-001.1.1": {"type": "req", "id": "REQ-SYS-001.1.1"},
+                "UC-LOGIN": {"type": "use_case", "id": "UC-LOGIN"},
+                "TC-1": {"type": "test_case", "id": "TC-1"}
+            }),
+            json!([
+                {"source": "REQ-SYS-001.1", "type": "derives_from", "target": "REQ-SYS-001"},
+                {"source": "REQ-SYS-001.1.1", "type": "derives_from", "target": "REQ-SYS-001.1"},
+                {"source": "UC-LOGIN", "type": "trace", "target": "REQ-SYS-001"},
+                {"source": "TC-1", "type": "verify", "target": "REQ-SYS-001.1.1"}
+            ]),
+        );
+        let result = run(&graph, "shortest \"UC-LOGIN\" to test_case via trace|derives_from|verify");
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(
+            result.matches[0].path,
+            vec!["UC-LOGIN", "REQ-SYS-001", "REQ-SYS-001.1", "REQ-SYS-001.1.1", "TC-1"]
+        );
+        assert!(run(&graph, "shortest \"UC-LOGIN\" to test_case via trace").matches.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_query("req -satisfy").is_err());
+        assert!(parse_query("shortest req test_case").unwrap_err().contains("'to'"));
+        assert!(parse_query("req <-verify- test_case extra").is_err());
+    }
+}
@@ -0,0 +1,945 @@
+//! ReqIF 1.2 Import and Export
+//!
+//! Exchanges requirements and trace links with ReqIF tools (DOORS, Polarion, ...).
+//!
+//! Mapping:
+//! - SPEC-OBJECT ↔ `req` element. The ID is the `ReqIF.ForeignID` attribute
+//!   (falling back to the IDENTIFIER), the title `ReqIF.Name` and the body the
+//!   plain text of the `ReqIF.Text` XHTML; every other attribute is a tag.
+//! - SPEC-RELATION ↔ `Link`, the relation type LONG-NAME being mapped through
+//!   `relation_types` (or used as is if it is a link type).
+//! - SPECIFICATION ↔ feature: top-level SPEC-HIERARCHY objects `belong_to` the
+//!   feature, nested objects derive from their parent object. A specification
+//!   with an empty `ReqIF.ForeignID` holds requirements without a feature. On
+//!   export the parents come from the `parent`/`derives_from` fields or from
+//!   `belongs_to`/`derives_from` link records; links expressed by the hierarchy
+//!   are not repeated as SPEC-RELATIONs. A derives_from cycle is broken at its
+//!   first requirement, which is exported without feature.
+//!
+//! Exported identifiers are the element IDs made XML-safe; the ID itself is
+//! kept in `ReqIF.ForeignID`, so an export imports back with the same IDs and
+//! links. Tag values that are not strings, booleans or numbers are written as
+//! JSON text in string attributes whose definition has `DESC="JSON"`.
+
+use crate::types::{Element, ElementCore, FeatureElement, LinkKind, ReqElement};
+use crate::xml::{self, XmlElement};
+use crate::{Link, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+const REQIF_NAMESPACE: &str = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd";
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+/// DESC marking string attributes that hold JSON tag values
+const JSON_DESC: &str = "JSON";
+
+/// Options controlling the ReqIF mapping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReqifOptions {
+    /// Relation type LONG-NAME → link type (export uses the reverse mapping)
+    #[serde(default)]
+    pub relation_types: BTreeMap<String, String>,
+    /// Link type for relation types that are neither mapped nor link types
+    #[serde(default = "default_link_type")]
+    pub default_link_type: String,
+    #[serde(default = "default_id_attribute")]
+    pub id_attribute: String,
+    #[serde(default = "default_title_attribute")]
+    pub title_attribute: String,
+    #[serde(default = "default_text_attribute")]
+    pub text_attribute: String,
+    /// Parent for features created from specifications
+    #[serde(default)]
+    pub root_parent: Option<String>,
+    /// LAST-CHANGE timestamp written on export
+    #[serde(default = "default_last_change")]
+    pub last_change: String,
+    /// Document TITLE written on export
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+fn default_link_type() -> String {
+    "trace".to_string()
+}
+
+fn default_id_attribute() -> String {
+    "ReqIF.ForeignID".to_string()
+}
+
+fn default_title_attribute() -> String {
+    "ReqIF.Name".to_string()
+}
+
+fn default_text_attribute() -> String {
+    "ReqIF.Text".to_string()
+}
+
+fn default_last_change() -> String {
+    "1970-01-01T00:00:00Z".to_string()
+}
+
+impl Default for ReqifOptions {
+    fn default() -> Self {
+        ReqifOptions {
+            relation_types: BTreeMap::new(),
+            default_link_type: default_link_type(),
+            id_attribute: default_id_attribute(),
+            title_attribute: default_title_attribute(),
+            text_attribute: default_text_attribute(),
+            root_parent: None,
+            last_change: default_last_change(),
+            title: None,
+        }
+    }
+}
+
+/// Result of a ReqIF import
+#[derive(Debug, Clone, Default)]
+pub struct ReqifImport {
+    pub registry: BTreeMap<String, Element>,
+    pub links: Vec<Link>,
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Attribute definition: LONG-NAME, value kind (`STRING`, `XHTML`, ...) and JSON flag
+struct AttributeDefinition {
+    name: String,
+    kind: String,
+    json: bool,
+}
+
+/// Values of one SPEC-OBJECT or SPECIFICATION by attribute LONG-NAME
+fn attribute_values(
+    owner: &XmlElement,
+    definitions: &HashMap<String, AttributeDefinition>,
+    enum_values: &HashMap<String, String>,
+) -> BTreeMap<String, serde_json::Value> {
+    let mut values = BTreeMap::new();
+    let Some(list) = owner.child("VALUES") else {
+        return values;
+    };
+
+    for value in list.elements() {
+        let definition_ref = value.child("DEFINITION").and_then(|d| d.elements().next()).map(|r| r.text());
+        let Some(definition) = definition_ref.and_then(|r| definitions.get(r.trim())) else {
+            continue;
+        };
+        let the_value = value.attr("THE-VALUE").unwrap_or_default();
+        let parsed = match definition.kind.as_str() {
+            "XHTML" => value.child("THE-VALUE").map(|v| serde_json::json!(xhtml_text(v))),
+            "BOOLEAN" => the_value.parse::<bool>().ok().map(serde_json::Value::Bool),
+            "INTEGER" => the_value.parse::<i64>().ok().map(|n| serde_json::json!(n)),
+            "REAL" => the_value.parse::<f64>().ok().map(|n| serde_json::json!(n)),
+            "ENUMERATION" => {
+                let names: Vec<String> = value
+                    .descendants("ENUM-VALUE-REF")
+                    .iter()
+                    .filter_map(|r| enum_values.get(r.text().trim()).cloned())
+                    .collect();
+                match names.len() {
+                    0 => None,
+                    1 => Some(serde_json::json!(names[0])),
+                    _ => Some(serde_json::json!(names)),
+                }
+            }
+            "STRING" if definition.json => serde_json::from_str(the_value).ok(),
+            _ => Some(serde_json::json!(the_value)),
+        };
+        if let Some(parsed) = parsed {
+            values.insert(definition.name.clone(), parsed);
+        }
+    }
+    values
+}
+
+/// Plain text of an XHTML fragment, one line per block element
+fn xhtml_text(value: &XmlElement) -> String {
+    fn collect(element: &XmlElement, out: &mut String) {
+        let block = matches!(
+            element.local_name(),
+            "p" | "div" | "br" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+        );
+        if block {
+            out.push('\n');
+        }
+        for node in &element.children {
+            match node {
+                xml::XmlNode::Text(text) => out.push_str(text),
+                xml::XmlNode::Element(child) => collect(child, out),
+            }
+        }
+        if block {
+            out.push('\n');
+        }
+    }
+
+    let mut out = String::new();
+    collect(value, &mut out);
+    out.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn string_value(values: &BTreeMap<String, serde_json::Value>, key: &str) -> Option<String> {
+    values.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Read a ReqIF document into requirement and feature elements and links
+pub fn import_reqif(text: &str, options: &ReqifOptions) -> Result<ReqifImport, String> {
+    let root = xml::parse(text)?;
+    if root.local_name() != "REQ-IF" {
+        return Err(format!("Root element is '{}', expected 'REQ-IF'", root.name));
+    }
+    let content = root
+        .child("CORE-CONTENT")
+        .and_then(|c| c.child("REQ-IF-CONTENT"))
+        .ok_or("Missing CORE-CONTENT/REQ-IF-CONTENT")?;
+
+    let mut import = ReqifImport::default();
+
+    // Definitions
+    let mut definitions = HashMap::new();
+    let mut enum_values = HashMap::new();
+    let mut relation_type_names = HashMap::new();
+    for types in content.children_named("SPEC-TYPES") {
+        for spec_type in types.elements() {
+            if spec_type.local_name() == "SPEC-RELATION-TYPE" {
+                let name = spec_type.attr("LONG-NAME").or(spec_type.attr("IDENTIFIER")).unwrap_or_default();
+                relation_type_names.insert(spec_type.attr("IDENTIFIER").unwrap_or_default(), name.to_string());
+            }
+            for definition in spec_type.child("SPEC-ATTRIBUTES").into_iter().flat_map(|a| a.elements()) {
+                let Some(kind) = definition.local_name().strip_prefix("ATTRIBUTE-DEFINITION-") else {
+                    continue;
+                };
+                let id = definition.attr("IDENTIFIER").unwrap_or_default().to_string();
+                definitions.insert(
+                    id.clone(),
+                    AttributeDefinition {
+                        name: definition.attr("LONG-NAME").unwrap_or(&id).to_string(),
+                        kind: kind.to_string(),
+                        json: definition.attr("DESC") == Some(JSON_DESC),
+                    },
+                );
+            }
+        }
+    }
+    for enum_value in content.descendants("ENUM-VALUE") {
+        let id = enum_value.attr("IDENTIFIER").unwrap_or_default();
+        let name = enum_value.attr("LONG-NAME").unwrap_or(id);
+        enum_values.insert(id.to_string(), name.to_string());
+    }
+
+    // Objects
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut requirements: BTreeMap<String, ReqElement> = BTreeMap::new();
+    for object in content.descendants("SPEC-OBJECT") {
+        let identifier = object.attr("IDENTIFIER").unwrap_or_default().to_string();
+        let mut values = attribute_values(object, &definitions, &enum_values);
+        let id = string_value(&values, &options.id_attribute)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| identifier.clone());
+        let title = string_value(&values, &options.title_attribute)
+            .or(object.attr("LONG-NAME").map(str::to_string))
+            .unwrap_or_default();
+        let body = values
+            .remove(&options.text_attribute)
+            .filter(|b| b.as_str() != Some(""))
+            .unwrap_or(serde_json::Value::Null);
+        values.remove(&options.id_attribute);
+        values.remove(&options.title_attribute);
+
+        if requirements.contains_key(&id) {
+            import.warnings.push(format!("line {}: duplicate requirement ID '{}' skipped", object.line, id));
+            continue;
+        }
+        ids.insert(identifier, id.clone());
+        requirements.insert(
+            id.clone(),
+            ReqElement {
+                id,
+                title,
+                tags: values.into_iter().collect(),
+                parent: None,
+                derives_from: None,
+                body,
+            },
+        );
+    }
+
+    // Specifications and hierarchy
+    let mut placed = HashSet::new();
+    for specification in content.descendants("SPECIFICATION") {
+        let values = attribute_values(specification, &definitions, &enum_values);
+        let feature = match string_value(&values, &options.id_attribute) {
+            Some(id) if id.is_empty() => None,
+            Some(id) => Some(id),
+            None => specification.attr("IDENTIFIER").map(str::to_string),
+        };
+        if let Some(feature_id) = &feature {
+            let title = string_value(&values, &options.title_attribute)
+                .or(specification.attr("LONG-NAME").map(str::to_string))
+                .unwrap_or_default();
+            import.registry.insert(
+                feature_id.clone(),
+                Element::Feature(FeatureElement {
+                    id: feature_id.clone(),
+                    title,
+                    tags: HashMap::new(),
+                    parent: options.root_parent.clone(),
+                    concrete: Some(true),
+                    group: None,
+                    body: serde_json::Value::Null,
+                    parameters: None,
+                    constraints: None,
+                    requires: None,
+                }),
+            );
+        }
+
+        let mut pending: Vec<(&XmlElement, Option<String>)> = specification
+            .child("CHILDREN")
+            .into_iter()
+            .flat_map(|c| c.children_named("SPEC-HIERARCHY"))
+            .map(|h| (h, None))
+            .collect();
+        pending.reverse();
+        while let Some((hierarchy, parent_object)) = pending.pop() {
+            let object_ref = hierarchy.child("OBJECT").and_then(|o| o.child("SPEC-OBJECT-REF")).map(|r| r.text());
+            let Some(id) = object_ref.and_then(|r| ids.get(r.trim()).cloned()) else {
+                import.warnings.push(format!("line {}: hierarchy references an unknown SPEC-OBJECT", hierarchy.line));
+                continue;
+            };
+            if !placed.insert(id.clone()) {
+                import.warnings.push(format!(
+                    "line {}: '{}' appears more than once in the hierarchy, first position kept",
+                    hierarchy.line, id
+                ));
+            } else if let Some(req) = requirements.get_mut(&id) {
+                match &parent_object {
+                    Some(parent) => req.derives_from = Some(parent.clone()),
+                    None => req.parent = feature.clone(),
+                }
+            }
+            let children = hierarchy.child("CHILDREN").into_iter().flat_map(|c| c.children_named("SPEC-HIERARCHY"));
+            let mut children: Vec<_> = children.map(|h| (h, Some(id.clone()))).collect();
+            children.reverse();
+            pending.extend(children);
+        }
+    }
+
+    // Relations
+    for relation in content.descendants("SPEC-RELATION") {
+        let end = |name: &str| {
+            relation
+                .child(name)
+                .and_then(|e| e.child("SPEC-OBJECT-REF"))
+                .and_then(|r| ids.get(r.text().trim()).cloned())
+        };
+        let (Some(source), Some(target)) = (end("SOURCE"), end("TARGET")) else {
+            import.warnings.push(format!("line {}: relation between unknown SPEC-OBJECTs skipped", relation.line));
+            continue;
+        };
+        let type_ref = relation.child("TYPE").and_then(|t| t.child("SPEC-RELATION-TYPE-REF")).map(|r| r.text());
+        let type_name = type_ref
+            .and_then(|r| relation_type_names.get(r.trim()).cloned())
+            .unwrap_or_default();
+        let link_type = match options.relation_types.get(&type_name) {
+            Some(mapped) => mapped.clone(),
+            None if LinkKind::parse(&type_name).is_some() => type_name,
+            None => {
+                import.warnings.push(format!(
+                    "line {}: relation type '{}' is not mapped, imported as '{}'",
+                    relation.line, type_name, options.default_link_type
+                ));
+                options.default_link_type.clone()
+            }
+        };
+        import.links.push(Link { source, link_type, target });
+    }
+
+    import
+        .registry
+        .extend(requirements.into_iter().map(|(id, req)| (id, Element::Req(req))));
+    Ok(import)
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Allocates document-unique XML identifiers
+#[derive(Default)]
+struct Identifiers {
+    used: HashSet<String>,
+}
+
+impl Identifiers {
+    fn allocate(&mut self, id: &str) -> String {
+        let mut base: String = id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+            .collect();
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            base.insert(0, '_');
+        }
+        let mut candidate = base.clone();
+        let mut n = 2;
+        while !self.used.insert(candidate.clone()) {
+            candidate = format!("{}-{}", base, n);
+            n += 1;
+        }
+        candidate
+    }
+}
+
+/// Indenting XML text writer
+struct Writer {
+    out: String,
+    depth: usize,
+}
+
+impl Writer {
+    fn start_tag(name: &str, attributes: &[(&str, &str)]) -> String {
+        let mut tag = format!("<{}", name);
+        for (key, value) in attributes {
+            tag.push_str(&format!(" {}=\"{}\"", key, xml::escape(value)));
+        }
+        tag
+    }
+
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.line(&format!("{}>", Self::start_tag(name, attributes)));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{}>", name));
+    }
+
+    fn empty(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.line(&format!("{}/>", Self::start_tag(name, attributes)));
+    }
+
+    fn leaf(&mut self, name: &str, text: &str) {
+        self.line(&format!("<{}>{}</{}>", name, xml::escape(text), name));
+    }
+
+    /// `<WRAPPER><REF>id</REF></WRAPPER>`
+    fn reference(&mut self, wrapper: &str, reference: &str, id: &str) {
+        self.open(wrapper, &[]);
+        self.leaf(reference, id);
+        self.close(wrapper);
+    }
+}
+
+/// ReqIF datatype used for a tag
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    String,
+    Boolean,
+    Integer,
+    Real,
+    Json,
+}
+
+impl ValueKind {
+    fn of(value: &serde_json::Value) -> ValueKind {
+        match value {
+            serde_json::Value::String(_) => ValueKind::String,
+            serde_json::Value::Bool(_) => ValueKind::Boolean,
+            serde_json::Value::Number(n) if n.is_i64() => ValueKind::Integer,
+            serde_json::Value::Number(_) => ValueKind::Real,
+            _ => ValueKind::Json,
+        }
+    }
+
+    /// (ReqIF type suffix, datatype identifier)
+    fn reqif_type(self) -> (&'static str, &'static str) {
+        match self {
+            ValueKind::String | ValueKind::Json => ("STRING", "_AL-DT-STRING"),
+            ValueKind::Boolean => ("BOOLEAN", "_AL-DT-BOOLEAN"),
+            ValueKind::Integer => ("INTEGER", "_AL-DT-INTEGER"),
+            ValueKind::Real => ("REAL", "_AL-DT-REAL"),
+        }
+    }
+
+    fn format(self, value: &serde_json::Value) -> String {
+        match (self, value) {
+            (ValueKind::String, serde_json::Value::String(s)) => s.clone(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Write requirements, their features and the links between them as ReqIF
+pub fn export_reqif<'a>(registry: &'a Registry, links: &'a [Link], options: &ReqifOptions) -> String {
+    let mut requirements: Vec<&ReqElement> = registry.values().filter_map(|e| e.as_req()).collect();
+    requirements.sort_by(|a, b| a.id.cmp(&b.id));
+    let exported: BTreeSet<&str> = requirements.iter().map(|r| r.id.as_str()).collect();
+    let stamp = options.last_change.as_str();
+
+    let mut identifiers = Identifiers::default();
+    for fixed in [
+        "_AL-DT-STRING", "_AL-DT-XHTML", "_AL-DT-BOOLEAN", "_AL-DT-INTEGER", "_AL-DT-REAL",
+        "_AL-ST-REQ", "_AL-ST-SPEC", "_AL-AD-ID", "_AL-AD-NAME", "_AL-AD-TEXT", "_AL-AD-SPEC-ID", "_AL-AD-SPEC-NAME",
+    ] {
+        identifiers.allocate(fixed);
+    }
+
+    // Tag attributes: one definition per key, typed by the values seen
+    let mut tag_kinds: BTreeMap<&str, ValueKind> = BTreeMap::new();
+    for req in &requirements {
+        for (key, value) in &req.tags {
+            let kind = ValueKind::of(value);
+            tag_kinds
+                .entry(key.as_str())
+                .and_modify(|k| if *k != kind { *k = ValueKind::Json })
+                .or_insert(kind);
+        }
+    }
+    let tag_definitions: BTreeMap<&str, (String, ValueKind)> = tag_kinds
+        .iter()
+        .map(|(key, kind)| (*key, (identifiers.allocate(&format!("_AL-AD-TAG-{}", key)), *kind)))
+        .collect();
+
+    let object_ids: HashMap<&str, String> = requirements
+        .iter()
+        .map(|r| (r.id.as_str(), identifiers.allocate(&r.id)))
+        .collect();
+
+    // Hierarchy: roots grouped by feature, children by derives_from (fields or links)
+    let linked = |id: &'a str, kind: LinkKind| {
+        links
+            .iter()
+            .filter(move |l| l.source == id && l.kind() == Some(kind))
+            .map(|l| l.target.as_str())
+    };
+    let mut roots: BTreeMap<Option<&str>, Vec<&str>> = BTreeMap::new();
+    let mut children: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for req in &requirements {
+        let parent = req
+            .derives_from
+            .as_deref()
+            .into_iter()
+            .chain(linked(&req.id, LinkKind::DerivesFrom))
+            .find(|p| exported.contains(p));
+        match parent {
+            Some(parent) => children.entry(parent).or_default().push(&req.id),
+            None => {
+                let feature = req
+                    .parent
+                    .as_deref()
+                    .into_iter()
+                    .chain(linked(&req.id, LinkKind::BelongsTo))
+                    .find(|f| matches!(registry.get(*f), Some(Element::Feature(_))));
+                roots.entry(feature).or_default().push(&req.id);
+            }
+        }
+    }
+
+    // Requirements in a derives_from cycle are not below any root; the first
+    // of each cycle becomes a root without feature and keeps its own parent as a relation
+    let mut reachable: HashSet<&str> = HashSet::new();
+    let reach = |start: &'a str, reachable: &mut HashSet<&'a str>| {
+        let mut pending = vec![start];
+        while let Some(id) = pending.pop() {
+            if reachable.insert(id) {
+                pending.extend(children.get(id).into_iter().flatten());
+            }
+        }
+    };
+    for root in roots.values().flatten() {
+        reach(root, &mut reachable);
+    }
+    let mut cycle_roots = Vec::new();
+    for req in &requirements {
+        if !reachable.contains(req.id.as_str()) {
+            reach(&req.id, &mut reachable);
+            cycle_roots.push(req.id.as_str());
+        }
+    }
+    if !cycle_roots.is_empty() {
+        roots.entry(None).or_default().extend(&cycle_roots);
+    }
+    let in_hierarchy = |link: &Link| {
+        link.kind() == Some(LinkKind::DerivesFrom)
+            && !cycle_roots.contains(&link.source.as_str())
+            && children.get(link.target.as_str()).is_some_and(|c| c.contains(&link.source.as_str()))
+    };
+
+    // Relations not already expressed by the hierarchy
+    let exported_links: Vec<&Link> = links
+        .iter()
+        .filter(|l| exported.contains(l.source.as_str()) && exported.contains(l.target.as_str()))
+        .filter(|l| !in_hierarchy(l))
+        .collect();
+    let relation_names: BTreeMap<&str, &str> = exported_links
+        .iter()
+        .map(|l| {
+            let name = options
+                .relation_types
+                .iter()
+                .find(|(_, link_type)| **link_type == l.link_type)
+                .map(|(name, _)| name.as_str())
+                .unwrap_or(&l.link_type);
+            (l.link_type.as_str(), name)
+        })
+        .collect();
+    let relation_types: BTreeMap<&str, String> = relation_names
+        .keys()
+        .map(|link_type| (*link_type, identifiers.allocate(&format!("_AL-RT-{}", link_type))))
+        .collect();
+
+    let mut w = Writer { out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"), depth: 0 };
+    w.open("REQ-IF", &[("xmlns", REQIF_NAMESPACE), ("xmlns:xhtml", XHTML_NAMESPACE)]);
+    w.open("THE-HEADER", &[]);
+    w.open("REQ-IF-HEADER", &[("IDENTIFIER", "_AL-HEADER")]);
+    w.leaf("CREATION-TIME", stamp);
+    w.leaf("REQ-IF-TOOL-ID", "AssemblyLine");
+    w.leaf("REQ-IF-VERSION", "1.0");
+    w.leaf("SOURCE-TOOL-ID", "AssemblyLine");
+    w.leaf("TITLE", options.title.as_deref().unwrap_or("Requirements"));
+    w.close("REQ-IF-HEADER");
+    w.close("THE-HEADER");
+    w.open("CORE-CONTENT", &[]);
+    w.open("REQ-IF-CONTENT", &[]);
+
+    w.open("DATATYPES", &[]);
+    let max_i64 = i64::MAX.to_string();
+    let min_i64 = i64::MIN.to_string();
+    let datatypes = [
+        ("DATATYPE-DEFINITION-STRING", "_AL-DT-STRING", "String", vec![("MAX-LENGTH", "1000000")]),
+        ("DATATYPE-DEFINITION-XHTML", "_AL-DT-XHTML", "XHTML", vec![]),
+        ("DATATYPE-DEFINITION-BOOLEAN", "_AL-DT-BOOLEAN", "Boolean", vec![]),
+        ("DATATYPE-DEFINITION-INTEGER", "_AL-DT-INTEGER", "Integer", vec![("MAX", &max_i64), ("MIN", &min_i64)]),
+        (
+            "DATATYPE-DEFINITION-REAL",
+            "_AL-DT-REAL",
+            "Real",
+            vec![("ACCURACY", "17"), ("MAX", "1.7976931348623157E308"), ("MIN", "-1.7976931348623157E308")],
+        ),
+    ];
+    for (tag, id, name, extra) in datatypes {
+        let mut attributes = vec![("IDENTIFIER", id), ("LONG-NAME", name), ("LAST-CHANGE", stamp)];
+        attributes.extend(extra);
+        w.empty(tag, &attributes);
+    }
+    w.close("DATATYPES");
+
+    let attribute_definition = |w: &mut Writer, kind: &str, id: &str, name: &str, desc: Option<&str>, datatype: &str| {
+        let tag = format!("ATTRIBUTE-DEFINITION-{}", kind);
+        let mut attributes = vec![("IDENTIFIER", id), ("LONG-NAME", name), ("LAST-CHANGE", stamp)];
+        attributes.extend(desc.map(|d| ("DESC", d)));
+        w.open(&tag, &attributes);
+        w.reference("TYPE", &format!("DATATYPE-DEFINITION-{}-REF", kind), datatype);
+        w.close(&tag);
+    };
+
+    w.open("SPEC-TYPES", &[]);
+    w.open("SPEC-OBJECT-TYPE", &[("IDENTIFIER", "_AL-ST-REQ"), ("LONG-NAME", "Requirement"), ("LAST-CHANGE", stamp)]);
+    w.open("SPEC-ATTRIBUTES", &[]);
+    attribute_definition(&mut w, "STRING", "_AL-AD-ID", &options.id_attribute, None, "_AL-DT-STRING");
+    attribute_definition(&mut w, "STRING", "_AL-AD-NAME", &options.title_attribute, None, "_AL-DT-STRING");
+    attribute_definition(&mut w, "XHTML", "_AL-AD-TEXT", &options.text_attribute, None, "_AL-DT-XHTML");
+    for (key, (id, kind)) in &tag_definitions {
+        let (suffix, datatype) = kind.reqif_type();
+        let desc = (*kind == ValueKind::Json).then_some(JSON_DESC);
+        attribute_definition(&mut w, suffix, id, key, desc, datatype);
+    }
+    w.close("SPEC-ATTRIBUTES");
+    w.close("SPEC-OBJECT-TYPE");
+    w.open("SPECIFICATION-TYPE", &[("IDENTIFIER", "_AL-ST-SPEC"), ("LONG-NAME", "Specification"), ("LAST-CHANGE", stamp)]);
+    w.open("SPEC-ATTRIBUTES", &[]);
+    attribute_definition(&mut w, "STRING", "_AL-AD-SPEC-ID", &options.id_attribute, None, "_AL-DT-STRING");
+    attribute_definition(&mut w, "STRING", "_AL-AD-SPEC-NAME", &options.title_attribute, None, "_AL-DT-STRING");
+    w.close("SPEC-ATTRIBUTES");
+    w.close("SPECIFICATION-TYPE");
+    for (link_type, id) in &relation_types {
+        w.empty("SPEC-RELATION-TYPE", &[("IDENTIFIER", id), ("LONG-NAME", relation_names[link_type]), ("LAST-CHANGE", stamp)]);
+    }
+    w.close("SPEC-TYPES");
+
+    let attribute_value = |w: &mut Writer, definition: &str, value: &str, kind: &str| {
+        let tag = format!("ATTRIBUTE-VALUE-{}", kind);
+        w.open(&tag, &[("THE-VALUE", value)]);
+        w.reference("DEFINITION", &format!("ATTRIBUTE-DEFINITION-{}-REF", kind), definition);
+        w.close(&tag);
+    };
+
+    w.open("SPEC-OBJECTS", &[]);
+    for req in &requirements {
+        w.open("SPEC-OBJECT", &[("IDENTIFIER", &object_ids[req.id.as_str()]), ("LONG-NAME", &req.title), ("LAST-CHANGE", stamp)]);
+        w.open("VALUES", &[]);
+        attribute_value(&mut w, "_AL-AD-ID", &req.id, "STRING");
+        attribute_value(&mut w, "_AL-AD-NAME", &req.title, "STRING");
+        let text = req.body_text();
+        if !text.is_empty() {
+            w.open("ATTRIBUTE-VALUE-XHTML", &[]);
+            w.reference("DEFINITION", "ATTRIBUTE-DEFINITION-XHTML-REF", "_AL-AD-TEXT");
+            w.open("THE-VALUE", &[]);
+            w.open("xhtml:div", &[]);
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                w.leaf("xhtml:p", line.trim());
+            }
+            w.close("xhtml:div");
+            w.close("THE-VALUE");
+            w.close("ATTRIBUTE-VALUE-XHTML");
+        }
+        let mut tags: Vec<(&String, &serde_json::Value)> = req.tags.iter().collect();
+        tags.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in tags {
+            let (id, kind) = &tag_definitions[key.as_str()];
+            attribute_value(&mut w, id, &kind.format(value), kind.reqif_type().0);
+        }
+        w.close("VALUES");
+        w.reference("TYPE", "SPEC-OBJECT-TYPE-REF", "_AL-ST-REQ");
+        w.close("SPEC-OBJECT");
+    }
+    w.close("SPEC-OBJECTS");
+
+    w.open("SPEC-RELATIONS", &[]);
+    for link in &exported_links {
+        let id = identifiers.allocate(&format!("_AL-REL-{}-{}-{}", link.source, link.link_type, link.target));
+        w.open("SPEC-RELATION", &[("IDENTIFIER", &id), ("LAST-CHANGE", stamp)]);
+        w.reference("SOURCE", "SPEC-OBJECT-REF", &object_ids[link.source.as_str()]);
+        w.reference("TARGET", "SPEC-OBJECT-REF", &object_ids[link.target.as_str()]);
+        w.reference("TYPE", "SPEC-RELATION-TYPE-REF", &relation_types[link.link_type.as_str()]);
+        w.close("SPEC-RELATION");
+    }
+    w.close("SPEC-RELATIONS");
+
+    w.open("SPECIFICATIONS", &[]);
+    for (feature, members) in &roots {
+        let (id, title) = match feature {
+            Some(feature_id) => (*feature_id, registry[*feature_id].title()),
+            None => ("", "Requirements without feature"),
+        };
+        let spec_id = identifiers.allocate(if id.is_empty() { "_AL-SPEC-UNASSIGNED" } else { id });
+        w.open("SPECIFICATION", &[("IDENTIFIER", &spec_id), ("LONG-NAME", title), ("LAST-CHANGE", stamp)]);
+        w.open("VALUES", &[]);
+        attribute_value(&mut w, "_AL-AD-SPEC-ID", id, "STRING");
+        attribute_value(&mut w, "_AL-AD-SPEC-NAME", title, "STRING");
+        w.close("VALUES");
+        w.reference("TYPE", "SPECIFICATION-TYPE-REF", "_AL-ST-SPEC");
+        w.open("CHILDREN", &[]);
+        let mut visited = HashSet::new();
+        for member in members {
+            write_hierarchy(&mut w, member, &children, &object_ids, &mut identifiers, &mut visited, stamp);
+        }
+        w.close("CHILDREN");
+        w.close("SPECIFICATION");
+    }
+    w.close("SPECIFICATIONS");
+
+    w.close("REQ-IF-CONTENT");
+    w.close("CORE-CONTENT");
+    w.close("REQ-IF");
+    w.out
+}
+
+fn write_hierarchy<'a>(
+    w: &mut Writer,
+    id: &'a str,
+    children: &BTreeMap<&str, Vec<&'a str>>,
+    object_ids: &HashMap<&str, String>,
+    identifiers: &mut Identifiers,
+    visited: &mut HashSet<&'a str>,
+    stamp: &str,
+) {
+    if !visited.insert(id) {
+        return;
+    }
+    let hierarchy_id = identifiers.allocate(&format!("_AL-H-{}", id));
+    w.open("SPEC-HIERARCHY", &[("IDENTIFIER", &hierarchy_id), ("LAST-CHANGE", stamp)]);
+    if let Some(below) = children.get(id) {
+        w.open("CHILDREN", &[]);
+        for child in below {
+            write_hierarchy(w, child, children, object_ids, identifiers, visited, stamp);
+        }
+        w.close("CHILDREN");
+    }
+    w.reference("OBJECT", "SPEC-OBJECT-REF", &object_ids[id]);
+    w.close("SPEC-HIERARCHY");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DOORS_EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <CORE-CONTENT><REQ-IF-CONTENT>
+    <DATATYPES>
+      <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="dt-prio" LAST-CHANGE="2024-01-01T00:00:00Z">
+        <SPECIFIED-VALUES>
+          <ENUM-VALUE IDENTIFIER="ev-high" LONG-NAME="High" LAST-CHANGE="2024-01-01T00:00:00Z"/>
+        </SPECIFIED-VALUES>
+      </DATATYPE-DEFINITION-ENUMERATION>
+    </DATATYPES>
+    <SPEC-TYPES>
+      <SPEC-OBJECT-TYPE IDENTIFIER="ot" LAST-CHANGE="2024-01-01T00:00:00Z"><SPEC-ATTRIBUTES>
+        <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-id" LONG-NAME="ReqIF.ForeignID"/>
+        <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="ad-text" LONG-NAME="ReqIF.Text"/>
+        <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="ad-prio" LONG-NAME="Priority"/>
+      </SPEC-ATTRIBUTES></SPEC-OBJECT-TYPE>
+      <SPEC-RELATION-TYPE IDENTIFIER="rt" LONG-NAME="refines"/>
+    </SPEC-TYPES>
+    <SPEC-OBJECTS>
+      <SPEC-OBJECT IDENTIFIER="_g1" LONG-NAME="Login"><VALUES>
+        <ATTRIBUTE-VALUE-STRING THE-VALUE="OEM-1"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-id</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>
+        <ATTRIBUTE-VALUE-XHTML><DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+          <THE-VALUE><xhtml:div><xhtml:p>The user shall</xhtml:p><xhtml:p>log <xhtml:b>in</xhtml:b>.</xhtml:p></xhtml:div></THE-VALUE></ATTRIBUTE-VALUE-XHTML>
+        <ATTRIBUTE-VALUE-ENUMERATION><DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>ad-prio</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+          <VALUES><ENUM-VALUE-REF>ev-high</ENUM-VALUE-REF></VALUES></ATTRIBUTE-VALUE-ENUMERATION>
+      </VALUES></SPEC-OBJECT>
+      <SPEC-OBJECT IDENTIFIER="_g2" LONG-NAME="Password"/>
+    </SPEC-OBJECTS>
+    <SPEC-RELATIONS>
+      <SPEC-RELATION IDENTIFIER="r1"><SOURCE><SPEC-OBJECT-REF>_g2</SPEC-OBJECT-REF></SOURCE>
+        <TARGET><SPEC-OBJECT-REF>_g1</SPEC-OBJECT-REF></TARGET><TYPE><SPEC-RELATION-TYPE-REF>rt</SPEC-RELATION-TYPE-REF></TYPE></SPEC-RELATION>
+    </SPEC-RELATIONS>
+    <SPECIFICATIONS>
+      <SPECIFICATION IDENTIFIER="spec-auth" LONG-NAME="Authentication"><CHILDREN>
+        <SPEC-HIERARCHY IDENTIFIER="h1"><OBJECT><SPEC-OBJECT-REF>_g1</SPEC-OBJECT-REF></OBJECT>
+          <CHILDREN><SPEC-HIERARCHY IDENTIFIER="h2"><OBJECT><SPEC-OBJECT-REF>_g2</SPEC-OBJECT-REF></OBJECT></SPEC-HIERARCHY></CHILDREN>
+        </SPEC-HIERARCHY>
+      </CHILDREN></SPECIFICATION>
+    </SPECIFICATIONS>
+  </REQ-IF-CONTENT></CORE-CONTENT>
+</REQ-IF>"#;
+
+    #[test]
+    fn test_import_tool_export() {
+        let mut options = ReqifOptions::default();
+        options.relation_types.insert("refines".to_string(), "derives_from".to_string());
+        let import = import_reqif(DOORS_EXPORT, &options).unwrap();
+
+        let login = import.registry["OEM-1"].as_req().unwrap();
+        assert_eq!(login.title, "Login");
+        assert_eq!(login.body, json!("The user shall\nlog in."));
+        assert_eq!(login.tags["Priority"], json!("High"));
+        assert_eq!(login.parent.as_deref(), Some("spec-auth"));
+        let password = import.registry["_g2"].as_req().unwrap();
+        assert_eq!(password.derives_from.as_deref(), Some("OEM-1"));
+        assert_eq!(import.registry["spec-auth"].title(), "Authentication");
+        assert_eq!(import.links.len(), 1);
+        assert_eq!(import.links[0].link_type, "derives_from");
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let registry: Registry = serde_json::from_value(json!({
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "title": "Authentication", "parent": null},
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login & logout", "parent": "F-AUTH",
+                      "tags": {"safety": "ASIL-B", "priority": 2, "variant": ["eu", "us"]},
+                      "body": {"func": "text", "text": "The user shall <log in>"}},
+            "REQ-1.1": {"type": "req", "id": "REQ-1.1", "title": "Password", "derives_from": "REQ-1",
+                        "tags": {"safety": "ASIL-B"}},
+            "REQ:2": {"type": "req", "id": "REQ:2", "title": "Orphan", "parent": null},
+            "UC-1": {"type": "use_case", "id": "UC-1", "title": "Log in"}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "REQ:2", "type": "derives_from", "target": "REQ-1"},
+            {"source": "UC-1", "type": "trace", "target": "REQ-1"}
+        ]))
+        .unwrap();
+
+        let text = export_reqif(&registry, &links, &ReqifOptions::default());
+        let import = import_reqif(&text, &ReqifOptions::default()).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+        let ids: Vec<&String> = import.registry.keys().collect();
+        assert_eq!(ids, vec!["F-AUTH", "REQ-1", "REQ-1.1", "REQ:2"]);
+        let req = import.registry["REQ-1"].as_req().unwrap();
+        assert_eq!(req.title, "Login & logout");
+        assert_eq!(req.parent.as_deref(), Some("F-AUTH"));
+        assert_eq!(req.body, json!("The user shall <log in>"));
+        assert_eq!(req.tags["priority"], json!(2));
+        assert_eq!(req.tags["variant"], json!(["eu", "us"]));
+        assert_eq!(
+            import.registry["REQ-1.1"].as_req().unwrap().derives_from.as_deref(),
+            Some("REQ-1")
+        );
+        // The derives_from link is carried by the hierarchy
+        let orphan = import.registry["REQ:2"].as_req().unwrap();
+        assert_eq!(orphan.parent, None);
+        assert_eq!(orphan.derives_from.as_deref(), Some("REQ-1"));
+        assert!(import.links.is_empty(), "{:?}", import.links);
+    }
+
+    #[test]
+    fn test_export_round_trip_from_link_records() {
+        // Typst models carry belongs_to/derives_from as link records only
+        let registry: Registry = serde_json::from_value(json!({
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "title": "Authentication", "parent": null},
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login"},
+            "REQ-2": {"type": "req", "id": "REQ-2", "title": "Password"},
+            "REQ-3": {"type": "req", "id": "REQ-3", "title": "Lockout"}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "REQ-1", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "REQ-2", "type": "derives_from", "target": "REQ-1"},
+            {"source": "REQ-3", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "REQ-3", "type": "trace", "target": "REQ-1"}
+        ]))
+        .unwrap();
+
+        let text = export_reqif(&registry, &links, &ReqifOptions::default());
+        let import = import_reqif(&text, &ReqifOptions::default()).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+        assert!(matches!(import.registry["F-AUTH"], Element::Feature(_)));
+        let parent = |id: &str| import.registry[id].as_req().unwrap().parent.clone();
+        assert_eq!(parent("REQ-1").as_deref(), Some("F-AUTH"));
+        assert_eq!(parent("REQ-3").as_deref(), Some("F-AUTH"));
+        assert_eq!(import.registry["REQ-2"].as_req().unwrap().derives_from.as_deref(), Some("REQ-1"));
+        let relations: Vec<(&str, &str, &str)> = import
+            .links
+            .iter()
+            .map(|l| (l.source.as_str(), l.link_type.as_str(), l.target.as_str()))
+            .collect();
+        assert_eq!(relations, vec![("REQ-3", "trace", "REQ-1")]);
+    }
+
+    #[test]
+    fn test_export_keeps_requirements_in_derivation_cycles() {
+        let registry: Registry = serde_json::from_value(json!({
+            "REQ-A": {"type": "req", "id": "REQ-A", "title": "A"},
+            "REQ-B": {"type": "req", "id": "REQ-B", "title": "B"}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "REQ-A", "type": "derives_from", "target": "REQ-B"},
+            {"source": "REQ-B", "type": "derives_from", "target": "REQ-A"}
+        ]))
+        .unwrap();
+
+        let text = export_reqif(&registry, &links, &ReqifOptions::default());
+        let import = import_reqif(&text, &ReqifOptions::default()).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.registry["REQ-B"].as_req().unwrap().derives_from.as_deref(), Some("REQ-A"));
+        let relations: Vec<(&str, &str, &str)> = import
+            .links
+            .iter()
+            .map(|l| (l.source.as_str(), l.link_type.as_str(), l.target.as_str()))
+            .collect();
+        assert_eq!(relations, vec![("REQ-A", "derives_from", "REQ-B")]);
+    }
+}
@@ -0,0 +1,542 @@
+//! Requirements CSV Round-Trip
+//!
+//! Exports the requirement set for review in spreadsheets and reads the edited
+//! sheet back as a patch.
+//!
+//! Columns: `id`, `title`, `body` (plain text), `type`, `safety`, `security`
+//! (multiple values separated by `;`), `belongs_to`, `derives_from`,
+//! `satisfied_by`, `verified_by` and an empty `comment` column for reviewers.
+//!
+//! On import, rows are compared with the current model: changed titles, tags
+//! (`type`, `safety`, `security`) and parents become patch entries, a non-empty
+//! `comment` a new review comment. `body`, `satisfied_by` and `verified_by` are
+//! read-only. Unknown IDs, broken parent references and a requirement left
+//! with both or neither of `belongs_to`/`derives_from` reject the import;
+//! errors carry the spreadsheet row number (the header is row 1). Comma and
+//! semicolon separated files are accepted.
+//!
+//! Applying a patch replaces the requirement's `belongs_to`/`derives_from`
+//! link records (or the `parent`/`derives_from` field where the relation is
+//! stored there).
+
+use crate::model_graph::ModelGraph;
+use crate::types::{Element, ElementCore, LinkKind};
+use crate::{Link, Registry};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Exported columns, in order
+pub const COLUMNS: [&str; 11] = [
+    "id",
+    "title",
+    "body",
+    "type",
+    "safety",
+    "security",
+    "belongs_to",
+    "derives_from",
+    "satisfied_by",
+    "verified_by",
+    "comment",
+];
+
+/// Tag keys with their own column
+const TAG_COLUMNS: [&str; 3] = ["type", "safety", "security"];
+
+/// Tag collecting review comments when a patch is applied
+pub const REVIEW_COMMENTS_TAG: &str = "review-comments";
+
+/// Changes to one requirement read from an edited sheet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequirementPatch {
+    pub id: String,
+    /// Spreadsheet row the changes come from
+    pub row: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Changed tags; `null` removes the tag
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, serde_json::Value>,
+    /// New owning feature; empty to remove
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub belongs_to: Option<String>,
+    /// New parent requirement; empty to remove
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derives_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl RequirementPatch {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.tags.is_empty()
+            && self.belongs_to.is_none()
+            && self.derives_from.is_none()
+            && self.comment.is_none()
+    }
+}
+
+// ============================================================================
+// CSV text
+// ============================================================================
+
+fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse CSV records (RFC 4180 quoting); the delimiter is `,` unless the header only has `;`
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let header = text.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(';') && !header.contains(',') { ';' } else { ',' };
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, c) if c == delimiter => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("row {}: unterminated quoted field", records.len() + 1));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+fn tag_cell(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .map(|item| tag_cell(Some(item)))
+            .collect::<Vec<_>>()
+            .join("; "),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Linked IDs of one kind and direction, sorted and `;`-joined
+fn linked(graph: &ModelGraph, node: NodeIndex, kind: LinkKind, direction: Direction) -> String {
+    let mut ids: Vec<&str> = graph
+        .edges_directed(node, direction)
+        .filter(|e| LinkKind::parse(e.weight()) == Some(kind))
+        .map(|e| {
+            let other = if direction == Direction::Outgoing { e.target() } else { e.source() };
+            graph[other].0.as_str()
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids.join("; ")
+}
+
+/// Cell values of a requirement, by column
+fn requirement_row(graph: &ModelGraph, node: NodeIndex) -> BTreeMap<&'static str, String> {
+    let (id, element) = &graph[node];
+    let mut row = BTreeMap::new();
+    row.insert("id", id.clone());
+    row.insert("title", element.title().to_string());
+    row.insert("body", element.as_req().map(|r| r.body_text()).unwrap_or_default());
+    for key in TAG_COLUMNS {
+        row.insert(key, tag_cell(element.tags().get(key)));
+    }
+    row.insert("belongs_to", linked(graph, node, LinkKind::BelongsTo, Direction::Outgoing));
+    row.insert("derives_from", linked(graph, node, LinkKind::DerivesFrom, Direction::Outgoing));
+    row.insert("satisfied_by", linked(graph, node, LinkKind::Satisfy, Direction::Incoming));
+    row.insert("verified_by", linked(graph, node, LinkKind::Verify, Direction::Incoming));
+    row.insert("comment", String::new());
+    row
+}
+
+fn requirement_nodes(graph: &ModelGraph) -> BTreeMap<&str, NodeIndex> {
+    graph
+        .node_indices()
+        .filter(|n| matches!(graph[*n].1, Element::Req(_)))
+        .map(|n| (graph[n].0.as_str(), n))
+        .collect()
+}
+
+/// CSV sheet of all requirements, in ID order
+pub fn export_requirements_csv(graph: &ModelGraph) -> String {
+    let mut out = COLUMNS.join(",");
+    out.push('\n');
+    for node in requirement_nodes(graph).values() {
+        let row = requirement_row(graph, *node);
+        let cells: Vec<String> = COLUMNS.iter().map(|c| csv_field(&row[c], ',')).collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Read an edited sheet as a patch, validated against the model
+///
+/// Returns all errors (with row numbers) if any row is invalid.
+pub fn import_requirements_csv(text: &str, graph: &ModelGraph) -> Result<Vec<RequirementPatch>, Vec<String>> {
+    let records = parse_csv(text).map_err(|e| vec![e])?;
+    let Some((header, rows)) = records.split_first() else {
+        return Err(vec!["row 1: missing header".to_string()]);
+    };
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim().to_lowercase(), i))
+        .collect();
+    if !columns.contains_key("id") {
+        return Err(vec!["row 1: missing 'id' column".to_string()]);
+    }
+
+    let requirements = requirement_nodes(graph);
+    let is_feature = |id: &str| graph.node_indices().any(|n| graph[n].0 == id && matches!(graph[n].1, Element::Feature(_)));
+    let mut errors = Vec::new();
+    let mut patches = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, record) in rows.iter().enumerate() {
+        let row = index + 2;
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let cell = |column: &str| {
+            columns
+                .get(column)
+                .map(|i| record.get(*i).map(|c| c.trim().to_string()).unwrap_or_default())
+        };
+
+        let id = cell("id").unwrap_or_default();
+        let Some(&node) = requirements.get(id.as_str()) else {
+            if id.is_empty() {
+                errors.push(format!("row {}: missing requirement ID", row));
+            } else {
+                errors.push(format!("row {}: unknown requirement ID '{}'", row, id));
+            }
+            continue;
+        };
+        if let Some(first) = seen.insert(id.clone(), row) {
+            errors.push(format!("row {}: requirement '{}' already appears in row {}", row, id, first));
+            continue;
+        }
+
+        let current = requirement_row(graph, node);
+        let changed = |column: &str| cell(column).filter(|value| *value != current[column]);
+        let mut patch = RequirementPatch { id: id.clone(), row, ..Default::default() };
+
+        patch.title = changed("title");
+        for key in TAG_COLUMNS {
+            let Some(value) = changed(key) else {
+                continue;
+            };
+            let values: Vec<&str> = value.split(';').map(str::trim).filter(|v| !v.is_empty()).collect();
+            let tag = match values.as_slice() {
+                [] => serde_json::Value::Null,
+                [single] if key != "security" || !graph[node].1.tags().get(key).is_some_and(|t| t.is_array()) => {
+                    serde_json::json!(single)
+                }
+                _ if key == "security" => serde_json::json!(values),
+                _ => serde_json::json!(value),
+            };
+            patch.tags.insert(key.to_string(), tag);
+        }
+
+        if let Some(feature) = changed("belongs_to") {
+            if feature.contains(';') {
+                errors.push(format!("row {}: belongs_to must name a single feature", row));
+            } else if !feature.is_empty() && !is_feature(&feature) {
+                errors.push(format!("row {}: belongs_to '{}' is not a feature", row, feature));
+            }
+            patch.belongs_to = Some(feature);
+        }
+        if let Some(parent) = changed("derives_from") {
+            if parent.contains(';') {
+                errors.push(format!("row {}: derives_from must name a single requirement", row));
+            } else if parent == id {
+                errors.push(format!("row {}: requirement '{}' cannot derive from itself", row, id));
+            } else if !parent.is_empty() && !requirements.contains_key(parent.as_str()) {
+                errors.push(format!("row {}: derives_from '{}' is not a requirement", row, parent));
+            }
+            patch.derives_from = Some(parent);
+        }
+        if patch.belongs_to.is_some() || patch.derives_from.is_some() {
+            let feature = patch.belongs_to.as_deref().unwrap_or(&current["belongs_to"]);
+            let parent = patch.derives_from.as_deref().unwrap_or(&current["derives_from"]);
+            match (feature.is_empty(), parent.is_empty()) {
+                (false, false) => errors.push(format!(
+                    "row {}: requirement '{}' cannot have both belongs_to and derives_from",
+                    row, id
+                )),
+                (true, true) => errors.push(format!(
+                    "row {}: requirement '{}' needs either belongs_to or derives_from",
+                    row, id
+                )),
+                _ => {}
+            }
+        }
+        patch.comment = cell("comment").filter(|c| !c.is_empty());
+
+        if !patch.is_empty() {
+            patches.push(patch);
+        }
+    }
+
+    // New parents must not close a derivation cycle
+    if errors.is_empty() {
+        let mut parents: HashMap<String, String> = requirements
+            .iter()
+            .filter_map(|(id, node)| {
+                let parent = linked(graph, *node, LinkKind::DerivesFrom, Direction::Outgoing);
+                (!parent.is_empty()).then(|| (id.to_string(), parent))
+            })
+            .collect();
+        for patch in &patches {
+            if let Some(parent) = &patch.derives_from {
+                parents.insert(patch.id.clone(), parent.clone());
+            }
+        }
+        for patch in patches.iter().filter(|p| p.derives_from.as_ref().is_some_and(|d| !d.is_empty())) {
+            let mut current = patch.derives_from.clone();
+            let mut steps = 0;
+            while let Some(id) = current.filter(|_| steps <= parents.len()) {
+                if id == patch.id {
+                    errors.push(format!(
+                        "row {}: derives_from '{}' creates a derivation cycle",
+                        patch.row,
+                        patch.derives_from.as_deref().unwrap_or_default()
+                    ));
+                    break;
+                }
+                current = parents.get(&id).cloned();
+                steps += 1;
+            }
+        }
+    }
+
+    if errors.is_empty() { Ok(patches) } else { Err(errors) }
+}
+
+/// Apply patches to a registry and its links (review comments are appended to `review-comments`)
+pub fn apply_requirements_patch(registry: &mut Registry, links: &mut Vec<Link>, patches: &[RequirementPatch]) {
+    for patch in patches {
+        let Some(Element::Req(req)) = registry.get_mut(&patch.id) else {
+            continue;
+        };
+        if let Some(title) = &patch.title {
+            req.title = title.clone();
+        }
+        for (key, value) in &patch.tags {
+            if value.is_null() {
+                req.tags.remove(key);
+            } else {
+                req.tags.insert(key.clone(), value.clone());
+            }
+        }
+        if let Some(feature) = &patch.belongs_to {
+            relink(&mut req.parent, links, &patch.id, LinkKind::BelongsTo, feature);
+        }
+        if let Some(parent) = &patch.derives_from {
+            relink(&mut req.derives_from, links, &patch.id, LinkKind::DerivesFrom, parent);
+        }
+        if let Some(comment) = &patch.comment {
+            let comments = req
+                .tags
+                .entry(REVIEW_COMMENTS_TAG.to_string())
+                .or_insert_with(|| serde_json::json!([]));
+            if let Some(list) = comments.as_array_mut() {
+                list.push(serde_json::json!(comment));
+            }
+        }
+    }
+}
+
+/// Replace the `kind` relation of `id` with `target` (empty removes it)
+///
+/// A relation stored in the element field stays there; otherwise it is a link record.
+fn relink(field: &mut Option<String>, links: &mut Vec<Link>, id: &str, kind: LinkKind, target: &str) {
+    let in_field = field.take().is_some();
+    links.retain(|link| link.source != id || link.kind() != Some(kind));
+    if target.is_empty() {
+        return;
+    }
+    if in_field {
+        *field = Some(target.to_string());
+    } else {
+        links.push(Link { source: id.to_string(), link_type: kind.as_str().to_string(), target: target.to_string() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::{build_model_graph, graph_from_json};
+    use serde_json::json;
+
+    #[test]
+    fn test_export_and_edit_round_trip() {
+        let mut registry: Registry = serde_json::from_value(json!({
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": null},
+            "F-LOG": {"type": "feature", "id": "F-LOG", "parent": null},
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login, securely", "parent": "F-AUTH",
+                      "tags": {"type": "functional", "safety": "ASIL-B", "security": ["confidentiality", "integrity"]},
+                      "body": {"func": "text", "text": "The user shall \"log in\""}},
+            "REQ-1.1": {"type": "req", "id": "REQ-1.1", "title": "Password", "parent": null, "derives_from": "REQ-1"},
+            "TC-1": {"type": "test_case", "id": "TC-1"}
+        }))
+        .unwrap();
+        let mut links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "TC-1", "type": "verify", "target": "REQ-1"}
+        ]))
+        .unwrap();
+        let graph = build_model_graph(&registry, &links).0;
+        let text = export_requirements_csv(&graph);
+        assert_eq!(
+            text.lines().nth(1).unwrap(),
+            "REQ-1,\"Login, securely\",\"The user shall \"\"log in\"\"\",functional,ASIL-B,\
+             confidentiality; integrity,F-AUTH,,,TC-1,"
+        );
+        assert_eq!(import_requirements_csv(&text, &graph).unwrap(), Vec::new());
+
+        // Reviewer edits: semicolon-separated sheet, reordered columns
+        let edited = "id;safety;security;title;belongs_to;derives_from;comment\n\
+                      REQ-1;ASIL-C;integrity;Login, securely;F-AUTH;;Raise to C?\n\
+                      REQ-1.1;;;Password policy;F-LOG;;\n";
+        let patches = import_requirements_csv(edited, &graph).unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].tags["safety"], json!("ASIL-C"));
+        assert_eq!(patches[0].tags["security"], json!(["integrity"]));
+        assert_eq!(patches[0].comment.as_deref(), Some("Raise to C?"));
+        assert_eq!(patches[1].title.as_deref(), Some("Password policy"));
+        assert_eq!(patches[1].belongs_to.as_deref(), Some("F-LOG"));
+        assert_eq!(patches[1].derives_from.as_deref(), Some(""));
+
+        apply_requirements_patch(&mut registry, &mut links, &patches);
+        let req = registry["REQ-1"].as_req().unwrap();
+        assert_eq!(req.tags["safety"], json!("ASIL-C"));
+        assert_eq!(req.tags[REVIEW_COMMENTS_TAG], json!(["Raise to C?"]));
+        let password = registry["REQ-1.1"].as_req().unwrap();
+        assert_eq!(password.derives_from, None);
+        assert_eq!(password.parent, None);
+        let link = links.last().unwrap();
+        assert_eq!((link.source.as_str(), link.link_type.as_str(), link.target.as_str()), ("REQ-1.1", "belongs_to", "F-LOG"));
+    }
+
+    #[test]
+    fn test_patch_replaces_link_records() {
+        // Relations as the Typst document stores them: link records only
+        let registry: Registry = serde_json::from_value(json!({
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": null},
+            "F-LOG": {"type": "feature", "id": "F-LOG", "parent": null},
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login"},
+            "REQ-2": {"type": "req", "id": "REQ-2", "title": "Audit"},
+            "REQ-3": {"type": "req", "id": "REQ-3", "title": "Lockout"}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "REQ-1", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "REQ-2", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "REQ-3", "type": "derives_from", "target": "REQ-1"}
+        ]))
+        .unwrap();
+        let graph = build_model_graph(&registry, &links).0;
+
+        let edited = "id,belongs_to,derives_from\n\
+                      REQ-1,F-LOG,\n\
+                      REQ-2,,REQ-1\n\
+                      REQ-3,,REQ-2\n";
+        let patches = import_requirements_csv(edited, &graph).unwrap();
+        let (mut patched_registry, mut patched_links) = (registry.clone(), links.clone());
+        apply_requirements_patch(&mut patched_registry, &mut patched_links, &patches);
+        let graph = build_model_graph(&patched_registry, &patched_links).0;
+        let text = export_requirements_csv(&graph);
+        let rows: Vec<&str> = text.lines().skip(1).collect();
+        assert_eq!(rows, vec!["REQ-1,Login,,,,,F-LOG,,,,", "REQ-2,Audit,,,,,,REQ-1,,,", "REQ-3,Lockout,,,,,,REQ-2,,,"]);
+        assert_eq!(patched_links.len(), 3);
+
+        // Both or neither relation is rejected
+        let edited = "id,belongs_to,derives_from\nREQ-1,F-AUTH,REQ-2\nREQ-3,,\n";
+        let graph = build_model_graph(&registry, &links).0;
+        let errors = import_requirements_csv(edited, &graph).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "row 2: requirement 'REQ-1' cannot have both belongs_to and derives_from",
+                "row 3: requirement 'REQ-3' needs either belongs_to or derives_from",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rows_are_rejected() {
+        let graph = graph_from_json(
+            json!({
+                "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": null},
+                "F-LOG": {"type": "feature", "id": "F-LOG", "parent": null},
+                "REQ-1": {"type": "req", "id": "REQ-1", "title": "Login, securely", "parent": "F-AUTH",
+                          "tags": {"type": "functional", "safety": "ASIL-B", "security": ["confidentiality", "integrity"]},
+                          "body": {"func": "text", "text": "The user shall \"log in\""}},
+                "REQ-1.1": {"type": "req", "id": "REQ-1.1", "title": "Password", "parent": null, "derives_from": "REQ-1"},
+                "TC-1": {"type": "test_case", "id": "TC-1"}
+            }),
+            json!([
+                {"source": "TC-1", "type": "verify", "target": "REQ-1"}
+            ]),
+        );
+        let edited = "id,title,belongs_to,derives_from\n\
+                      REQ-9,Ghost,,\n\
+                      REQ-1,Login,F-NOPE,\n\
+                      ,\"multi\nline\",,\n\
+                      REQ-1.1,Password,,REQ-1.1\n\
+                      REQ-1,Login,,\n";
+        let errors = import_requirements_csv(edited, &graph).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "row 2: unknown requirement ID 'REQ-9'",
+                "row 3: belongs_to 'F-NOPE' is not a feature",
+                "row 4: missing requirement ID",
+                "row 5: requirement 'REQ-1.1' cannot derive from itself",
+                "row 6: requirement 'REQ-1' already appears in row 3",
+            ]
+        );
+
+        let cycle = "id,belongs_to,derives_from\nREQ-1,,REQ-1.1\n";
+        let errors = import_requirements_csv(cycle, &graph).unwrap_err();
+        assert_eq!(errors, vec!["row 2: derives_from 'REQ-1.1' creates a derivation cycle"]);
+    }
+}
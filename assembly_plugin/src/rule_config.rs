@@ -0,0 +1,315 @@
+//! Rule Configuration and Suppressions
+//!
+//! Lets a project enable or disable individual rule codes, override their
+//! severity, and suppress findings on single elements. Every suppression must
+//! carry a justification; applied suppressions are reported back so they stay
+//! visible in the validation report.
+
+use crate::diagnostics::{rule_info, Diagnostic, Severity};
+use crate::types::Element;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Per-rule setting
+///
+/// Accepts `false`/`true`, a severity string (`"warning"`), or
+/// `{ "enabled": bool, "severity": "..." }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleSetting {
+    Enabled(bool),
+    Severity(Severity),
+    Detailed {
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        #[serde(default)]
+        severity: Option<Severity>,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RuleSetting {
+    pub fn enabled(&self) -> bool {
+        match self {
+            RuleSetting::Enabled(enabled) => *enabled,
+            RuleSetting::Severity(_) => true,
+            RuleSetting::Detailed { enabled, .. } => *enabled,
+        }
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            RuleSetting::Enabled(_) => None,
+            RuleSetting::Severity(severity) => Some(*severity),
+            RuleSetting::Detailed { severity, .. } => *severity,
+        }
+    }
+}
+
+/// Rule configuration - maps rule code (e.g. "AL002") to its setting
+pub type RuleConfig = BTreeMap<String, RuleSetting>;
+
+/// Check whether a rule is enabled (rules are enabled unless configured otherwise)
+pub fn rule_enabled(config: &RuleConfig, code: &str) -> bool {
+    config.get(code).is_none_or(RuleSetting::enabled)
+}
+
+/// Suppression of one rule on one element
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub element_id: String,
+    #[serde(alias = "rule")]
+    pub code: String,
+    #[serde(default)]
+    pub justification: String,
+}
+
+/// Suppression that matched at least one finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedSuppression {
+    pub element_id: String,
+    pub code: String,
+    pub justification: String,
+    /// Number of findings hidden by this suppression
+    pub suppressed: usize,
+}
+
+/// Result of applying rule configuration and suppressions
+#[derive(Debug, Clone, Default)]
+pub struct RuleOutcome {
+    pub diagnostics: Vec<Diagnostic>,
+    pub suppressions: Vec<AppliedSuppression>,
+    /// Findings dropped because their rule is disabled
+    pub disabled: usize,
+}
+
+/// Collect suppressions from `tags.suppress` of all elements plus an explicit list
+///
+/// `tags.suppress` is either a dictionary `{ "AL002": "justification" }` or an
+/// array of `{ "rule": "AL002", "justification": "..." }`. Suppressions
+/// without a justification are rejected with an AL021 diagnostic.
+pub fn collect_suppressions(
+    registry: &HashMap<String, Element>,
+    explicit: &[Suppression],
+) -> (Vec<Suppression>, Vec<Diagnostic>) {
+    let mut candidates: Vec<Suppression> = explicit.to_vec();
+    let mut diagnostics = Vec::new();
+
+    for element in registry.values() {
+        let Some(value) = element.tags().get("suppress") else {
+            continue;
+        };
+        let element_id = element.id().to_string();
+        match value {
+            serde_json::Value::Object(map) => {
+                for (code, justification) in map {
+                    candidates.push(Suppression {
+                        element_id: element_id.clone(),
+                        code: code.clone(),
+                        justification: justification.as_str().unwrap_or_default().to_string(),
+                    });
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    let code = item.get("rule").or_else(|| item.get("code")).and_then(|v| v.as_str());
+                    let justification = item.get("justification").and_then(|v| v.as_str());
+                    match code {
+                        Some(code) => candidates.push(Suppression {
+                            element_id: element_id.clone(),
+                            code: code.to_string(),
+                            justification: justification.unwrap_or_default().to_string(),
+                        }),
+                        None => diagnostics.push(malformed_suppression(&element_id, item)),
+                    }
+                }
+            }
+            other => diagnostics.push(malformed_suppression(&element_id, other)),
+        }
+    }
+
+    let mut suppressions = Vec::new();
+    for suppression in candidates {
+        if suppression.justification.trim().is_empty() {
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL021",
+                    format!(
+                        "Suppression of {} on {} has no justification and was not applied",
+                        suppression.code, suppression.element_id
+                    ),
+                )
+                .on(&suppression.element_id)
+                .hint("Give every suppression a justification explaining why the finding is acceptable"),
+            );
+        } else {
+            suppressions.push(suppression);
+        }
+    }
+    suppressions.sort_by(|a, b| (&a.element_id, &a.code).cmp(&(&b.element_id, &b.code)));
+    suppressions.dedup_by(|a, b| a.element_id == b.element_id && a.code == b.code);
+
+    (suppressions, diagnostics)
+}
+
+fn malformed_suppression(element_id: &str, value: &serde_json::Value) -> Diagnostic {
+    Diagnostic::new(
+        "AL021",
+        format!("Malformed suppression on {}: {}", element_id, value),
+    )
+    .on(element_id)
+    .hint("Use suppress: (AL002: \"justification\") or ((rule: \"AL002\", justification: \"...\"),)")
+}
+
+/// Apply rule configuration (enable/disable, severity) and suppressions to diagnostics
+///
+/// `custom_codes` are the IDs of user-defined rules, which may be configured
+/// like built-in ones. Unknown rule codes in the configuration produce AL020,
+/// suppressions that match nothing produce AL022.
+pub fn apply_rule_config(
+    mut diagnostics: Vec<Diagnostic>,
+    config: &RuleConfig,
+    suppressions: &[Suppression],
+    custom_codes: &[&str],
+) -> RuleOutcome {
+    for code in config.keys() {
+        if rule_info(code).is_none() && !custom_codes.contains(&code.as_str()) {
+            diagnostics.push(
+                Diagnostic::new("AL020", format!("Rule configuration names unknown rule '{}'", code))
+                    .hint("Rule codes are listed in the diagnostics reference (AL001, AL002, ...)"),
+            );
+        }
+    }
+
+    let mut outcome = RuleOutcome::default();
+    let mut counts = vec![0usize; suppressions.len()];
+    for mut diagnostic in diagnostics {
+        if let Some(setting) = config.get(&diagnostic.code) {
+            if !setting.enabled() {
+                outcome.disabled += 1;
+                continue;
+            }
+            if let Some(severity) = setting.severity() {
+                diagnostic.severity = severity;
+            }
+        }
+
+        let matched = suppressions.iter().position(|s| {
+            s.code == diagnostic.code && diagnostic.element_id.as_deref() == Some(s.element_id.as_str())
+        });
+        match matched {
+            Some(index) => counts[index] += 1,
+            None => outcome.diagnostics.push(diagnostic),
+        }
+    }
+
+    for (suppression, count) in suppressions.iter().zip(counts) {
+        if count > 0 {
+            outcome.suppressions.push(AppliedSuppression {
+                element_id: suppression.element_id.clone(),
+                code: suppression.code.clone(),
+                justification: suppression.justification.clone(),
+                suppressed: count,
+            });
+        } else if rule_enabled(config, "AL022") {
+            outcome.diagnostics.push(
+                Diagnostic::new(
+                    "AL022",
+                    format!(
+                        "Suppression of {} on {} did not match any finding",
+                        suppression.code, suppression.element_id
+                    ),
+                )
+                .on(&suppression.element_id)
+                .hint("Remove the stale suppression"),
+            );
+        }
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::UseCaseElement;
+    use serde_json::json;
+
+    fn create_test_use_case(id: &str, suppress: Option<serde_json::Value>) -> Element {
+        let mut tags = HashMap::new();
+        if let Some(value) = suppress {
+            tags.insert("suppress".to_string(), value);
+        }
+        Element::UseCase(UseCaseElement {
+            id: id.to_string(),
+            title: id.to_string(),
+            tags,
+            body: json!({}),
+        })
+    }
+
+    #[test]
+    fn test_rule_settings_change_severity_and_disable() {
+        let config: RuleConfig = serde_json::from_value(json!({
+            "AL002": "warning",
+            "AL006": false,
+            "AL005": { "severity": "info" },
+            "AL999": true
+        }))
+        .unwrap();
+
+        let diagnostics = vec![
+            Diagnostic::new("AL002", "not traced").on("UC-1"),
+            Diagnostic::new("AL006", "small group").on("F-1"),
+            Diagnostic::new("AL005", "missing parent").on("F-2"),
+        ];
+        let outcome = apply_rule_config(diagnostics, &config, &[], &[]);
+
+        assert_eq!(outcome.disabled, 1);
+        let by_code: HashMap<&str, Severity> = outcome
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.severity))
+            .collect();
+        assert_eq!(by_code["AL002"], Severity::Warning);
+        assert_eq!(by_code["AL005"], Severity::Info);
+        assert_eq!(by_code["AL020"], Severity::Warning);
+        assert!(!by_code.contains_key("AL006"));
+    }
+
+    #[test]
+    fn test_suppressions_require_justification_and_are_reported() {
+        let mut registry = HashMap::new();
+        registry.insert(
+            "UC-1".to_string(),
+            create_test_use_case("UC-1", Some(json!({ "AL002": "Concept phase, traced in v2" }))),
+        );
+        registry.insert(
+            "UC-2".to_string(),
+            create_test_use_case("UC-2", Some(json!([{ "rule": "AL002" }]))),
+        );
+        registry.insert(
+            "UC-3".to_string(),
+            create_test_use_case("UC-3", Some(json!({ "AL001": "stale" }))),
+        );
+
+        let (suppressions, mut diagnostics) = collect_suppressions(&registry, &[]);
+        assert_eq!(suppressions.len(), 2);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "AL021");
+
+        diagnostics.push(Diagnostic::new("AL002", "not traced").on("UC-1"));
+        diagnostics.push(Diagnostic::new("AL002", "not traced").on("UC-2"));
+        let outcome = apply_rule_config(diagnostics, &RuleConfig::new(), &suppressions, &[]);
+
+        assert_eq!(outcome.suppressions.len(), 1);
+        assert_eq!(outcome.suppressions[0].element_id, "UC-1");
+        assert_eq!(outcome.suppressions[0].suppressed, 1);
+        let mut codes: Vec<&str> = outcome.diagnostics.iter().map(|d| d.code.as_str()).collect();
+        codes.sort();
+        assert_eq!(codes, vec!["AL002", "AL021", "AL022"]);
+    }
+}
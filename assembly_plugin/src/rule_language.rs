@@ -0,0 +1,797 @@
+//! User Rule Language
+//!
+//! Project-specific traceability checks, written as quantified expressions over
+//! element types, element fields/tags and link paths, and evaluated over the
+//! link graph built by `validate_rules`.
+//!
+//! ```text
+//! forall r: req where r.tags.safety in ["ASIL-C", "ASIL-D"]
+//!     => exists t: test_case in r <-verify- where t.tags.method == "HIL"
+//!
+//! forall b: block_definition where b.tags.stereotype == "software"
+//!     => exists i: implementation in b <-satisfy-
+//! ```
+//!
+//! Grammar:
+//! - Quantifier: `forall|exists|none VAR [: TYPE] [in PATH] [where COND] [=> COND]`.
+//!   `where` filters the candidates, `=>` is the condition checked on them
+//!   (for `forall` without `=>`, the `where` clause is the condition).
+//!   `TYPE` is an element type name (`req`, `test_case`, ...) or `*`.
+//! - Path: `VAR` followed by steps `-kind->` (outgoing) or `<-kind-` (incoming);
+//!   `*` matches any link kind and a trailing `+` follows the step transitively.
+//! - Conditions: `and`, `or`, `not`, parentheses, `has REF`,
+//!   `OPERAND (== | != | < | <= | > | >=) OPERAND`, `REF in [literals]`,
+//!   `REF contains literal`, `count(PATH [: TYPE]) OP INT`.
+//! - References: `VAR.field`, `VAR.tags.key`, `VAR.tags["kebab-key"]`, `VAR.type`.
+//!
+//! A top-level `forall` reports each element failing the condition, `none`
+//! each element matching it, `exists` one model-wide violation if nothing matches.
+
+use crate::constraint_parser::CompOp;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::types::Element;
+use crate::model_graph::ModelGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A user-defined rule as passed in the validation input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserRule {
+    /// Rule code reported in diagnostics, e.g. "PRJ-SAFETY-1"
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    /// Rule text in the rule language
+    pub rule: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Violation message; `{id}` and `{title}` are replaced with the offending element
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub hint: Option<String>,
+}
+
+fn default_severity() -> Severity {
+    Severity::Error
+}
+
+/// Quantifier kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    ForAll,
+    Exists,
+    None,
+}
+
+/// One step of a link path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStep {
+    /// Link kind, `None` for any kind (`*`)
+    pub kind: Option<String>,
+    pub direction: Direction,
+    /// Follow the step one or more times (`+`)
+    pub transitive: bool,
+}
+
+/// Link path starting at a bound variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathExpr {
+    pub start: String,
+    pub steps: Vec<PathStep>,
+}
+
+/// Comparison operand
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    /// Field reference: variable plus JSON path into the element (`r.tags.safety`)
+    Ref { var: String, path: Vec<String> },
+    Literal(serde_json::Value),
+}
+
+/// Rule expression AST
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleExpr {
+    Quantified {
+        quantifier: Quantifier,
+        var: String,
+        element_type: Option<String>,
+        path: Option<PathExpr>,
+        filter: Option<Box<RuleExpr>>,
+        body: Option<Box<RuleExpr>>,
+    },
+    And(Box<RuleExpr>, Box<RuleExpr>),
+    Or(Box<RuleExpr>, Box<RuleExpr>),
+    Not(Box<RuleExpr>),
+    Compare { op: CompOp, left: Operand, right: Operand },
+    In { value: Operand, list: Vec<serde_json::Value> },
+    Contains { value: Operand, item: serde_json::Value },
+    Has(Operand),
+    Count { path: PathExpr, element_type: Option<String>, op: CompOp, value: i64 },
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Sym(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "=>", "->", "<-", "==", "!=", "<=", ">=", "(", ")", "[", "]", ",", ":", ".", "<", ">", "-", "+", "*", "|",
+];
+
+/// Split rule or query text into tokens with their (0-based) column
+pub(crate) fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(format!("Unterminated string at column {}", start + 1)),
+                    Some('"') => break,
+                    Some('\\') => {
+                        i += 1;
+                        s.extend(chars.get(i));
+                    }
+                    Some(&ch) => s.push(ch),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((Token::Str(s), start));
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text
+                .parse()
+                .map_err(|_| format!("Invalid number '{}' at column {}", text, start + 1))?;
+            tokens.push((Token::Num(n), start));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+                return Err(format!("Unexpected character '{}' at column {}", c, start + 1));
+            };
+            i += sym.chars().count();
+            tokens.push((Token::Sym(sym), start));
+        }
+    }
+    Ok(tokens)
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+/// Parse a rule; the top level must be a quantifier without a path
+pub fn parse_rule(input: &str) -> Result<RuleExpr, String> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0, scope: Vec::new() };
+    let expr = parser.parse_quantifier()?;
+    if let Some((token, col)) = parser.tokens.get(parser.pos) {
+        return Err(format!("Unexpected {:?} at column {}", token, col + 1));
+    }
+    if let RuleExpr::Quantified { path: Some(_), .. } = expr {
+        return Err("Top-level quantifier cannot have an 'in' path".to_string());
+    }
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Variables bound by enclosing quantifiers
+    scope: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((token, col)) => format!("Expected {} at column {}, found {:?}", expected, col + 1, token),
+            None => format!("Expected {} at end of rule", expected),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s == keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Sym(s)) if *s == sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_sym(&mut self, sym: &str) -> Result<(), String> {
+        if self.eat_sym(sym) { Ok(()) } else { Err(self.error(&format!("'{}'", sym))) }
+    }
+
+    fn expect_ident(&mut self, what: &str) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Ident(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            }
+            _ => Err(self.error(what)),
+        }
+    }
+
+    fn expect_bound_var(&mut self) -> Result<String, String> {
+        let col = self.tokens.get(self.pos).map(|(_, c)| c + 1);
+        let var = self.expect_ident("variable")?;
+        if !self.scope.contains(&var) {
+            return Err(format!("Unbound variable '{}' at column {}", var, col.unwrap_or(0)));
+        }
+        Ok(var)
+    }
+
+    fn parse_type(&mut self) -> Result<Option<String>, String> {
+        if self.eat_sym("*") {
+            Ok(None)
+        } else {
+            self.expect_ident("element type").map(Some)
+        }
+    }
+
+    fn parse_quantifier(&mut self) -> Result<RuleExpr, String> {
+        let quantifier = if self.eat_keyword("forall") {
+            Quantifier::ForAll
+        } else if self.eat_keyword("exists") {
+            Quantifier::Exists
+        } else if self.eat_keyword("none") {
+            Quantifier::None
+        } else {
+            return Err(self.error("'forall', 'exists' or 'none'"));
+        };
+
+        let var = self.expect_ident("variable name")?;
+        let element_type = if self.eat_sym(":") { self.parse_type()? } else { None };
+        let path = if self.eat_keyword("in") { Some(self.parse_path()?) } else { None };
+
+        self.scope.push(var.clone());
+        let filter = if self.eat_keyword("where") { Some(Box::new(self.parse_or()?)) } else { None };
+        let body = if self.eat_sym("=>") { Some(Box::new(self.parse_or()?)) } else { None };
+        self.scope.pop();
+
+        Ok(RuleExpr::Quantified { quantifier, var, element_type, path, filter, body })
+    }
+
+    fn parse_path(&mut self) -> Result<PathExpr, String> {
+        let start = self.expect_bound_var()?;
+        let mut steps = Vec::new();
+        loop {
+            let direction = if self.eat_sym("-") {
+                Direction::Outgoing
+            } else if self.eat_sym("<-") {
+                Direction::Incoming
+            } else {
+                break;
+            };
+            let kind = if self.eat_sym("*") { None } else { Some(self.expect_ident("link kind")?) };
+            match direction {
+                Direction::Outgoing => self.expect_sym("->")?,
+                Direction::Incoming => self.expect_sym("-")?,
+            }
+            let transitive = self.eat_sym("+");
+            steps.push(PathStep { kind, direction, transitive });
+        }
+        if steps.is_empty() {
+            return Err(self.error("path step '-kind->' or '<-kind-'"));
+        }
+        Ok(PathExpr { start, steps })
+    }
+
+    fn parse_or(&mut self) -> Result<RuleExpr, String> {
+        let mut left = self.parse_and()?;
+        while self.eat_keyword("or") {
+            left = RuleExpr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<RuleExpr, String> {
+        let mut left = self.parse_not()?;
+        while self.eat_keyword("and") {
+            left = RuleExpr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<RuleExpr, String> {
+        if self.eat_keyword("not") {
+            return Ok(RuleExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<RuleExpr, String> {
+        if self.eat_sym("(") {
+            let expr = self.parse_or()?;
+            self.expect_sym(")")?;
+            return Ok(expr);
+        }
+        if self.is_keyword("forall") || self.is_keyword("exists") || self.is_keyword("none") {
+            return self.parse_quantifier();
+        }
+        if self.eat_keyword("has") {
+            return Ok(RuleExpr::Has(self.parse_ref()?));
+        }
+        if self.eat_keyword("count") {
+            self.expect_sym("(")?;
+            let path = self.parse_path()?;
+            let element_type = if self.eat_sym(":") { self.parse_type()? } else { None };
+            self.expect_sym(")")?;
+            let op = self.parse_comp_op()?;
+            let value = match self.parse_literal()? {
+                serde_json::Value::Number(n) if n.is_i64() => n.as_i64().unwrap(),
+                _ => return Err("count() must be compared with an integer".to_string()),
+            };
+            return Ok(RuleExpr::Count { path, element_type, op, value });
+        }
+
+        let left = self.parse_operand()?;
+        if self.eat_keyword("in") {
+            self.expect_sym("[")?;
+            let mut list = Vec::new();
+            if !self.eat_sym("]") {
+                loop {
+                    list.push(self.parse_literal()?);
+                    if self.eat_sym("]") {
+                        break;
+                    }
+                    self.expect_sym(",")?;
+                }
+            }
+            return Ok(RuleExpr::In { value: left, list });
+        }
+        if self.eat_keyword("contains") {
+            return Ok(RuleExpr::Contains { value: left, item: self.parse_literal()? });
+        }
+        let op = self.parse_comp_op()?;
+        let right = self.parse_operand()?;
+        Ok(RuleExpr::Compare { op, left, right })
+    }
+
+    fn parse_comp_op(&mut self) -> Result<CompOp, String> {
+        for (sym, op) in [
+            ("==", CompOp::Eq),
+            ("!=", CompOp::Ne),
+            ("<=", CompOp::Le),
+            (">=", CompOp::Ge),
+            ("<", CompOp::Lt),
+            (">", CompOp::Gt),
+        ] {
+            if self.eat_sym(sym) {
+                return Ok(op);
+            }
+        }
+        Err(self.error("comparison operator"))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.peek() {
+            Some(Token::Ident(s)) if !matches!(s.as_str(), "true" | "false" | "null") => self.parse_ref(),
+            _ => self.parse_literal().map(Operand::Literal),
+        }
+    }
+
+    fn parse_ref(&mut self) -> Result<Operand, String> {
+        let var = self.expect_bound_var()?;
+        let mut path = Vec::new();
+        loop {
+            if self.eat_sym(".") {
+                path.push(self.expect_ident("field name")?);
+            } else if self.eat_sym("[") {
+                match self.peek() {
+                    Some(Token::Str(s)) => {
+                        path.push(s.clone());
+                        self.pos += 1;
+                    }
+                    _ => return Err(self.error("quoted key")),
+                }
+                self.expect_sym("]")?;
+            } else {
+                break;
+            }
+        }
+        if path.is_empty() {
+            return Err(format!("Reference to '{}' needs a field, e.g. {}.id", var, var));
+        }
+        Ok(Operand::Ref { var, path })
+    }
+
+    fn parse_literal(&mut self) -> Result<serde_json::Value, String> {
+        let negative = self.eat_sym("-");
+        let value = match self.peek() {
+            Some(Token::Str(s)) if !negative => serde_json::Value::String(s.clone()),
+            Some(Token::Num(n)) => {
+                let n = if negative { -n } else { *n };
+                if n.fract() == 0.0 {
+                    serde_json::Value::from(n as i64)
+                } else {
+                    serde_json::Value::from(n)
+                }
+            }
+            Some(Token::Ident(s)) if !negative && s == "true" => serde_json::Value::Bool(true),
+            Some(Token::Ident(s)) if !negative && s == "false" => serde_json::Value::Bool(false),
+            Some(Token::Ident(s)) if !negative && s == "null" => serde_json::Value::Null,
+            _ => return Err(self.error("literal")),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+}
+
+// ============================================================================
+// Evaluator
+// ============================================================================
+
+struct Evaluator<'a> {
+    graph: &'a ModelGraph,
+    /// Elements serialized to JSON once, for field references
+    json: HashMap<NodeIndex, serde_json::Value>,
+}
+
+type Bindings = Vec<(String, NodeIndex)>;
+
+impl<'a> Evaluator<'a> {
+    fn new(graph: &'a ModelGraph) -> Self {
+        let json = graph
+            .node_indices()
+            .map(|n| (n, serde_json::to_value(&graph[n].1).unwrap_or_default()))
+            .collect();
+        Evaluator { graph, json }
+    }
+
+    fn lookup(bindings: &Bindings, var: &str) -> NodeIndex {
+        bindings.iter().rev().find(|(v, _)| v == var).map(|(_, n)| *n).expect("variable bound by parser")
+    }
+
+    fn type_matches(&self, node: NodeIndex, element_type: &Option<String>) -> bool {
+        element_type.as_ref().is_none_or(|t| self.graph[node].1.type_name() == t)
+    }
+
+    /// Candidates of a quantifier, sorted by element ID
+    fn candidates(&self, path: &Option<PathExpr>, element_type: &Option<String>, bindings: &Bindings) -> Vec<NodeIndex> {
+        let mut nodes: Vec<NodeIndex> = match path {
+            Some(path) => self.follow(path, bindings).into_iter().collect(),
+            None => self.graph.node_indices().collect(),
+        };
+        nodes.retain(|&n| self.type_matches(n, element_type));
+        nodes.sort_by(|a, b| self.graph[*a].0.cmp(&self.graph[*b].0));
+        nodes
+    }
+
+    fn follow(&self, path: &PathExpr, bindings: &Bindings) -> HashSet<NodeIndex> {
+        let mut current: HashSet<NodeIndex> = [Self::lookup(bindings, &path.start)].into();
+        for step in &path.steps {
+            let mut next = HashSet::new();
+            let mut frontier: Vec<NodeIndex> = current.into_iter().collect();
+            while let Some(node) = frontier.pop() {
+                for edge in self.graph.edges_directed(node, step.direction) {
+                    if step.kind.as_ref().is_some_and(|k| k != edge.weight()) {
+                        continue;
+                    }
+                    let other = match step.direction {
+                        Direction::Outgoing => edge.target(),
+                        Direction::Incoming => edge.source(),
+                    };
+                    if next.insert(other) && step.transitive {
+                        frontier.push(other);
+                    }
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    fn resolve(&self, operand: &Operand, bindings: &Bindings) -> Option<serde_json::Value> {
+        match operand {
+            Operand::Literal(value) => Some(value.clone()),
+            Operand::Ref { var, path } => {
+                let node = Self::lookup(bindings, var);
+                if path.len() == 1 && path[0] == "type" {
+                    return Some(serde_json::Value::String(self.graph[node].1.type_name().to_string()));
+                }
+                let mut value = &self.json[&node];
+                for key in path {
+                    value = value.get(key)?;
+                }
+                (!value.is_null()).then(|| value.clone())
+            }
+        }
+    }
+
+    fn eval(&self, expr: &RuleExpr, bindings: &mut Bindings) -> bool {
+        match expr {
+            RuleExpr::Quantified { quantifier, var, element_type, path, filter, body } => {
+                let candidates = self.candidates(path, element_type, bindings);
+                let (filter, body) = match (quantifier, filter, body) {
+                    (Quantifier::ForAll, Some(f), None) => (None, Some(f)),
+                    _ => (filter.as_ref(), body.as_ref()),
+                };
+                let mut matching = candidates.into_iter().filter_map(|node| {
+                    bindings.push((var.clone(), node));
+                    let selected = filter.is_none_or(|f| self.eval(f, bindings));
+                    let holds = selected.then(|| body.is_none_or(|b| self.eval(b, bindings)));
+                    bindings.pop();
+                    holds
+                });
+                match quantifier {
+                    Quantifier::ForAll => matching.all(|holds| holds),
+                    Quantifier::Exists => matching.any(|holds| holds),
+                    Quantifier::None => !matching.any(|holds| holds),
+                }
+            }
+            RuleExpr::And(left, right) => self.eval(left, bindings) && self.eval(right, bindings),
+            RuleExpr::Or(left, right) => self.eval(left, bindings) || self.eval(right, bindings),
+            RuleExpr::Not(inner) => !self.eval(inner, bindings),
+            RuleExpr::Has(operand) => self.resolve(operand, bindings).is_some(),
+            RuleExpr::Compare { op, left, right } => {
+                let left = self.resolve(left, bindings);
+                let right = self.resolve(right, bindings);
+                match (left, right) {
+                    (Some(l), Some(r)) => compare(op, &l, &r),
+                    (l, r) => *op == CompOp::Ne && l != r,
+                }
+            }
+            RuleExpr::In { value, list } => self
+                .resolve(value, bindings)
+                .is_some_and(|v| list.iter().any(|item| compare(&CompOp::Eq, &v, item))),
+            RuleExpr::Contains { value, item } => match self.resolve(value, bindings) {
+                Some(serde_json::Value::Array(items)) => items.iter().any(|v| compare(&CompOp::Eq, v, item)),
+                Some(serde_json::Value::String(s)) => item.as_str().is_some_and(|i| s.contains(i)),
+                _ => false,
+            },
+            RuleExpr::Count { path, element_type, op, value } => {
+                let count = self.candidates(&Some(path.clone()), element_type, bindings).len() as i64;
+                compare(op, &serde_json::Value::from(count), &serde_json::Value::from(*value))
+            }
+        }
+    }
+}
+
+/// Compare two JSON scalars; numbers (or numeric strings) numerically, everything else as text
+fn compare(op: &CompOp, left: &serde_json::Value, right: &serde_json::Value) -> bool {
+    fn as_number(v: &serde_json::Value) -> Option<f64> {
+        v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+    }
+    fn as_text(v: &serde_json::Value) -> String {
+        v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())
+    }
+
+    let ordering = match (as_number(left), as_number(right)) {
+        (Some(l), Some(r)) => l.partial_cmp(&r),
+        _ => Some(as_text(left).cmp(&as_text(right))),
+    };
+    let Some(ordering) = ordering else {
+        return false;
+    };
+    match op {
+        CompOp::Eq => ordering.is_eq(),
+        CompOp::Ne => ordering.is_ne(),
+        CompOp::Lt => ordering.is_lt(),
+        CompOp::Le => ordering.is_le(),
+        CompOp::Gt => ordering.is_gt(),
+        CompOp::Ge => ordering.is_ge(),
+    }
+}
+
+/// Evaluate user rules over the validation graph
+///
+/// Rules that fail to parse are reported as AL023; violations use the rule's
+/// own ID as diagnostic code and its configured severity.
+pub fn evaluate_user_rules(rules: &[UserRule], graph: &ModelGraph) -> Vec<Diagnostic> {
+    let evaluator = Evaluator::new(graph);
+    let mut diagnostics = Vec::new();
+
+    for rule in rules {
+        let expr = match parse_rule(&rule.rule) {
+            Ok(expr) => expr,
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::new("AL023", format!("User rule {} cannot be parsed: {}", rule.id, e))
+                        .hint(rule.rule.clone()),
+                );
+                continue;
+            }
+        };
+        let RuleExpr::Quantified { quantifier, var, element_type, filter, body, .. } = &expr else {
+            unreachable!("parse_rule returns a quantifier");
+        };
+
+        let violation = |element: Option<&(String, Element)>| {
+            let mut message = rule.message.clone().unwrap_or_else(|| match element {
+                Some(_) => format!("{{id}} violates rule {}", rule.id),
+                None => format!("No element satisfies rule {}", rule.id),
+            });
+            if let Some((id, element)) = element {
+                message = message.replace("{id}", id).replace("{title}", element.title());
+            }
+            if !rule.description.is_empty() {
+                message = format!("{}: {}", message, rule.description);
+            }
+            let mut diagnostic = Diagnostic::new(&rule.id, message);
+            diagnostic.severity = rule.severity;
+            diagnostic.hint = rule.hint.clone();
+            if let Some((id, _)) = element {
+                diagnostic = diagnostic.on(id);
+            }
+            diagnostic
+        };
+
+        let mut bindings = Bindings::new();
+        match quantifier {
+            Quantifier::Exists => {
+                if !evaluator.eval(&expr, &mut bindings) {
+                    diagnostics.push(violation(None));
+                }
+            }
+            Quantifier::ForAll | Quantifier::None => {
+                let (filter, body) = match (quantifier, filter, body) {
+                    (Quantifier::ForAll, Some(f), None) => (None, Some(f)),
+                    _ => (filter.as_ref(), body.as_ref()),
+                };
+                for node in evaluator.candidates(&None, element_type, &bindings) {
+                    bindings.push((var.clone(), node));
+                    let selected = filter.is_none_or(|f| evaluator.eval(f, &mut bindings));
+                    let holds = selected && body.is_none_or(|b| evaluator.eval(b, &mut bindings));
+                    bindings.pop();
+                    let violated = match quantifier {
+                        Quantifier::ForAll => selected && !holds,
+                        _ => holds,
+                    };
+                    if violated {
+                        diagnostics.push(violation(Some(&graph[node])));
+                    }
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn element(value: serde_json::Value) -> Element {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn create_test_graph(elements: Vec<Element>, links: &[(&str, &str, &str)]) -> ModelGraph {
+        let mut graph = ModelGraph::new();
+        let mut nodes = HashMap::new();
+        for e in elements {
+            let id = e.id().to_string();
+            nodes.insert(id.clone(), graph.add_node((id, e)));
+        }
+        for (source, kind, target) in links {
+            graph.add_edge(nodes[*source], nodes[*target], kind.to_string());
+        }
+        graph
+    }
+
+    fn create_test_rule(id: &str, rule: &str) -> UserRule {
+        UserRule {
+            id: id.to_string(),
+            description: String::new(),
+            rule: rule.to_string(),
+            severity: Severity::Error,
+            message: None,
+            hint: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        assert!(parse_rule("forall r: req => x.id == \"A\"").unwrap_err().contains("Unbound variable 'x'"));
+        assert!(parse_rule("forall r: req => exists t in r -verify t.id == 1").is_err());
+        assert!(parse_rule("exists t in r <-verify-").is_err());
+        assert!(parse_rule("forall r: req where r.tags[\"pre-condition\"] == \"x\"").is_ok());
+    }
+
+    #[test]
+    fn test_safety_requirements_need_hil_verification() {
+        let graph = create_test_graph(
+            vec![
+                element(json!({"type": "req", "id": "R-1", "tags": {"safety": "ASIL-D"}})),
+                element(json!({"type": "req", "id": "R-2", "tags": {"safety": "ASIL-C"}})),
+                element(json!({"type": "req", "id": "R-3", "tags": {"safety": "QM"}})),
+                element(json!({"type": "test_case", "id": "TC-1", "tags": {"method": "HIL"}})),
+                element(json!({"type": "test_case", "id": "TC-2", "tags": {"method": "SIL"}})),
+            ],
+            &[("TC-1", "verify", "R-1"), ("TC-2", "verify", "R-2")],
+        );
+        let rule = create_test_rule(
+            "PRJ-1",
+            "forall r: req where r.tags.safety in [\"ASIL-C\", \"ASIL-D\"]
+                => exists t: test_case in r <-verify- where t.tags.method == \"HIL\"",
+        );
+
+        let diagnostics = evaluate_user_rules(&[rule], &graph);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PRJ-1");
+        assert_eq!(diagnostics[0].element_id.as_deref(), Some("R-2"));
+    }
+
+    #[test]
+    fn test_paths_counts_and_top_level_exists() {
+        let graph = create_test_graph(
+            vec![
+                element(json!({"type": "block_definition", "id": "BLK-1", "tags": {"stereotype": "software"}})),
+                element(json!({"type": "block_definition", "id": "BLK-2", "tags": {"stereotype": "software"}})),
+                element(json!({"type": "implementation", "id": "IMPL-1"})),
+                element(json!({"type": "req", "id": "R-1"})),
+                element(json!({"type": "req", "id": "R-1.1"})),
+                element(json!({"type": "req", "id": "R-1.1.1"})),
+            ],
+            &[
+                ("IMPL-1", "satisfy", "BLK-1"),
+                ("R-1.1", "derives_from", "R-1"),
+                ("R-1.1.1", "derives_from", "R-1.1"),
+            ],
+        );
+        let rules = [
+            create_test_rule(
+                "SW-IMPL",
+                "forall b: block_definition where b.tags.stereotype == \"software\" \
+                 => exists i: implementation in b <-satisfy-",
+            ),
+            create_test_rule("DEPTH", "forall r: req where r.id == \"R-1\" => count(r <-derives_from-+) == 2"),
+            create_test_rule("ANY-TC", "exists t: test_case"),
+            create_test_rule("BAD", "forall r: req =>"),
+        ];
+
+        let diagnostics = evaluate_user_rules(&rules, &graph);
+        let found: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.element_id.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("SW-IMPL", Some("BLK-2")), ("ANY-TC", None), ("AL023", None)]
+        );
+    }
+}
@@ -1,8 +1,8 @@
-/// Pure Rust SAT Solver using DPLL algorithm
-/// No external dependencies - designed for WASM compatibility
-///
-/// This module provides a simple but efficient SAT solver for checking
-/// satisfiability of propositional formulas in CNF (Conjunctive Normal Form).
+//! Pure Rust SAT Solver using DPLL algorithm
+//! No external dependencies - designed for WASM compatibility
+//!
+//! This module provides a simple but efficient SAT solver for checking
+//! satisfiability of propositional formulas in CNF (Conjunctive Normal Form).
 
 use std::collections::HashMap;
 
@@ -15,6 +15,7 @@ pub type Literal = i32;
 pub type Clause = Vec<Literal>;
 
 /// CNF formula is a conjunction (AND) of clauses
+#[allow(clippy::upper_case_acronyms)]
 pub type CNF = Vec<Clause>;
 
 /// Assignment of variables to boolean values
@@ -52,6 +53,7 @@ impl SatSolver {
 
     /// Get a satisfying assignment if one exists
     /// Only valid if solve() returned true
+    #[cfg(test)]
     pub fn get_model(&self) -> HashMap<i32, bool> {
         let mut model = HashMap::new();
         for var in 1..=self.num_vars {
@@ -114,7 +116,7 @@ impl SatSolver {
             let unit_literal = self.find_unit_clause();
 
             if let Some(lit) = unit_literal {
-                let var = lit.abs() as usize;
+                let var = lit.unsigned_abs() as usize;
                 let val = lit > 0;
 
                 // Check for conflict
@@ -235,23 +237,15 @@ impl SatSolver {
 
     /// Evaluate a literal under current assignment
     fn evaluate_literal(&self, lit: Literal) -> Option<bool> {
-        let var = lit.abs() as usize;
-        match self.assignment[var] {
-            Some(val) => Some(if lit > 0 { val } else { !val }),
-            None => None,
-        }
+        let var = lit.unsigned_abs() as usize;
+        self.assignment[var].map(|val| if lit > 0 { val } else { !val })
     }
 
     /// Select an unassigned variable (simple heuristic: first unassigned)
     /// More sophisticated heuristics (VSIDS, etc.) could be added here
     fn select_variable(&self) -> Option<usize> {
         // Simple heuristic: select first unassigned variable
-        for var in 1..=self.num_vars {
-            if self.assignment[var].is_none() {
-                return Some(var);
-            }
-        }
-        None
+        (1..=self.num_vars).find(|&var| self.assignment[var].is_none())
     }
 }
 
@@ -0,0 +1,365 @@
+//! Source Code Trace Annotation Scanner
+//!
+//! Extracts requirement references from source comments and doc attributes and
+//! turns them into implementation elements and `satisfy` links.
+//!
+//! Recognised annotations (keywords are configurable, matched ignoring case):
+//! - `// @satisfies REQ-AUTH-001a` (comment styles `//`, `/* */`, `--`, and `#`
+//!   in languages where it starts a comment, see `HASH_COMMENT_EXTENSIONS`)
+//! - `/// implements REQ-AUTH-002, REQ-AUTH-003`
+//! - `#[doc = "implements REQ-AUTH-004"]`
+//!
+//! One `implementation` element is emitted per annotated file (`<p><path>`, `<p>`
+//! being the ID prefix) with `source-file` and `source-lines` (target → line
+//! numbers) tags, and one `satisfy` link per referenced requirement. When a
+//! registry is given, references to IDs that are not requirements in it are
+//! reported instead of linked.
+
+use crate::types::{Element, ImplementationElement};
+use crate::{Link, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Options controlling the scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceTraceOptions {
+    /// Annotation keywords, with or without a leading `@`
+    #[serde(default = "default_keywords")]
+    pub keywords: Vec<String>,
+    /// File extensions to scan
+    #[serde(default = "default_extensions")]
+    pub extensions: Vec<String>,
+    /// Prefix prepended to file paths to form implementation IDs
+    #[serde(default = "default_id_prefix")]
+    pub id_prefix: String,
+}
+
+fn default_keywords() -> Vec<String> {
+    vec!["satisfies".to_string(), "implements".to_string()]
+}
+
+fn default_extensions() -> Vec<String> {
+    ["rs", "c", "h", "cc", "cpp", "hpp", "py", "js", "ts", "go", "java", "kt", "swift"]
+        .iter()
+        .map(|e| e.to_string())
+        .collect()
+}
+
+fn default_id_prefix() -> String {
+    "IMPL-".to_string()
+}
+
+/// Extensions of languages where `#` starts a comment (in C it starts a directive)
+const HASH_COMMENT_EXTENSIONS: [&str; 8] = ["py", "sh", "rb", "pl", "cmake", "toml", "yaml", "yml"];
+
+impl Default for SourceTraceOptions {
+    fn default() -> Self {
+        SourceTraceOptions {
+            keywords: default_keywords(),
+            extensions: default_extensions(),
+            id_prefix: default_id_prefix(),
+        }
+    }
+}
+
+/// One requirement reference found in a source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceAnnotation {
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    pub keyword: String,
+    pub target: String,
+}
+
+/// Result of a scan, in registry JSON format
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceTraceScan {
+    pub registry: BTreeMap<String, Element>,
+    pub links: Vec<Link>,
+    /// Annotations referencing IDs that are not requirements in the registry (if one was given)
+    pub unknown: Vec<TraceAnnotation>,
+    pub files_scanned: usize,
+}
+
+/// Find the annotations in one source file
+pub fn scan_source(file: &str, text: &str, options: &SourceTraceOptions) -> Vec<TraceAnnotation> {
+    let keywords: Vec<String> = options
+        .keywords
+        .iter()
+        .map(|k| k.trim_start_matches('@').to_lowercase())
+        .collect();
+    let extension = file.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+    let hash_comments = HASH_COMMENT_EXTENSIONS.contains(&extension);
+    let mut annotations = Vec::new();
+    let mut in_block = false;
+
+    for (index, line) in text.lines().enumerate() {
+        let Some(comment) = comment_text(line, hash_comments, &mut in_block) else {
+            continue;
+        };
+        let words: Vec<&str> = comment
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '"' || c == '(' || c == ')')
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        for (i, word) in words.iter().enumerate() {
+            let word = word.trim_start_matches('@').trim_end_matches(':').to_lowercase();
+            if !keywords.contains(&word) {
+                continue;
+            }
+            let targets = words[i + 1..]
+                .iter()
+                .map(|w| w.trim_end_matches(['.', ';', ':']))
+                .take_while(|w| looks_like_id(w));
+            for target in targets {
+                annotations.push(TraceAnnotation {
+                    file: file.to_string(),
+                    line: index + 1,
+                    keyword: word.clone(),
+                    target: target.to_string(),
+                });
+            }
+        }
+    }
+
+    annotations
+}
+
+/// Comment or doc attribute part of a line, `None` if the line has neither
+///
+/// `in_block` tracks a `/* */` comment spanning lines. Comment markers inside
+/// double-quoted string literals are ignored.
+fn comment_text<'a>(line: &'a str, hash_comments: bool, in_block: &mut bool) -> Option<&'a str> {
+    if *in_block {
+        *in_block = !line.contains("*/");
+        return Some(line.trim_start());
+    }
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("--") || trimmed.starts_with("#[doc") || trimmed.starts_with("#![doc") {
+        return Some(trimmed);
+    }
+
+    let mut in_string = false;
+    let mut escaped = false;
+    for (start, c) in line.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        let rest = &line[start..];
+        if c == '"' {
+            in_string = true;
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            *in_block = !comment.contains("*/");
+            return Some(rest);
+        } else if rest.starts_with("//") || (hash_comments && c == '#') {
+            return Some(rest);
+        }
+    }
+    None
+}
+
+/// Requirement IDs start with a letter and contain a digit or a dash
+fn looks_like_id(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic())
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        && word.chars().any(|c| c.is_ascii_digit() || c == '-')
+}
+
+/// Scan source texts (path → content) and build elements and links
+///
+/// Without a registry every reference is linked.
+pub fn scan_sources(
+    files: &BTreeMap<String, String>,
+    registry: Option<&Registry>,
+    options: &SourceTraceOptions,
+) -> SourceTraceScan {
+    let mut scan = SourceTraceScan { files_scanned: files.len(), ..Default::default() };
+
+    for (path, text) in files {
+        let mut lines: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for annotation in scan_source(path, text, options) {
+            if registry.is_none_or(|r| matches!(r.get(&annotation.target), Some(Element::Req(_)))) {
+                lines.entry(annotation.target).or_default().push(annotation.line);
+            } else {
+                scan.unknown.push(annotation);
+            }
+        }
+        if lines.is_empty() {
+            continue;
+        }
+
+        let id = format!("{}{}", options.id_prefix, path);
+        for target in lines.keys() {
+            scan.links.push(Link {
+                source: id.clone(),
+                link_type: "satisfy".to_string(),
+                target: target.clone(),
+            });
+        }
+        let mut tags = HashMap::new();
+        tags.insert("source-file".to_string(), serde_json::json!(path));
+        tags.insert("source-lines".to_string(), serde_json::json!(lines));
+        scan.registry.insert(
+            id.clone(),
+            Element::Implementation(ImplementationElement {
+                id,
+                title: path.clone(),
+                tags,
+                body: serde_json::Value::Null,
+            }),
+        );
+    }
+
+    scan
+}
+
+/// Walk a local source tree and scan every file with a configured extension
+///
+/// Hidden directories, `target` and `node_modules` are skipped. Paths in the
+/// result are relative to `root` and use `/` separators.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn scan_source_tree(
+    root: &std::path::Path,
+    registry: Option<&Registry>,
+    options: &SourceTraceOptions,
+) -> Result<SourceTraceScan, String> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("Cannot list {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !name.starts_with('.') && name != "target" && name != "node_modules" {
+                    pending.push(path);
+                }
+                continue;
+            }
+            let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+            if !options.extensions.contains(&extension) {
+                continue;
+            }
+            // Skip files that are not UTF-8 text
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let key: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+            files.insert(key.join("/"), text);
+        }
+    }
+
+    Ok(scan_sources(&files, registry, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SOURCE: &str = r#"use std::io;
+
+// @satisfies REQ-AUTH-001a
+fn login() {}
+
+/// Implements REQ-AUTH-002, REQ-AUTH-003.
+#[doc = "implements REQ-AUTH-004"]
+fn logout() {
+    let url = "http://example.com"; // implements the logout flow
+}
+
+/* @satisfies REQ-GONE */
+
+/*
+ * Session handling
+ * implements REQ-AUTH-003
+ */
+fn session(satisfies: &mut usize) {
+    *satisfies = REQ_LIMIT_2;
+}
+"#;
+
+    #[test]
+    fn test_scan_annotations() {
+        let annotations = scan_source("src/auth.rs", SOURCE, &SourceTraceOptions::default());
+        let found: Vec<(usize, &str)> = annotations.iter().map(|a| (a.line, a.target.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, "REQ-AUTH-001a"),
+                (6, "REQ-AUTH-002"),
+                (6, "REQ-AUTH-003"),
+                (7, "REQ-AUTH-004"),
+                (12, "REQ-GONE"),
+                (16, "REQ-AUTH-003"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_only_starts_comments_in_scripting_languages() {
+        let options = SourceTraceOptions::default();
+        let c_source = "#define AUTH_RETRIES 3 /* @satisfies REQ-AUTH-002 */\n\
+                        #include \"implements REQ-AUTH-003.h\"\n\
+                        const char *tag = \"// implements REQ-AUTH-004\";\n";
+        let found: Vec<(usize, String)> = scan_source("src/auth.c", c_source, &options)
+            .into_iter()
+            .map(|a| (a.line, a.target))
+            .collect();
+        assert_eq!(found, vec![(1, "REQ-AUTH-002".to_string())]);
+
+        let py_source = "# @satisfies REQ-AUTH-001a\nprompt = \"# implements REQ-AUTH-003\"  # implements REQ-AUTH-004\n";
+        let found: Vec<(usize, String)> = scan_source("tools/login.py", py_source, &options)
+            .into_iter()
+            .map(|a| (a.line, a.target))
+            .collect();
+        assert_eq!(found, vec![(1, "REQ-AUTH-001a".to_string()), (2, "REQ-AUTH-004".to_string())]);
+    }
+
+    #[test]
+    fn test_scan_without_registry_links_every_reference() {
+        let files = BTreeMap::from([("src/auth.rs".to_string(), SOURCE.to_string())]);
+        let scan = scan_sources(&files, None, &SourceTraceOptions::default());
+        assert_eq!(scan.links.len(), 5);
+        assert!(scan.links.iter().any(|l| l.target == "REQ-GONE"));
+        assert!(scan.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_scan_source_tree() {
+        let dir = std::env::temp_dir().join(format!("assemblyline-trace-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::write(dir.join("src/auth.rs"), SOURCE).unwrap();
+        std::fs::write(dir.join("target/generated.rs"), "// @satisfies REQ-AUTH-002").unwrap();
+        std::fs::write(dir.join("README.md"), "@satisfies REQ-AUTH-002").unwrap();
+
+        let registry: Registry = serde_json::from_value(json!({
+            "REQ-AUTH-001a": {"type": "req", "id": "REQ-AUTH-001a"},
+            "REQ-AUTH-002": {"type": "req", "id": "REQ-AUTH-002"},
+            "REQ-AUTH-003": {"type": "req", "id": "REQ-AUTH-003"},
+            "REQ-AUTH-004": {"type": "req", "id": "REQ-AUTH-004"}
+        }))
+        .unwrap();
+        let scan = scan_source_tree(&dir, Some(&registry), &SourceTraceOptions::default()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(scan.files_scanned, 1);
+        assert_eq!(scan.links.len(), 4);
+        assert!(scan.links.iter().all(|l| l.source == "IMPL-src/auth.rs" && l.link_type == "satisfy"));
+        let element = &scan.registry["IMPL-src/auth.rs"];
+        assert_eq!(element.tags()["source-lines"]["REQ-AUTH-003"], json!([6, 16]));
+        assert_eq!(scan.unknown.len(), 1);
+        assert_eq!(scan.unknown[0].target, "REQ-GONE");
+        assert_eq!(scan.unknown[0].line, 12);
+    }
+}
@@ -0,0 +1,363 @@
+//! SysML v2 Textual Export
+//!
+//! Renders block definitions and internal block diagrams as SysML v2 textual notation.
+//!
+//! Mapping (everything goes into one package):
+//! - block definition → `part def` with `attribute`s (properties), `port`s
+//!   (direction `in`/`out`/`inout`, typed by a `port def` per protocol;
+//!   `bidirectional` is written as `inout`, `provided` without direction and
+//!   `required` as a conjugated `~Type`, which the import maps back),
+//!   `part`s with multiplicity and `connection`s named after the flow (a
+//!   repeated flow gets a numbered name and the flow as its `connection def`
+//!   type, e.g. `connection Speed_2 : Speed connect ...`)
+//! - internal block diagram → `part` usage with the same members
+//! - `satisfy` link → `satisfy` in the source block/IBD; the target
+//!   requirements are declared as `requirement` usages
+//!
+//! Names that are not SysML basic names (or are keywords) are written as
+//! unrestricted names: single-quoted with `\` and `'` escaped.
+
+use crate::types::{Element, SysmlConnector, SysmlPart, SysmlPort, SysmlProperty};
+use crate::{Link, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Options controlling the SysML v2 export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SysmlExportOptions {
+    /// Name of the enclosing package
+    #[serde(default = "default_package")]
+    pub package: String,
+}
+
+fn default_package() -> String {
+    "AssemblyLine".to_string()
+}
+
+impl Default for SysmlExportOptions {
+    fn default() -> Self {
+        SysmlExportOptions { package: default_package() }
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "about", "abstract", "accept", "action", "actor", "after", "alias", "all", "allocate", "allocation", "analysis",
+    "and", "as", "assert", "assign", "assume", "at", "attribute", "bind", "binding", "by", "calc", "case",
+    "comment", "concern", "connect", "connection", "constant", "constraint", "crosses", "decide", "def", "default",
+    "defined", "dependency", "derived", "do", "doc", "else", "end", "entry", "enum", "event", "exhibit", "exit",
+    "expose", "false", "filter", "first", "flow", "for", "fork", "frame", "from", "hastype", "if", "implies",
+    "import", "in", "include", "individual", "inout", "interface", "istype", "item", "join", "language", "library",
+    "locale", "loop", "merge", "message", "meta", "metadata", "nonunique", "not", "null", "objective",
+    "occurrence", "of", "or", "ordered", "out", "package", "parallel", "part", "perform", "port", "private",
+    "protected", "public", "redefines", "ref", "references", "render", "rendering", "rep", "require",
+    "requirement", "return", "satisfy", "send", "snapshot", "specializes", "stakeholder", "standard", "state",
+    "subject", "subsets", "succession", "terminate", "then", "timeslice", "to", "transition", "true", "until",
+    "use", "variant", "variation", "verification", "verify", "via", "view", "viewpoint", "when", "while", "xor",
+];
+
+/// SysML v2 name: basic name if possible, otherwise a quoted unrestricted name
+pub fn sysml_name(name: &str) -> String {
+    let basic = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if basic && !KEYWORDS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Qualified feature chain (`part.port`) with each segment escaped
+fn sysml_chain(path: &str) -> String {
+    path.split('.').map(sysml_name).collect::<Vec<_>>().join(".")
+}
+
+/// Library type for common property type names
+fn attribute_type(type_name: &str) -> Option<&'static str> {
+    match type_name.to_lowercase().as_str() {
+        "integer" | "int" | "i32" | "i64" | "u32" | "u64" => Some("Integer"),
+        "natural" | "unsigned" => Some("Natural"),
+        "real" | "float" | "double" | "f32" | "f64" | "number" => Some("Real"),
+        "boolean" | "bool" => Some("Boolean"),
+        "string" | "str" => Some("String"),
+        _ => None,
+    }
+}
+
+/// `[..]` multiplicity, `None` if the text is not a SysML multiplicity range
+fn multiplicity(text: &str) -> Option<String> {
+    let bound = |b: &str| b == "*" || (!b.is_empty() && b.chars().all(|c| c.is_ascii_digit()));
+    let text = text.trim();
+    let valid = match text.split_once("..") {
+        Some((lower, upper)) => bound(lower) && lower != "*" && bound(upper),
+        None => bound(text),
+    };
+    valid.then(|| format!("[{}]", text))
+}
+
+fn doc(out: &mut String, indent: &str, text: &str) {
+    if !text.trim().is_empty() {
+        out.push_str(&format!("{}doc /* {} */\n", indent, text.trim().replace("*/", "* /")));
+    }
+}
+
+/// Everything declared at package level before the blocks
+#[derive(Default)]
+struct Declarations {
+    port_defs: BTreeSet<String>,
+    attribute_defs: BTreeSet<String>,
+    connection_defs: BTreeSet<String>,
+}
+
+fn write_members(
+    out: &mut String,
+    declarations: &mut Declarations,
+    properties: &[SysmlProperty],
+    ports: &[SysmlPort],
+    parts: &[SysmlPart],
+    connectors: &[SysmlConnector],
+    satisfied: &[&str],
+) {
+    let indent = "        ";
+    for property in properties {
+        let type_name = match attribute_type(&property.property_type) {
+            Some(library) => format!(" : {}", library),
+            None if property.property_type.is_empty() => String::new(),
+            None => {
+                declarations.attribute_defs.insert(property.property_type.clone());
+                format!(" : {}", sysml_name(&property.property_type))
+            }
+        };
+        let value = match property.default.trim() {
+            "" => String::new(),
+            v if v == "true" || v == "false" || v.parse::<f64>().is_ok() => format!(" = {}", v),
+            v => format!(" = \"{}\"", v.replace('\\', "\\\\").replace('"', "\\\"")),
+        };
+        let unit = if property.unit.is_empty() { String::new() } else { format!(" // unit: {}", property.unit) };
+        out.push_str(&format!("{}attribute {}{}{};{}\n", indent, sysml_name(&property.name), type_name, value, unit));
+    }
+
+    for port in ports {
+        let direction = match port.direction.as_str() {
+            "in" => "in ",
+            "out" => "out ",
+            "inout" | "bidirectional" => "inout ",
+            _ => "",
+        };
+        let type_name = if port.protocol.is_empty() {
+            String::new()
+        } else {
+            declarations.port_defs.insert(port.protocol.clone());
+            let conjugated = if port.direction == "required" { "~" } else { "" };
+            format!(" : {}{}", conjugated, sysml_name(&port.protocol))
+        };
+        out.push_str(&format!("{}{}port {}{};\n", indent, direction, sysml_name(&port.name), type_name));
+    }
+
+    for part in parts {
+        let multiplicity = multiplicity(&part.multiplicity).unwrap_or_default();
+        out.push_str(&format!(
+            "{}part {} : {}{};\n",
+            indent,
+            sysml_name(&part.name),
+            sysml_name(&part.part_type),
+            multiplicity
+        ));
+    }
+
+    let mut used_names: BTreeMap<&str, usize> = BTreeMap::new();
+    for connector in connectors {
+        let name = if connector.flow.is_empty() {
+            String::new()
+        } else {
+            let count = used_names.entry(connector.flow.as_str()).or_default();
+            *count += 1;
+            match *count {
+                1 => format!("{} ", sysml_name(&connector.flow)),
+                n => {
+                    declarations.connection_defs.insert(connector.flow.clone());
+                    let unique = sysml_name(&format!("{}_{}", connector.flow, n));
+                    format!("{} : {} ", unique, sysml_name(&connector.flow))
+                }
+            }
+        };
+        out.push_str(&format!(
+            "{}connection {}connect {} to {};\n",
+            indent,
+            name,
+            sysml_chain(&connector.from),
+            sysml_chain(&connector.to)
+        ));
+    }
+
+    for requirement in satisfied {
+        out.push_str(&format!("{}satisfy {};\n", indent, sysml_name(requirement)));
+    }
+}
+
+/// SysML v2 text for all block definitions and IBDs of the registry
+pub fn to_sysml_v2(registry: &Registry, links: &[Link], options: &SysmlExportOptions) -> String {
+    let mut ids: Vec<&String> = registry
+        .keys()
+        .filter(|k| matches!(registry[*k], Element::BlockDefinition(_) | Element::InternalBlockDiagram(_)))
+        .collect();
+    ids.sort();
+
+    let mut satisfied: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in links.iter().filter(|l| l.link_type == "satisfy") {
+        if matches!(registry.get(&link.target), Some(Element::Req(_))) {
+            satisfied.entry(link.source.as_str()).or_default().insert(link.target.as_str());
+        }
+    }
+
+    // Blocks first, so the package-level declarations they need are known
+    let mut declarations = Declarations::default();
+    let mut blocks = String::new();
+    for id in ids {
+        let element = &registry[id];
+        let satisfied: Vec<&str> = satisfied.get(id.as_str()).map(|s| s.iter().copied().collect()).unwrap_or_default();
+        blocks.push('\n');
+        match element {
+            Element::BlockDefinition(block) => {
+                blocks.push_str(&format!("    part def {} {{\n", sysml_name(id)));
+                doc(&mut blocks, "        ", &block.title);
+                write_members(
+                    &mut blocks,
+                    &mut declarations,
+                    &block.properties(),
+                    &block.ports(),
+                    &block.parts(),
+                    &block.connectors(),
+                    &satisfied,
+                );
+            }
+            Element::InternalBlockDiagram(ibd) => {
+                blocks.push_str(&format!("    part {} {{\n", sysml_name(id)));
+                doc(&mut blocks, "        ", &ibd.title);
+                write_members(
+                    &mut blocks,
+                    &mut declarations,
+                    &[],
+                    &ibd.ports(),
+                    &ibd.parts(),
+                    &ibd.connectors(),
+                    &satisfied,
+                );
+            }
+            _ => {}
+        }
+        blocks.push_str("    }\n");
+    }
+
+    let mut out = format!("package {} {{\n", sysml_name(&options.package));
+    out.push_str("    private import ScalarValues::*;\n");
+    if !declarations.port_defs.is_empty()
+        || !declarations.attribute_defs.is_empty()
+        || !declarations.connection_defs.is_empty()
+    {
+        out.push('\n');
+    }
+    for port_def in &declarations.port_defs {
+        out.push_str(&format!("    port def {};\n", sysml_name(port_def)));
+    }
+    for attribute_def in &declarations.attribute_defs {
+        out.push_str(&format!("    attribute def {};\n", sysml_name(attribute_def)));
+    }
+    for connection_def in &declarations.connection_defs {
+        out.push_str(&format!("    connection def {};\n", sysml_name(connection_def)));
+    }
+
+    let requirements: BTreeSet<&str> = satisfied.values().flatten().copied().collect();
+    for requirement in requirements {
+        out.push('\n');
+        out.push_str(&format!("    requirement {} {{\n", sysml_name(requirement)));
+        doc(&mut out, "        ", registry[requirement].title());
+        out.push_str("    }\n");
+    }
+
+    out.push_str(&blocks);
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_names_are_escaped() {
+        assert_eq!(sysml_name("speedIn"), "speedIn");
+        assert_eq!(sysml_name("BLK-ECU"), "'BLK-ECU'");
+        assert_eq!(sysml_name("part"), "'part'");
+        assert_eq!(sysml_name("2nd"), "'2nd'");
+        assert_eq!(sysml_name("it's \\ here"), "'it\\'s \\\\ here'");
+        assert_eq!(sysml_chain("brake.in"), "brake.'in'");
+        assert_eq!(multiplicity("0..*").as_deref(), Some("[0..*]"));
+        assert_eq!(multiplicity("many"), None);
+    }
+
+    #[test]
+    fn test_export_blocks_and_ibds() {
+        let registry: Registry = serde_json::from_value(json!({
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Brake on demand"},
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "title": "Brake ECU", "tags": {
+                "sysml-properties": [
+                    {"name": "timeout", "type": "Integer", "default": "100", "unit": "ms"},
+                    {"name": "supply", "type": "Voltage"}
+                ],
+                "sysml-ports": [
+                    {"name": "diag", "direction": "bidirectional", "protocol": "UDS"},
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"}
+                ],
+                "sysml-parts": [{"name": "ctrl", "type": "BLK-CTRL", "multiplicity": "1..2"}],
+                "sysml-connectors": [
+                    {"from": "speedIn", "to": "ctrl.in", "flow": "Speed"},
+                    {"from": "diag", "to": "ctrl.diag", "flow": "Speed"}
+                ]
+            }},
+            "IBD-ECU": {"type": "internal_block_diagram", "id": "IBD-ECU", "title": "ECU wiring", "tags": {
+                "ibd-parts": [{"name": "ecu", "type": "BLK-ECU", "multiplicity": "1"}],
+                "ibd-ports": [],
+                "ibd-connectors": []
+            }}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "BLK-ECU", "type": "satisfy", "target": "REQ-1"}
+        ]))
+        .unwrap();
+
+        let text = to_sysml_v2(&registry, &links, &SysmlExportOptions::default());
+        let expected = "package AssemblyLine {
+    private import ScalarValues::*;
+
+    port def CAN;
+    port def UDS;
+    attribute def Voltage;
+    connection def Speed;
+
+    requirement 'REQ-1' {
+        doc /* Brake on demand */
+    }
+
+    part def 'BLK-ECU' {
+        doc /* Brake ECU */
+        attribute timeout : Integer = 100; // unit: ms
+        attribute supply : Voltage;
+        inout port diag : UDS;
+        in port speedIn : CAN;
+        part ctrl : 'BLK-CTRL'[1..2];
+        connection Speed connect speedIn to ctrl.'in';
+        connection Speed_2 : Speed connect diag to ctrl.diag;
+        satisfy 'REQ-1';
+    }
+
+    part 'IBD-ECU' {
+        doc /* ECU wiring */
+        part ecu : 'BLK-ECU'[1];
+    }
+}
+";
+        assert_eq!(text, expected);
+    }
+}
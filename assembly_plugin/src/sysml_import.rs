@@ -0,0 +1,709 @@
+//! SysML v2 Textual Import
+//!
+//! Parses the structural subset of SysML v2 textual notation into block definitions.
+//!
+//! Supported (everything else is reported with its line number and skipped):
+//! - `package` (nested packages are flattened), `import`
+//! - `part def` → `block_definition` with the `sysml-*` tag layout; its `doc`
+//!   becomes the title
+//! - `attribute name : Type = value;` → `sysml-properties` (a trailing
+//!   `// unit: ms` note sets the unit)
+//! - `in|out|inout port name : Type;` → `sysml-ports` (the port type is the
+//!   protocol; `inout` is a `bidirectional` port, a conjugated `~Type` without
+//!   direction a `required` port and any other port without direction a
+//!   `provided` port)
+//! - `part name : Type[mult];` → `sysml-parts`
+//! - `connect a.p to b.q;` and `connection name [: Flow] connect ...;` →
+//!   `sysml-connectors` (the connection type, or else its name, is the flow)
+//! - `satisfy Req;` → `satisfy` link from the block
+//! - package-level `part name { ... }` → `internal_block_diagram`
+//!
+//! `port def`, `attribute def`, `connection def` and `requirement` declarations are accepted
+//! and skipped: requirements come from the document's own registry.
+
+use crate::types::{
+    BlockDefinitionElement, Element, InternalBlockDiagramElement, SysmlConnector, SysmlPart, SysmlPort, SysmlProperty,
+};
+use crate::Link;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Options controlling the SysML v2 import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SysmlImportOptions {
+    /// Prefix prepended to imported block IDs (and to part types defined in the same text)
+    #[serde(default)]
+    pub id_prefix: String,
+}
+
+/// Result of a SysML v2 import
+#[derive(Debug, Clone, Default)]
+pub struct SysmlImport {
+    pub registry: BTreeMap<String, Element>,
+    pub links: Vec<Link>,
+    /// Unsupported syntax, one entry per skipped statement ("line N: ...")
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// Lexer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Basic name or keyword
+    Word(String),
+    /// Unrestricted name ('...')
+    Quoted(String),
+    Str(String),
+    Number(String),
+    Symbol(String),
+    /// Block comment text (`/* ... */`)
+    Comment(String),
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Word(s) | Token::Number(s) | Token::Symbol(s) => s.clone(),
+            Token::Quoted(s) => format!("'{}'", s),
+            Token::Str(s) => format!("\"{}\"", s),
+            Token::Comment(_) => "/* */".to_string(),
+        }
+    }
+}
+
+struct Lexed {
+    tokens: Vec<(Token, usize)>,
+    /// `//` notes by line
+    notes: HashMap<usize, String>,
+}
+
+const SYMBOLS: &[&str] = &["::>", ":>>", "::", ":>", "..", "{", "}", ";", ":", "=", "[", "]", ".", ",", "~", "*", "-", "+", "(", ")"];
+
+fn lex(text: &str) -> Result<Lexed, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut lexed = Lexed { tokens: Vec::new(), notes: HashMap::new() };
+    let mut i = 0;
+    let mut line = 1;
+    let starts_with = |i: usize, s: &str| s.chars().enumerate().all(|(k, c)| chars.get(i + k) == Some(&c));
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if starts_with(i, "//") {
+            let start = i + 2;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            lexed.notes.insert(line, chars[start..i].iter().collect::<String>().trim().to_string());
+        } else if starts_with(i, "/*") {
+            let start_line = line;
+            let start = i + 2;
+            i = start;
+            while i < chars.len() && !starts_with(i, "*/") {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(format!("line {}: unterminated comment", start_line));
+            }
+            let comment: String = chars[start..i].iter().collect();
+            lexed.tokens.push((Token::Comment(comment.trim().to_string()), start_line));
+            i += 2;
+        } else if c == '\'' || c == '"' {
+            let start_line = line;
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None | Some('\n') => return Err(format!("line {}: unterminated {} literal", start_line, c)),
+                    Some('\\') => {
+                        let escaped = chars.get(i + 1).copied().unwrap_or('\\');
+                        value.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            other => other,
+                        });
+                        i += 2;
+                    }
+                    Some(q) if *q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(other) => {
+                        value.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+            let token = if c == '\'' { Token::Quoted(value) } else { Token::Str(value) };
+            lexed.tokens.push((token, start_line));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+            }
+            lexed.tokens.push((Token::Number(chars[start..i].iter().collect()), line));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            lexed.tokens.push((Token::Word(chars[start..i].iter().collect()), line));
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| starts_with(i, s)) {
+            lexed.tokens.push((Token::Symbol(symbol.to_string()), line));
+            i += symbol.len();
+        } else {
+            return Err(format!("line {}: unexpected character '{}'", line, c));
+        }
+    }
+
+    Ok(lexed)
+}
+
+// ============================================================================
+// Statements
+// ============================================================================
+
+/// Tokens up to `;` (or a body), with the nested statements of a `{ }` body
+struct Statement {
+    tokens: Vec<Token>,
+    body: Option<Vec<Statement>>,
+    line: usize,
+    /// Line of the last token (for trailing notes)
+    end_line: usize,
+}
+
+impl Statement {
+    fn preview(&self) -> String {
+        let words: Vec<String> = self.tokens.iter().take(6).map(Token::text).collect();
+        let ellipsis = if self.tokens.len() > 6 { " ..." } else { "" };
+        format!("{}{}", words.join(" "), ellipsis)
+    }
+
+    /// Tokens without leading visibility / `abstract` prefixes
+    fn core(&self) -> &[Token] {
+        let skip = self
+            .tokens
+            .iter()
+            .take_while(|t| matches!(t, Token::Word(w) if matches!(w.as_str(), "private" | "public" | "protected" | "abstract")))
+            .count();
+        &self.tokens[skip..]
+    }
+}
+
+/// `open_line` is the line of the `{` opening the body being parsed (None at the top level)
+fn parse_statements(
+    tokens: &[(Token, usize)],
+    pos: &mut usize,
+    open_line: Option<usize>,
+) -> Result<Vec<Statement>, String> {
+    let mut statements = Vec::new();
+    while *pos < tokens.len() {
+        let (first, line) = &tokens[*pos];
+        if *first == Token::Symbol("}".to_string()) {
+            if open_line.is_some() {
+                *pos += 1;
+                return Ok(statements);
+            }
+            return Err(format!("line {}: unexpected '}}'", line));
+        }
+
+        let mut statement = Statement { tokens: Vec::new(), body: None, line: *line, end_line: *line };
+        while *pos < tokens.len() {
+            let (token, line) = &tokens[*pos];
+            *pos += 1;
+            statement.end_line = *line;
+            match token {
+                Token::Symbol(s) if s == ";" => break,
+                Token::Symbol(s) if s == "{" => {
+                    statement.body = Some(parse_statements(tokens, pos, Some(*line))?);
+                    break;
+                }
+                Token::Comment(_) if matches!(statement.tokens.as_slice(), [] | [Token::Word(_)]) => {
+                    // `doc /* ... */`, `comment /* ... */` or a bare comment
+                    statement.tokens.push(token.clone());
+                    break;
+                }
+                _ => statement.tokens.push(token.clone()),
+            }
+        }
+        statements.push(statement);
+    }
+    if let Some(line) = open_line {
+        return Err(format!("line {}: unexpected end of text: missing '}}' for this '{{'", line));
+    }
+    Ok(statements)
+}
+
+/// Cursor over the tokens of one statement
+struct Cursor<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(w)) if w == keyword);
+        self.pos += found as usize;
+        found
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol);
+        self.pos += found as usize;
+        found
+    }
+
+    fn name(&mut self) -> Option<String> {
+        match self.peek()? {
+            Token::Word(w) | Token::Quoted(w) => {
+                self.pos += 1;
+                Some(w.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// `A::B::C` → `C`
+    fn qualified_name(&mut self) -> Option<String> {
+        let mut name = self.name()?;
+        while self.symbol("::") {
+            name = self.name()?;
+        }
+        Some(name)
+    }
+
+    /// `a.b.c` → `a.b.c`
+    fn chain(&mut self) -> Option<String> {
+        let mut chain = vec![self.name()?];
+        while self.symbol(".") {
+            chain.push(self.name()?);
+        }
+        Some(chain.join("."))
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// Remaining tokens as text (literal strings unquoted)
+    fn rest_text(&mut self) -> String {
+        let rest: Vec<String> = self.tokens[self.pos..]
+            .iter()
+            .map(|t| match t {
+                Token::Str(s) | Token::Quoted(s) => s.clone(),
+                other => other.text(),
+            })
+            .collect();
+        self.pos = self.tokens.len();
+        rest.join("")
+    }
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+#[derive(Default)]
+struct Members {
+    title: Option<String>,
+    properties: Vec<SysmlProperty>,
+    ports: Vec<SysmlPort>,
+    parts: Vec<SysmlPart>,
+    connectors: Vec<SysmlConnector>,
+    satisfies: Vec<String>,
+}
+
+struct Importer<'a> {
+    notes: &'a HashMap<usize, String>,
+    warnings: Vec<String>,
+}
+
+impl Importer<'_> {
+    fn unsupported(&mut self, statement: &Statement) {
+        self.warnings
+            .push(format!("line {}: unsupported syntax '{}'", statement.line, statement.preview()));
+    }
+
+    /// Report every statement of a body that is not documentation
+    fn ignore_body(&mut self, statement: &Statement) {
+        for inner in statement.body.iter().flatten() {
+            if !matches!(inner.core().first(), Some(Token::Word(w)) if w == "doc" || w == "comment")
+                && !matches!(inner.core(), [Token::Comment(_)])
+            {
+                self.unsupported(inner);
+            }
+        }
+    }
+
+    fn members(&mut self, statements: &[Statement], allow_attributes: bool) -> Members {
+        let mut members = Members::default();
+        for statement in statements {
+            let mut cursor = Cursor { tokens: statement.core(), pos: 0 };
+            let parsed = match cursor.peek() {
+                Some(Token::Comment(_)) => true,
+                Some(Token::Word(w)) if w == "doc" || w == "comment" => {
+                    if let (true, Some(Token::Comment(text))) = (w == "doc", cursor.tokens.get(1)) {
+                        members.title.get_or_insert_with(|| text.clone());
+                    }
+                    true
+                }
+                Some(Token::Word(w)) if w == "attribute" && allow_attributes => self.attribute(&mut cursor, statement, &mut members),
+                Some(Token::Word(w)) if matches!(w.as_str(), "in" | "out" | "inout" | "port") => {
+                    Self::port(&mut cursor, &mut members)
+                }
+                Some(Token::Word(w)) if w == "part" => Self::part(&mut cursor, &mut members),
+                Some(Token::Word(w)) if w == "connect" || w == "connection" => Self::connect(&mut cursor, &mut members),
+                Some(Token::Word(w)) if w == "satisfy" => {
+                    cursor.keyword("satisfy");
+                    cursor.keyword("requirement");
+                    let requirement = cursor.qualified_name();
+                    // `by <subject>` is implied by the owning block
+                    if cursor.keyword("by") {
+                        cursor.chain();
+                    }
+                    match requirement {
+                        Some(requirement) if cursor.done() => {
+                            members.satisfies.push(requirement);
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                _ => false,
+            };
+            if parsed {
+                self.ignore_body(statement);
+            } else {
+                self.unsupported(statement);
+            }
+        }
+        members
+    }
+
+    fn attribute(&mut self, cursor: &mut Cursor, statement: &Statement, members: &mut Members) -> bool {
+        cursor.keyword("attribute");
+        let Some(name) = cursor.name() else {
+            return false;
+        };
+        let property_type = if cursor.symbol(":") { cursor.qualified_name() } else { Some(String::new()) };
+        let Some(property_type) = property_type else {
+            return false;
+        };
+        let default = if cursor.symbol("=") { cursor.rest_text() } else { String::new() };
+        if !cursor.done() {
+            return false;
+        }
+        let unit = self
+            .notes
+            .get(&statement.end_line)
+            .and_then(|note| note.strip_prefix("unit:"))
+            .map(|unit| unit.trim().to_string())
+            .unwrap_or_default();
+        members.properties.push(SysmlProperty { name, property_type, default, unit });
+        true
+    }
+
+    fn port(cursor: &mut Cursor, members: &mut Members) -> bool {
+        let direction = ["in", "out", "inout"]
+            .into_iter()
+            .find(|d| cursor.keyword(d))
+            .unwrap_or_default();
+        if !cursor.keyword("port") {
+            return false;
+        }
+        let Some(name) = cursor.name() else {
+            return false;
+        };
+        let (conjugated, protocol) = if cursor.symbol(":") {
+            let conjugated = cursor.symbol("~");
+            match cursor.qualified_name() {
+                Some(protocol) => (conjugated, protocol),
+                None => return false,
+            }
+        } else {
+            (false, String::new())
+        };
+        let direction = match (direction, conjugated) {
+            ("", true) => "required",
+            ("", false) => "provided",
+            ("inout", _) => "bidirectional",
+            (direction, _) => direction,
+        };
+        members.ports.push(SysmlPort {
+            name,
+            direction: direction.to_string(),
+            protocol,
+            tags: HashMap::new(),
+        });
+        cursor.done()
+    }
+
+    fn part(cursor: &mut Cursor, members: &mut Members) -> bool {
+        cursor.keyword("part");
+        let (Some(name), true) = (cursor.name(), cursor.symbol(":")) else {
+            return false;
+        };
+        let Some(part_type) = cursor.qualified_name() else {
+            return false;
+        };
+        let mut multiplicity = "1".to_string();
+        if cursor.symbol("[") {
+            let bounds: Vec<String> = cursor.tokens[cursor.pos..]
+                .iter()
+                .take_while(|t| **t != Token::Symbol("]".to_string()))
+                .map(Token::text)
+                .collect();
+            cursor.pos += bounds.len();
+            if !cursor.symbol("]") {
+                return false;
+            }
+            multiplicity = bounds.concat();
+        }
+        members.parts.push(SysmlPart { name, part_type, multiplicity });
+        cursor.done()
+    }
+
+    fn connect(cursor: &mut Cursor, members: &mut Members) -> bool {
+        let mut flow = String::new();
+        if cursor.keyword("connection") && !matches!(cursor.peek(), Some(Token::Word(w)) if w == "connect") {
+            match cursor.name() {
+                Some(name) => flow = name,
+                None => return false,
+            }
+            if cursor.symbol(":") {
+                match cursor.qualified_name() {
+                    Some(flow_type) => flow = flow_type,
+                    None => return false,
+                }
+            }
+        }
+        if !cursor.keyword("connect") {
+            return false;
+        }
+        let (Some(from), true, Some(to)) = (cursor.chain(), cursor.keyword("to"), cursor.chain()) else {
+            return false;
+        };
+        members.connectors.push(SysmlConnector { from, to, flow, tags: HashMap::new() });
+        cursor.done()
+    }
+}
+
+fn sysml_tags(members: &Members, prefix: &str, with_properties: bool) -> HashMap<String, serde_json::Value> {
+    let mut tags = HashMap::new();
+    let mut set = |key: &str, value: serde_json::Value| {
+        tags.insert(format!("{}-{}", prefix, key), value);
+    };
+    set("ports", serde_json::json!(members.ports));
+    set("parts", serde_json::json!(members.parts));
+    set("connectors", serde_json::json!(members.connectors));
+    set("references", serde_json::json!([]));
+    if with_properties {
+        set("properties", serde_json::json!(members.properties));
+        for empty in ["provides", "requires", "operations", "constraints"] {
+            set(empty, serde_json::json!([]));
+        }
+    }
+    tags
+}
+
+/// Parse SysML v2 text into block definitions, IBDs and satisfy links
+///
+/// Lexical and brace errors abort the import; unsupported statements are
+/// skipped and listed in `warnings`.
+pub fn import_sysml_v2(text: &str, options: &SysmlImportOptions) -> Result<SysmlImport, String> {
+    let lexed = lex(text)?;
+    let mut pos = 0;
+    let statements = parse_statements(&lexed.tokens, &mut pos, None)?;
+
+    let mut importer = Importer { notes: &lexed.notes, warnings: Vec::new() };
+    // (name, is a part def, members, line)
+    let mut blocks: Vec<(String, bool, Members, usize)> = Vec::new();
+    let mut pending: Vec<&Statement> = statements.iter().rev().collect();
+
+    while let Some(statement) = pending.pop() {
+        let mut cursor = Cursor { tokens: statement.core(), pos: 0 };
+        let first = cursor.peek().cloned();
+        match first {
+            Some(Token::Comment(_)) => {}
+            Some(Token::Word(w)) if w == "doc" || w == "comment" => {}
+            Some(Token::Word(w)) if w == "import" => {}
+            Some(Token::Word(w)) if w == "package" => {
+                pending.extend(statement.body.iter().flatten().rev());
+            }
+            Some(Token::Word(w)) if w == "requirement" => {}
+            Some(Token::Word(w))
+                if matches!(w.as_str(), "port" | "attribute" | "connection")
+                    && cursor.tokens.get(1) == Some(&Token::Word("def".to_string())) =>
+            {
+                importer.ignore_body(statement);
+            }
+            Some(Token::Word(w)) if w == "part" => {
+                cursor.keyword("part");
+                let definition = cursor.keyword("def");
+                let Some(name) = cursor.name() else {
+                    importer.unsupported(statement);
+                    continue;
+                };
+                if !cursor.done() {
+                    importer.warnings.push(format!(
+                        "line {}: unsupported syntax '{}' (ignored after '{}')",
+                        statement.line,
+                        statement.preview(),
+                        name
+                    ));
+                }
+                let members = importer.members(statement.body.as_deref().unwrap_or_default(), definition);
+                blocks.push((name, definition, members, statement.line));
+            }
+            _ => importer.unsupported(statement),
+        }
+    }
+
+    let defined: Vec<String> = blocks.iter().filter(|b| b.1).map(|b| b.0.clone()).collect();
+    let mut import = SysmlImport::default();
+    for (name, definition, mut members, line) in blocks {
+        let id = format!("{}{}", options.id_prefix, name);
+        if import.registry.contains_key(&id) {
+            importer.warnings.push(format!("line {}: duplicate definition of '{}' skipped", line, name));
+            continue;
+        }
+        for part in &mut members.parts {
+            if defined.contains(&part.part_type) {
+                part.part_type = format!("{}{}", options.id_prefix, part.part_type);
+            }
+        }
+        for requirement in &members.satisfies {
+            import.links.push(Link {
+                source: id.clone(),
+                link_type: "satisfy".to_string(),
+                target: requirement.clone(),
+            });
+        }
+        let title = members.title.clone().unwrap_or_else(|| name.clone());
+        let element = if definition {
+            Element::BlockDefinition(BlockDefinitionElement {
+                id: id.clone(),
+                title,
+                tags: sysml_tags(&members, "sysml", true),
+                body: serde_json::Value::Null,
+            })
+        } else {
+            Element::InternalBlockDiagram(InternalBlockDiagramElement {
+                id: id.clone(),
+                title,
+                tags: sysml_tags(&members, "ibd", false),
+                body: serde_json::Value::Null,
+            })
+        };
+        import.registry.insert(id, element);
+    }
+
+    import.warnings = importer.warnings;
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysml_export::{to_sysml_v2, SysmlExportOptions};
+    use crate::Registry;
+    use serde_json::json;
+
+    #[test]
+    fn test_export_imports_back() {
+        let registry: Registry = serde_json::from_value(json!({
+            "REQ-1": {"type": "req", "id": "REQ-1", "title": "Brake on demand"},
+            "BLK-ECU": {"type": "block_definition", "id": "BLK-ECU", "title": "Brake ECU", "tags": {
+                "sysml-properties": [{"name": "timeout", "type": "Integer", "default": "100", "unit": "ms"}],
+                "sysml-ports": [
+                    {"name": "diag", "direction": "required", "protocol": "UDS"},
+                    {"name": "speedIn", "direction": "in", "protocol": "CAN"},
+                    {"name": "status", "direction": "provided", "protocol": "CAN"},
+                    {"name": "bus", "direction": "bidirectional", "protocol": "CAN"}
+                ],
+                "sysml-parts": [{"name": "ctrl", "type": "BLK-CTRL", "multiplicity": "0..*"}],
+                "sysml-connectors": [
+                    {"from": "speedIn", "to": "ctrl.in", "flow": "Speed"},
+                    {"from": "status", "to": "ctrl.status", "flow": "Speed"}
+                ]
+            }},
+            "IBD-ECU": {"type": "internal_block_diagram", "id": "IBD-ECU", "title": "ECU wiring", "tags": {
+                "ibd-parts": [{"name": "ecu", "type": "BLK-ECU", "multiplicity": "1"}]
+            }}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "BLK-ECU", "type": "satisfy", "target": "REQ-1"}
+        ]))
+        .unwrap();
+        let text = to_sysml_v2(&registry, &links, &SysmlExportOptions::default());
+
+        let import = import_sysml_v2(&text, &SysmlImportOptions::default()).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let block = import.registry["BLK-ECU"].as_block_definition().unwrap();
+        assert_eq!(block.title, "Brake ECU");
+        let original = registry["BLK-ECU"].as_block_definition().unwrap();
+        assert_eq!(json!(block.properties()), json!(original.properties()));
+        assert_eq!(json!(block.ports()), json!(original.ports()));
+        assert_eq!(json!(block.parts()), json!(original.parts()));
+        assert_eq!(json!(block.connectors()), json!(original.connectors()));
+        assert_eq!(import.registry["IBD-ECU"].type_name(), "internal_block_diagram");
+        assert_eq!(import.links.len(), 1);
+        assert_eq!(import.links[0].target, "REQ-1");
+    }
+
+    #[test]
+    fn test_unsupported_syntax_is_reported_with_lines() {
+        let text = "package Supplier {
+    part def Pump :> Device {
+        doc /* Fuel pump */
+        out port flow : Fuel;
+        action run;
+        part motor : Motor[2] {
+            attribute rpm;
+        }
+    }
+    part def Motor;
+    item def Fuel;
+}
+";
+        let import = import_sysml_v2(text, &SysmlImportOptions { id_prefix: "SUP-".to_string() }).unwrap();
+        assert_eq!(
+            import.warnings,
+            vec![
+                "line 2: unsupported syntax 'part def Pump :> Device' (ignored after 'Pump')",
+                "line 5: unsupported syntax 'action run'",
+                "line 7: unsupported syntax 'attribute rpm'",
+                "line 11: unsupported syntax 'item def Fuel'",
+            ]
+        );
+        let pump = import.registry["SUP-Pump"].as_block_definition().unwrap();
+        assert_eq!(pump.title, "Fuel pump");
+        assert_eq!(pump.parts()[0].part_type, "SUP-Motor");
+        assert_eq!(pump.parts()[0].multiplicity, "2");
+        assert_eq!(pump.ports()[0].direction, "out");
+
+        let error = import_sysml_v2("package P {\n  part def A {\n", &SysmlImportOptions::default()).unwrap_err();
+        assert!(error.contains("missing '}'"), "{}", error);
+        assert!(error.starts_with("line 2:"), "{}", error);
+    }
+}
@@ -0,0 +1,422 @@
+//! Tag Schemas
+//!
+//! Per element type, a schema lists the tag keys an element must carry and
+//! the type, allowed values or pattern of each key. Schemas are supplied in
+//! the validation input keyed by element type (`req`, `feature`, ...); a
+//! schema under `*` applies to every element type and is merged with the
+//! type-specific one.
+//!
+//! ```json
+//! {
+//!   "req": {
+//!     "required": ["type", "safety"],
+//!     "properties": {
+//!       "type": { "enum": ["functional", "non-functional", "safety"] },
+//!       "safety": { "pattern": "ASIL-[A-D]|QM" },
+//!       "priority": { "type": "string", "pattern": "P#" }
+//!     },
+//!     "closed": false
+//!   }
+//! }
+//! ```
+//!
+//! Patterns are matched against the whole value: `*` any sequence, `?` any
+//! character, `#` a digit, `[A-D]` a character class, `|` alternatives.
+//! Unknown keys are reported when the schema is `closed`, and otherwise only
+//! when they look like a misspelling of a known key.
+
+use crate::diagnostics::Diagnostic;
+use crate::Registry;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Tags every element may carry regardless of its schema
+const SYSTEM_TAGS: &[&str] = &["suppress", "variant"];
+
+/// Value type of a tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+}
+
+impl TagType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            TagType::String => value.is_string(),
+            TagType::Number => value.is_number(),
+            TagType::Integer => value.is_i64() || value.is_u64(),
+            TagType::Boolean => value.is_boolean(),
+            TagType::Array => value.is_array(),
+            TagType::Object => value.is_object(),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TagType::String => "string",
+            TagType::Number => "number",
+            TagType::Integer => "integer",
+            TagType::Boolean => "boolean",
+            TagType::Array => "array",
+            TagType::Object => "object",
+        }
+    }
+}
+
+/// Constraints on one tag key
+///
+/// `enum` and `pattern` apply to each item when the value is an array.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagSpec {
+    #[serde(default, rename = "type")]
+    pub value_type: Option<TagType>,
+    #[serde(default, rename = "enum")]
+    pub values: Vec<Value>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+/// Schema for the tags of one element type
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagSchema {
+    #[serde(default)]
+    pub required: Vec<String>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, TagSpec>,
+    /// Report every key not listed in `properties`
+    #[serde(default)]
+    pub closed: bool,
+}
+
+/// Tag schemas keyed by element type (`*` for all types)
+pub type TagSchemas = BTreeMap<String, TagSchema>;
+
+/// Schema effective for an element type (`*` merged with the type-specific schema)
+fn effective_schema(schemas: &TagSchemas, element_type: &str) -> Option<TagSchema> {
+    let (common, specific) = (schemas.get("*"), schemas.get(element_type));
+    if common.is_none() && specific.is_none() {
+        return None;
+    }
+    let mut schema = common.cloned().unwrap_or_default();
+    if let Some(specific) = specific {
+        schema.required.extend(specific.required.iter().cloned());
+        schema.properties.extend(specific.properties.clone());
+        schema.closed |= specific.closed;
+    }
+    schema.required.sort();
+    schema.required.dedup();
+    Some(schema)
+}
+
+/// Check all element tags against the schemas
+pub fn validate_tags(registry: &Registry, schemas: &TagSchemas) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (element_type, schema) in schemas {
+        for (key, spec) in &schema.properties {
+            if let Some(pattern) = &spec.pattern
+                && let Err(e) = check_pattern(pattern)
+            {
+                diagnostics.push(
+                    Diagnostic::new(
+                        "AL029",
+                        format!("Tag schema for '{}' has invalid pattern for '{}': {}", element_type, key, e),
+                    )
+                    .hint("Patterns support *, ?, # (digit), [A-Z] classes and | alternatives"),
+                );
+            }
+        }
+    }
+
+    let mut keys: Vec<&String> = registry.keys().collect();
+    keys.sort();
+    for key in keys {
+        let element = &registry[key];
+        let Some(schema) = effective_schema(schemas, element.type_name()) else {
+            continue;
+        };
+        let id = element.id();
+        let tags = element.tags();
+
+        for required in &schema.required {
+            if !tags.contains_key(required) {
+                let mut diagnostic = Diagnostic::new(
+                    "AL024",
+                    format!("{} {} is missing required tag '{}'", element.type_name(), id, required),
+                )
+                .on(id);
+                let misspelled = tags.keys().find(|k| is_misspelling(k, required));
+                diagnostic = match misspelled {
+                    Some(k) => diagnostic.hint(format!("Rename tag '{}' to '{}'", k, required)),
+                    None => diagnostic.hint(format!("Add tags: ({}: ...)", required)),
+                };
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        let mut tag_keys: Vec<&String> = tags.keys().collect();
+        tag_keys.sort();
+        for tag in tag_keys {
+            let value = &tags[tag];
+            match schema.properties.get(tag) {
+                Some(spec) => check_value(id, tag, value, spec, &mut diagnostics),
+                None if SYSTEM_TAGS.contains(&tag.as_str()) => {}
+                None => {
+                    let known = schema
+                        .properties
+                        .keys()
+                        .chain(schema.required.iter())
+                        .find(|k| is_misspelling(tag, k));
+                    if schema.closed || known.is_some() {
+                        let diagnostic = Diagnostic::new(
+                            "AL028",
+                            format!("{} {} has unknown tag '{}'", element.type_name(), id, tag),
+                        )
+                        .on(id);
+                        diagnostics.push(match known {
+                            Some(k) => diagnostic.hint(format!("Did you mean '{}'?", k)),
+                            None => diagnostic.hint(format!(
+                                "Known tags: {}",
+                                schema.properties.keys().cloned().collect::<Vec<_>>().join(", ")
+                            )),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn check_value(id: &str, tag: &str, value: &Value, spec: &TagSpec, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(value_type) = spec.value_type
+        && !value_type.matches(value)
+    {
+        diagnostics.push(
+            Diagnostic::new(
+                "AL025",
+                format!("Tag '{}' of {} must be {}, got {}", tag, id, value_type.as_str(), value),
+            )
+            .on(id),
+        );
+        return;
+    }
+
+    let items: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+    for item in items {
+        if !spec.values.is_empty() && !spec.values.contains(item) {
+            let allowed: Vec<String> = spec.values.iter().map(Value::to_string).collect();
+            let case_match = spec.values.iter().find(|v| match (v.as_str(), item.as_str()) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
+            });
+            let diagnostic = Diagnostic::new(
+                "AL026",
+                format!("Tag '{}' of {} has value {}, allowed: {}", tag, id, item, allowed.join(", ")),
+            )
+            .on(id);
+            diagnostics.push(match case_match {
+                Some(v) => diagnostic.hint(format!("Did you mean {}?", v)),
+                None => diagnostic,
+            });
+        }
+        if let (Some(pattern), Some(text)) = (&spec.pattern, item.as_str())
+            && check_pattern(pattern).is_ok()
+            && !pattern_matches(pattern, text)
+        {
+            diagnostics.push(
+                Diagnostic::new(
+                    "AL027",
+                    format!("Tag '{}' of {} has value \"{}\", which does not match '{}'", tag, id, text, pattern),
+                )
+                .on(id),
+            );
+        }
+    }
+}
+
+/// True if `a` differs from `b` by one edit, or two for keys longer than five characters (and is not equal)
+fn is_misspelling(a: &str, b: &str) -> bool {
+    let limit = if b.chars().count() <= 5 { 1 } else { 2 };
+    a != b && edit_distance(&a.to_lowercase(), &b.to_lowercase()) <= limit
+}
+
+/// Levenshtein distance with adjacent transpositions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Check that character classes in a pattern are closed
+fn check_pattern(pattern: &str) -> Result<(), String> {
+    let mut in_class = false;
+    for c in pattern.chars() {
+        match c {
+            '[' if in_class => return Err("nested '['".to_string()),
+            '[' => in_class = true,
+            ']' if in_class => in_class = false,
+            _ => {}
+        }
+    }
+    if in_class {
+        return Err("unclosed '['".to_string());
+    }
+    Ok(())
+}
+
+/// Match a whole string against a pattern with `|` alternatives
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    split_alternatives(pattern).into_iter().any(|alt| {
+        let alt: Vec<char> = alt.chars().collect();
+        glob_match(&alt, &text)
+    })
+}
+
+/// Split on `|` outside character classes
+fn split_alternatives(pattern: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_class = false;
+    for (i, c) in pattern.char_indices() {
+        match c {
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '|' if !in_class => {
+                parts.push(&pattern[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&pattern[start..]);
+    parts
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| glob_match(&pattern[1..], &text[skip..])),
+        Some('[') => {
+            let end = pattern.iter().position(|&c| c == ']').unwrap_or(pattern.len() - 1);
+            let class = &pattern[1..end];
+            let Some(&c) = text.first() else {
+                return false;
+            };
+            let (negated, class) = match class.first() {
+                Some('!') | Some('^') => (true, &class[1..]),
+                _ => (false, class),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            matched != negated && glob_match(&pattern[end + 1..], &text[1..])
+        }
+        Some(&p) => {
+            let Some(&c) = text.first() else {
+                return false;
+            };
+            let ok = match p {
+                '?' => true,
+                '#' => c.is_ascii_digit(),
+                _ => p == c,
+            };
+            ok && glob_match(&pattern[1..], &text[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn codes(diagnostics: &[Diagnostic], id: &str) -> Vec<String> {
+        let mut codes: Vec<String> = diagnostics
+            .iter()
+            .filter(|d| d.element_id.as_deref() == Some(id))
+            .map(|d| d.code.clone())
+            .collect();
+        codes.sort();
+        codes
+    }
+
+    #[test]
+    fn test_schema_violations_name_element_and_key() {
+        let registry: Registry = serde_json::from_value(json!({
+            "REQ-OK": {"type": "req", "id": "REQ-OK", "tags": {"type": "functional", "safety": "ASIL-B", "priority": "P1", "time": "10ms"}},
+            "REQ-TYPO": {"type": "req", "id": "REQ-TYPO", "tags": {"type": "functional", "saftey": "ASIL-B"}},
+            "REQ-BAD": {"type": "req", "id": "REQ-BAD", "tags": {"type": "Functional", "safety": "ASIL-E", "priority": "p1"}},
+            "UC-1": {"type": "use_case", "id": "UC-1", "tags": {"actor": 3, "owner": "me", "suppress": {}}},
+            "F-1": {"type": "feature", "id": "F-1", "tags": {"anything": true}}
+        }))
+        .unwrap();
+        let schemas: TagSchemas = serde_json::from_value(json!({
+            "req": {
+                "required": ["type", "safety"],
+                "properties": {
+                    "type": {"enum": ["functional", "non-functional"]},
+                    "safety": {"type": "string", "pattern": "ASIL-[A-D]|QM"},
+                    "priority": {"type": "string", "pattern": "P#"}
+                }
+            },
+            "use_case": {"properties": {"actor": {"type": "string"}}, "closed": true}
+        }))
+        .unwrap();
+        let diagnostics = validate_tags(&registry, &schemas);
+
+        // "time" is two edits from "type", which is too far for a short key
+        assert!(codes(&diagnostics, "REQ-OK").is_empty());
+        assert_eq!(codes(&diagnostics, "REQ-TYPO"), vec!["AL024", "AL028"]);
+        let missing = diagnostics.iter().find(|d| d.code == "AL024").unwrap();
+        assert!(missing.message.contains("'safety'"));
+        assert_eq!(missing.hint.as_deref(), Some("Rename tag 'saftey' to 'safety'"));
+        assert_eq!(codes(&diagnostics, "REQ-BAD"), vec!["AL026", "AL027", "AL027"]);
+        assert_eq!(codes(&diagnostics, "UC-1"), vec!["AL025", "AL028"]);
+        assert!(codes(&diagnostics, "F-1").is_empty());
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert!(pattern_matches("ASIL-[A-D]|QM", "QM"));
+        assert!(pattern_matches("ASIL-[A-D]|QM", "ASIL-C"));
+        assert!(!pattern_matches("ASIL-[A-D]|QM", "ASIL-E"));
+        assert!(pattern_matches("REQ-*-###", "REQ-AUTH-001"));
+        assert!(!pattern_matches("P#", "P12"));
+        assert!(check_pattern("[A-").is_err());
+    }
+}
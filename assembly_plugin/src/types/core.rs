@@ -19,4 +19,31 @@ pub trait ElementCore {
     fn has_tag(&self, key: &str) -> bool {
         self.tags().contains_key(key)
     }
+
+    /// Plain text of the body (a string, or Typst content serialized as JSON)
+    fn body_text(&self) -> String {
+        let mut out = String::new();
+        collect_text(self.body(), &mut out);
+        out.trim().to_string()
+    }
+}
+
+fn collect_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::String(s) => out.push_str(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|item| collect_text(item, out)),
+        serde_json::Value::Object(map) => match map.get("func").and_then(|f| f.as_str()) {
+            Some("space") => out.push(' '),
+            Some("linebreak") => out.push('\n'),
+            Some("parbreak") => out.push_str("\n\n"),
+            _ => {
+                for key in ["text", "children", "body", "child"] {
+                    if let Some(inner) = map.get(key) {
+                        collect_text(inner, out);
+                    }
+                }
+            }
+        },
+        _ => {}
+    }
 }
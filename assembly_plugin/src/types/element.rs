@@ -8,6 +8,7 @@ use super::feature::FeatureElement;
 use super::requirement::ReqElement;
 use super::use_case::UseCaseElement;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Top-level element enum - represents all AssemblyLine element types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +56,42 @@ impl Element {
         }
     }
 
+    /// Get element tags (convenience)
+    pub fn tags(&self) -> &HashMap<String, serde_json::Value> {
+        match self {
+            Element::Feature(e) => e.tags(),
+            Element::Req(e) => e.tags(),
+            Element::UseCase(e) => e.tags(),
+            Element::Config(e) => e.tags(),
+            Element::BlockDefinition(e) => e.tags(),
+            Element::InternalBlockDiagram(e) => e.tags(),
+            Element::SequenceDiagram(e) => e.tags(),
+            Element::Implementation(e) => e.tags(),
+            Element::TestCase(e) => e.tags(),
+        }
+    }
+
+    /// Get element body (convenience)
+    pub fn body(&self) -> &serde_json::Value {
+        match self {
+            Element::Feature(e) => e.body(),
+            Element::Req(e) => e.body(),
+            Element::UseCase(e) => e.body(),
+            Element::Config(e) => e.body(),
+            Element::BlockDefinition(e) => e.body(),
+            Element::InternalBlockDiagram(e) => e.body(),
+            Element::SequenceDiagram(e) => e.body(),
+            Element::Implementation(e) => e.body(),
+            Element::TestCase(e) => e.body(),
+        }
+    }
+
+    /// Get the safety level tag (`safety`, falling back to `asil`)
+    pub fn safety_tag(&self) -> Option<&str> {
+        let tags = self.tags();
+        tags.get("safety").or_else(|| tags.get("asil"))?.as_str()
+    }
+
     /// Get element type as string
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParameterSchema {
     #[serde(rename = "type")]
-    pub param_type: String,  // "Integer", "Boolean", "Enum"
+    pub param_type: String,  // "Integer", "Boolean", "Enum", "String"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub range: Option<(i64, i64)>,  // For Integer type
     #[serde(skip_serializing_if = "Option::is_none")]
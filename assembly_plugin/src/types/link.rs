@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// Typed traceability link kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    ChildOf,
+    BelongsTo,
+    DerivesFrom,
+    Trace,
+    Allocate,
+    Satisfy,
+    Verify,
+}
+
+impl LinkKind {
+    /// All link kinds, in metamodel order
+    pub const ALL: [LinkKind; 7] = [
+        LinkKind::ChildOf,
+        LinkKind::BelongsTo,
+        LinkKind::DerivesFrom,
+        LinkKind::Trace,
+        LinkKind::Allocate,
+        LinkKind::Satisfy,
+        LinkKind::Verify,
+    ];
+
+    /// Parse the link type string used in link records
+    pub fn parse(s: &str) -> Option<LinkKind> {
+        LinkKind::ALL.into_iter().find(|k| k.as_str() == s)
+    }
+
+    /// Link type string as written in link records
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::ChildOf => "child_of",
+            LinkKind::BelongsTo => "belongs_to",
+            LinkKind::DerivesFrom => "derives_from",
+            LinkKind::Trace => "trace",
+            LinkKind::Allocate => "allocate",
+            LinkKind::Satisfy => "satisfy",
+            LinkKind::Verify => "verify",
+        }
+    }
+
+    /// Element types allowed as the source of this link kind
+    pub fn allowed_sources(&self) -> Vec<&'static str> {
+        let mut sources: Vec<&'static str> = METAMODEL
+            .iter()
+            .filter(|(kind, _, _)| kind == self)
+            .map(|(_, source, _)| *source)
+            .collect();
+        sources.dedup();
+        sources
+    }
+
+    /// Element types allowed as the target of this link kind from a given source type
+    pub fn allowed_targets(&self, source_type: &str) -> &'static [&'static str] {
+        METAMODEL
+            .iter()
+            .find(|(kind, source, _)| kind == self && *source == source_type)
+            .map(|(_, _, targets)| *targets)
+            .unwrap_or(&[])
+    }
+
+    /// Check a (source type, target type) pair against the metamodel
+    pub fn allows(&self, source_type: &str, target_type: &str) -> bool {
+        self.allowed_targets(source_type).contains(&target_type)
+    }
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Traceability metamodel: (link kind, source element type, allowed target element types)
+///
+/// Mirrors the link types reference in the user guide; element types use
+/// `Element::type_name()` spelling.
+pub const METAMODEL: &[(LinkKind, &str, &[&str])] = &[
+    (LinkKind::ChildOf, "feature", &["feature"]),
+    (LinkKind::BelongsTo, "req", &["feature"]),
+    (LinkKind::BelongsTo, "internal_block_diagram", &["use_case"]),
+    (LinkKind::BelongsTo, "sequence_diagram", &["use_case"]),
+    (LinkKind::DerivesFrom, "req", &["req"]),
+    (LinkKind::Trace, "use_case", &["req"]),
+    (LinkKind::Allocate, "block_definition", &["req"]),
+    (LinkKind::Satisfy, "block_definition", &["req"]),
+    (LinkKind::Satisfy, "internal_block_diagram", &["req"]),
+    (LinkKind::Satisfy, "sequence_diagram", &["req"]),
+    (LinkKind::Satisfy, "implementation", &["req", "block_definition"]),
+    (LinkKind::Verify, "test_case", &["req"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for kind in LinkKind::ALL {
+            assert_eq!(LinkKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(LinkKind::parse("verifies"), None);
+    }
+
+    #[test]
+    fn test_metamodel_checks_sources_and_targets() {
+        assert!(LinkKind::Verify.allows("test_case", "req"));
+        assert!(!LinkKind::Verify.allows("test_case", "block_definition"));
+        assert!(!LinkKind::Satisfy.allows("implementation", "use_case"));
+        assert!(LinkKind::Satisfy.allows("implementation", "block_definition"));
+        assert!(LinkKind::BelongsTo.allows("sequence_diagram", "use_case"));
+        assert!(!LinkKind::BelongsTo.allows("req", "use_case"));
+        assert_eq!(LinkKind::Verify.allowed_sources(), vec!["test_case"]);
+        assert!(LinkKind::Trace.allowed_targets("block_definition").is_empty());
+    }
+}
@@ -5,6 +5,7 @@ pub mod core;
 pub mod diagram;
 pub mod element;
 pub mod feature;
+pub mod link;
 pub mod requirement;
 pub mod sysml;
 pub mod use_case;
@@ -18,6 +19,7 @@ pub use diagram::{
 };
 pub use element::Element;
 pub use feature::{FeatureElement, ParameterSchema, VariabilityGroup};
+pub use link::{LinkKind, METAMODEL};
 pub use requirement::ReqElement;
 pub use sysml::{SysmlConnector, SysmlOperation, SysmlPart, SysmlPort, SysmlProperty};
 pub use use_case::UseCaseElement;
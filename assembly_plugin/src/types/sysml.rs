@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Typed SysML property
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +27,9 @@ pub struct SysmlPort {
     pub name: String,
     pub direction: String, // "in", "out", "inout"
     pub protocol: String,
+    /// Optional metadata, e.g. `mitigation` documenting a safety mechanism
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, serde_json::Value>,
 }
 
 /// Typed SysML part (composition)
@@ -43,4 +47,7 @@ pub struct SysmlConnector {
     pub from: String,
     pub to: String,
     pub flow: String,
+    /// Optional metadata, e.g. `mitigation` documenting a safety mechanism
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, serde_json::Value>,
 }
@@ -0,0 +1,217 @@
+//! Configuration-Aware Model Filtering
+//!
+//! Reduces the model to the product described by one configuration before
+//! traceability rules are evaluated:
+//!
+//! - requirements that belong to an unselected feature (directly via
+//!   `belongs_to`/`parent`, or through the requirements they derive from) are
+//!   excluded;
+//! - any other element tagged `variant: "F-X"` or `variant: ("F-X", "F-Y")` is
+//!   excluded unless at least one of the listed features is selected.
+//!
+//! Features and configurations are always kept so that feature model rules
+//! still see the whole tree. Links touching an excluded element are dropped;
+//! links with a non-existent endpoint are kept so they are still reported.
+
+use crate::diagnostics::Diagnostic;
+use crate::types::{ConfigElement, Element, LinkKind};
+use crate::{Link, Registry};
+use std::collections::{HashMap, HashSet};
+
+/// Model reduced to one configuration
+#[derive(Debug, Clone)]
+pub struct FilteredModel {
+    pub registry: Registry,
+    pub links: Vec<Link>,
+    /// Registry keys of excluded elements, sorted
+    pub excluded: Vec<String>,
+}
+
+/// Look up a configuration by ID (with or without the "CONFIG:" prefix)
+pub fn find_config<'a>(registry: &'a Registry, config_id: &str) -> Option<(&'a str, &'a ConfigElement)> {
+    [format!("CONFIG:{}", config_id), config_id.to_string()]
+        .into_iter()
+        .find_map(|key| registry.get_key_value(&key))
+        .and_then(|(key, element)| element.as_config().map(|config| (key.as_str(), config)))
+}
+
+/// Filter the model to the elements present in a configuration's product
+pub fn filter_to_configuration(
+    registry: &Registry,
+    links: &[Link],
+    config_id: &str,
+) -> Result<FilteredModel, Box<Diagnostic>> {
+    let Some((_, config)) = find_config(registry, config_id) else {
+        return Err(Box::new(
+            Diagnostic::new(
+                "AL101",
+                format!("Active configuration '{}' does not exist", config_id),
+            )
+            .on(format!("CONFIG:{}", config_id))
+            .hint("Set the active configuration to the ID of a defined config"),
+        ));
+    };
+    let selected: HashSet<&str> = config.selected.iter().map(String::as_str).collect();
+
+    // Requirement -> owning feature / parent requirement, from link records and fields
+    let mut owner: HashMap<&str, &str> = HashMap::new();
+    let mut derives_from: HashMap<&str, &str> = HashMap::new();
+    for link in links {
+        match link.kind() {
+            Some(LinkKind::BelongsTo) => {
+                owner.entry(&link.source).or_insert(&link.target);
+            }
+            Some(LinkKind::DerivesFrom) => {
+                derives_from.entry(&link.source).or_insert(&link.target);
+            }
+            _ => {}
+        }
+    }
+    for (key, element) in registry {
+        if let Some(req) = element.as_req() {
+            if let Some(parent) = req.parent.as_deref() {
+                owner.entry(key).or_insert(parent);
+            }
+            if let Some(parent) = req.derives_from.as_deref() {
+                derives_from.entry(key).or_insert(parent);
+            }
+        }
+    }
+
+    let mut excluded: Vec<String> = Vec::new();
+    for (key, element) in registry {
+        let keep = match element {
+            Element::Feature(_) | Element::Config(_) => true,
+            Element::Req(_) => {
+                requirement_feature(key, registry, &owner, &derives_from).is_none_or(|f| selected.contains(f))
+                    && variant_selected(element, &selected)
+            }
+            _ => variant_selected(element, &selected),
+        };
+        if !keep {
+            excluded.push(key.clone());
+        }
+    }
+    excluded.sort();
+
+    let excluded_set: HashSet<&str> = excluded.iter().map(String::as_str).collect();
+    let filtered_registry: Registry = registry
+        .iter()
+        .filter(|(key, _)| !excluded_set.contains(key.as_str()))
+        .map(|(key, element)| (key.clone(), element.clone()))
+        .collect();
+    let filtered_links: Vec<Link> = links
+        .iter()
+        .filter(|l| !excluded_set.contains(l.source.as_str()) && !excluded_set.contains(l.target.as_str()))
+        .cloned()
+        .collect();
+
+    Ok(FilteredModel {
+        registry: filtered_registry,
+        links: filtered_links,
+        excluded,
+    })
+}
+
+/// Reduce the model in place to the active configuration's product, if one is set
+///
+/// Returns the number of excluded elements.
+pub fn apply(registry: &mut Registry, links: &mut Vec<Link>, active_config: Option<&str>) -> Result<usize, Box<Diagnostic>> {
+    let Some(config_id) = active_config else {
+        return Ok(0);
+    };
+    let filtered = filter_to_configuration(registry, links, config_id)?;
+    *registry = filtered.registry;
+    *links = filtered.links;
+    Ok(filtered.excluded.len())
+}
+
+/// Feature owning a requirement, walking derives_from up to the root requirement
+fn requirement_feature<'a>(
+    req_id: &'a str,
+    registry: &Registry,
+    owner: &HashMap<&'a str, &'a str>,
+    derives_from: &HashMap<&'a str, &'a str>,
+) -> Option<&'a str> {
+    let mut visited = HashSet::new();
+    let mut current = req_id;
+    while visited.insert(current) {
+        if let Some(&feature) = owner.get(current)
+            && matches!(registry.get(feature), Some(Element::Feature(_)))
+        {
+            return Some(feature);
+        }
+        current = derives_from.get(current)?;
+    }
+    None
+}
+
+/// True unless the element is tagged `variant` and none of its variant features is selected
+fn variant_selected(element: &Element, selected: &HashSet<&str>) -> bool {
+    match element.tags().get("variant") {
+        Some(serde_json::Value::String(feature)) => selected.contains(feature.as_str()),
+        Some(serde_json::Value::Array(features)) => features
+            .iter()
+            .filter_map(|f| f.as_str())
+            .any(|f| selected.contains(f)),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unselected_requirements_and_variants_are_excluded() {
+        let registry: Registry = serde_json::from_value(json!({
+            "F-ROOT": {"type": "feature", "id": "F-ROOT"},
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": "F-ROOT"},
+            "F-LOG": {"type": "feature", "id": "F-LOG", "parent": "F-ROOT"},
+            "REQ-AUTH-001": {"type": "req", "id": "REQ-AUTH-001"},
+            "REQ-AUTH-002": {"type": "req", "id": "REQ-AUTH-002"},
+            "REQ-LOG-001": {"type": "req", "id": "REQ-LOG-001"},
+            "UC-LOGIN": {"type": "use_case", "id": "UC-LOGIN", "tags": {"variant": "F-AUTH"}},
+            "TC-AUTH": {"type": "test_case", "id": "TC-AUTH", "tags": {"variant": ["F-AUTH", "F-LOG"]}},
+            "CONFIG:BASIC": {"type": "config", "id": "BASIC", "root": "F-ROOT", "selected": ["F-ROOT", "F-LOG"]}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "REQ-AUTH-001", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "REQ-LOG-001", "type": "belongs_to", "target": "F-LOG"},
+            {"source": "REQ-AUTH-002", "type": "derives_from", "target": "REQ-AUTH-001"},
+            {"source": "UC-LOGIN", "type": "trace", "target": "REQ-AUTH-001"},
+            {"source": "TC-AUTH", "type": "verify", "target": "REQ-LOG-001"},
+            {"source": "TC-AUTH", "type": "verify", "target": "REQ-GONE"}
+        ]))
+        .unwrap();
+        let filtered = filter_to_configuration(&registry, &links, "BASIC").unwrap();
+
+        assert_eq!(filtered.excluded, vec!["REQ-AUTH-001", "REQ-AUTH-002", "UC-LOGIN"]);
+        assert!(filtered.registry.contains_key("TC-AUTH"));
+        assert!(filtered.registry.contains_key("F-AUTH"));
+        let kept: Vec<(&str, &str)> = filtered
+            .links
+            .iter()
+            .map(|l| (l.source.as_str(), l.target.as_str()))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![("REQ-LOG-001", "F-LOG"), ("TC-AUTH", "REQ-LOG-001"), ("TC-AUTH", "REQ-GONE")]
+        );
+    }
+
+    #[test]
+    fn test_unknown_configuration_is_reported() {
+        let registry: Registry = serde_json::from_value(json!({
+            "F-ROOT": {"type": "feature", "id": "F-ROOT"},
+            "CONFIG:BASIC": {"type": "config", "id": "BASIC", "root": "F-ROOT", "selected": ["F-ROOT"]}
+        }))
+        .unwrap();
+        let links: Vec<Link> = Vec::new();
+        let error = filter_to_configuration(&registry, &links, "PREMIUM").unwrap_err();
+        assert_eq!(error.code, "AL101");
+        assert!(find_config(&registry, "CONFIG:BASIC").is_some());
+    }
+}
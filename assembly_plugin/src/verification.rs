@@ -0,0 +1,443 @@
+//! Test Result Ingestion and Verification Status
+//!
+//! Reads JUnit XML reports, matches each `<testcase>` to a `test_case`
+//! element and rolls the results up through `verify` links and the
+//! requirement/feature trees:
+//!
+//! - test case: `failed` if any run failed or errored, else `passed` if any
+//!   run passed, else `skipped`; `not_run` without results;
+//! - requirement: combines its verifying test cases and derived requirements;
+//! - feature: combines its requirements and child features.
+//!
+//! Combined status precedence is `failed` > `not_run` > `skipped` > `passed`;
+//! elements with nothing to combine are `unverified`.
+//!
+//! JUnit test cases are matched by an attribute (`name`, `classname`, any
+//! other attribute, or `property:<name>` for a `<property>` child) and an
+//! optional pattern in which `{id}` captures the test case ID and `*` matches
+//! anything, e.g. `"*test_{id}"`. IDs are compared ignoring case and treating
+//! `_` like `-`.
+
+use crate::model_graph::{build_model_graph, ModelGraph};
+use crate::types::{Element, LinkKind};
+use crate::{xml, Link, Registry};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Verification status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Passed,
+    Skipped,
+    NotRun,
+    Failed,
+    /// No test case verifies the element
+    Unverified,
+}
+
+/// How JUnit test cases are matched to `test_case` IDs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JUnitMatch {
+    #[serde(default = "default_attribute")]
+    pub attribute: String,
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+fn default_attribute() -> String {
+    "name".to_string()
+}
+
+impl Default for JUnitMatch {
+    fn default() -> Self {
+        JUnitMatch { attribute: default_attribute(), pattern: None }
+    }
+}
+
+/// One `<testcase>` from a JUnit report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JUnitCase {
+    pub attributes: BTreeMap<String, String>,
+    pub properties: BTreeMap<String, String>,
+    pub status: Option<VerificationStatus>,
+    pub message: Option<String>,
+}
+
+impl JUnitCase {
+    fn name(&self) -> String {
+        let get = |key: &str| self.attributes.get(key).cloned().unwrap_or_default();
+        match get("classname").as_str() {
+            "" => get("name"),
+            classname => format!("{}::{}", classname, get("name")),
+        }
+    }
+}
+
+/// Result of one test run, matched to a test case (also the JSON input format)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestResult {
+    pub test_case: String,
+    pub status: VerificationStatus,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Verification status of a test case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestCaseStatus {
+    pub status: VerificationStatus,
+    pub runs: usize,
+    /// First failure message
+    pub message: Option<String>,
+}
+
+/// Rolled-up status of a requirement or feature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollupStatus {
+    pub status: VerificationStatus,
+    /// Distinct test cases below the element, per status
+    pub tests: BTreeMap<VerificationStatus, usize>,
+}
+
+/// Verification status of the whole model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub test_cases: BTreeMap<String, TestCaseStatus>,
+    pub requirements: BTreeMap<String, RollupStatus>,
+    pub features: BTreeMap<String, RollupStatus>,
+    /// JUnit test names that did not match any test case
+    pub unmatched: Vec<String>,
+}
+
+// ============================================================================
+// JUnit XML
+// ============================================================================
+
+/// Parse the `<testcase>` entries of a JUnit XML report
+pub fn parse_junit(xml: &str) -> Result<Vec<JUnitCase>, String> {
+    let root = xml::parse(xml)?;
+    let mut cases = Vec::new();
+
+    for testcase in root.descendants("testcase") {
+        let mut case = JUnitCase { attributes: testcase.attributes.clone(), ..Default::default() };
+        for child in testcase.elements() {
+            match child.local_name() {
+                "failure" | "error" => {
+                    case.status = Some(VerificationStatus::Failed);
+                    if case.message.is_none() {
+                        case.message = Some(child.attr("message").unwrap_or(child.local_name()).to_string());
+                    }
+                }
+                "skipped" if case.status.is_none() => {
+                    case.status = Some(VerificationStatus::Skipped);
+                    case.message = child.attr("message").map(str::to_string);
+                }
+                _ => {}
+            }
+        }
+        for property in testcase.descendants("property") {
+            if let (Some(k), Some(v)) = (property.attr("name"), property.attr("value")) {
+                case.properties.insert(k.to_string(), v.to_string());
+            }
+        }
+        case.status.get_or_insert(VerificationStatus::Passed);
+        cases.push(case);
+    }
+
+    Ok(cases)
+}
+
+/// Read and parse JUnit XML report files
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_junit_files(paths: &[std::path::PathBuf]) -> Result<Vec<JUnitCase>, String> {
+    let mut cases = Vec::new();
+    for path in paths {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        cases.extend(parse_junit(&text).map_err(|e| format!("Invalid JUnit report {}: {}", path.display(), e))?);
+    }
+    Ok(cases)
+}
+
+// ============================================================================
+// Matching
+// ============================================================================
+
+fn normalize_id(id: &str) -> String {
+    id.to_lowercase().replace('_', "-")
+}
+
+/// Match `text` against a pattern with `*` wildcards and one `{id}` capture
+fn capture_id(pattern: &str, text: &str) -> Option<String> {
+    let (prefix, suffix) = pattern.split_once("{id}")?;
+    (0..=text.len())
+        .filter(|&i| text.is_char_boundary(i))
+        .flat_map(|i| (i..=text.len()).filter(|&j| text.is_char_boundary(j)).map(move |j| (i, j)))
+        .find(|&(i, j)| j > i && wildcard_match(prefix, &text[..i]) && wildcard_match(suffix, &text[j..]))
+        .map(|(i, j)| text[i..j].to_string())
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((head, tail)) => {
+            text.starts_with(head)
+                && (head.len()..=text.len())
+                    .filter(|&i| text.is_char_boundary(i))
+                    .any(|i| wildcard_match(tail, &text[i..]))
+        }
+    }
+}
+
+/// Match JUnit cases to test case IDs; returns the results and the unmatched test names
+pub fn match_results(
+    cases: &[JUnitCase],
+    test_case_ids: &[&str],
+    options: &JUnitMatch,
+) -> (Vec<TestResult>, Vec<String>) {
+    let by_normalized: HashMap<String, &str> = test_case_ids.iter().map(|id| (normalize_id(id), *id)).collect();
+    let mut results = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for case in cases {
+        let value = match options.attribute.strip_prefix("property:") {
+            Some(property) => case.properties.get(property),
+            None => case.attributes.get(&options.attribute),
+        };
+        let candidate = value.and_then(|v| match &options.pattern {
+            Some(pattern) => capture_id(pattern, v),
+            None => Some(v.clone()),
+        });
+        match candidate.and_then(|c| by_normalized.get(&normalize_id(&c))) {
+            Some(id) => results.push(TestResult {
+                test_case: id.to_string(),
+                status: case.status.unwrap_or(VerificationStatus::Passed),
+                name: case.name(),
+                message: case.message.clone(),
+            }),
+            None => unmatched.push(case.name()),
+        }
+    }
+    unmatched.sort();
+    unmatched.dedup();
+    (results, unmatched)
+}
+
+// ============================================================================
+// Roll-up
+// ============================================================================
+
+/// Combined status by precedence failed > not_run > skipped > passed
+fn combine(statuses: impl IntoIterator<Item = VerificationStatus>) -> Option<VerificationStatus> {
+    statuses
+        .into_iter()
+        .filter(|s| *s != VerificationStatus::Unverified)
+        .max_by_key(|s| match s {
+            VerificationStatus::Passed => 0,
+            VerificationStatus::Skipped => 1,
+            VerificationStatus::NotRun => 2,
+            VerificationStatus::Failed => 3,
+            VerificationStatus::Unverified => -1,
+        })
+}
+
+/// Compute verification status of test cases, requirements and features
+pub fn verification_report(graph: &ModelGraph, results: &[TestResult]) -> VerificationReport {
+    let mut report = VerificationReport::default();
+
+    let mut runs: HashMap<&str, Vec<&TestResult>> = HashMap::new();
+    for result in results {
+        runs.entry(result.test_case.as_str()).or_default().push(result);
+    }
+
+    // Test cases
+    let mut test_status: HashMap<NodeIndex, VerificationStatus> = HashMap::new();
+    for node in graph.node_indices() {
+        let (id, element) = &graph[node];
+        if !matches!(element, Element::TestCase(_)) {
+            continue;
+        }
+        let case_runs = runs.get(id.as_str()).map(Vec::as_slice).unwrap_or_default();
+        let status = if case_runs.is_empty() {
+            VerificationStatus::NotRun
+        } else if case_runs.iter().any(|r| r.status == VerificationStatus::Failed) {
+            VerificationStatus::Failed
+        } else if case_runs.iter().any(|r| r.status == VerificationStatus::Passed) {
+            VerificationStatus::Passed
+        } else {
+            VerificationStatus::Skipped
+        };
+        test_status.insert(node, status);
+        report.test_cases.insert(
+            id.clone(),
+            TestCaseStatus {
+                status,
+                runs: case_runs.len(),
+                message: case_runs
+                    .iter()
+                    .find(|r| r.status == VerificationStatus::Failed)
+                    .and_then(|r| r.message.clone()),
+            },
+        );
+    }
+
+    // Requirements and features: distinct test cases reachable below them
+    for node in graph.node_indices() {
+        let (id, element) = &graph[node];
+        let target = match element {
+            Element::Req(_) => &mut report.requirements,
+            Element::Feature(_) => &mut report.features,
+            _ => continue,
+        };
+        let mut tests: Vec<NodeIndex> = Vec::new();
+        collect_tests(graph, node, &mut Vec::new(), &mut tests);
+        tests.sort();
+        tests.dedup();
+
+        let mut counts = BTreeMap::new();
+        for test in &tests {
+            *counts.entry(test_status[test]).or_default() += 1;
+        }
+        let status = combine(tests.iter().map(|t| test_status[t])).unwrap_or(VerificationStatus::Unverified);
+        target.insert(id.clone(), RollupStatus { status, tests: counts });
+    }
+
+    report
+}
+
+/// Test cases verifying `node` or anything below it (derived requirements,
+/// requirements of a feature, child features)
+fn collect_tests(graph: &ModelGraph, node: NodeIndex, visited: &mut Vec<NodeIndex>, tests: &mut Vec<NodeIndex>) {
+    if visited.contains(&node) {
+        return;
+    }
+    visited.push(node);
+    for edge in graph.edges_directed(node, Direction::Incoming) {
+        let source = edge.source();
+        let below = match (LinkKind::parse(edge.weight()), &graph[source].1) {
+            (Some(LinkKind::Verify), Element::TestCase(_)) => {
+                tests.push(source);
+                false
+            }
+            (Some(LinkKind::DerivesFrom), Element::Req(_)) => true,
+            (Some(LinkKind::BelongsTo), Element::Req(_)) => matches!(graph[node].1, Element::Feature(_)),
+            (Some(LinkKind::ChildOf), Element::Feature(_)) => true,
+            _ => false,
+        };
+        if below {
+            collect_tests(graph, source, visited, tests);
+        }
+    }
+}
+
+/// Match JUnit cases to the registry's test cases and compute the report
+///
+/// `results` are already matched results (e.g. from JSON) added to the JUnit ones.
+pub fn junit_report(
+    registry: &Registry,
+    links: &[Link],
+    cases: &[JUnitCase],
+    mut results: Vec<TestResult>,
+    options: &JUnitMatch,
+) -> VerificationReport {
+    let test_case_ids: Vec<&str> = registry
+        .values()
+        .filter(|e| matches!(e, Element::TestCase(_)))
+        .map(|e| e.id())
+        .collect();
+    let (matched, unmatched) = match_results(cases, &test_case_ids, options);
+    results.extend(matched);
+
+    let (graph, _, _) = build_model_graph(registry, links);
+    let mut report = verification_report(&graph, &results);
+    report.unmatched = unmatched;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_graph::build_model_graph;
+    use crate::{Link, Registry};
+    use serde_json::json;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="auth" tests="4">
+    <testcase classname="auth" name="test_tc_login" time="0.1"/>
+    <testcase classname="auth" name="test_tc_logout">
+      <failure message="expected &quot;bye&quot;"><![CDATA[assert <testcase> failed]]></failure>
+    </testcase>
+    <!-- <testcase name="test_tc_commented"/> -->
+    <testcase classname="auth" name="test_tc_lockout"><skipped/></testcase>
+    <testcase classname="auth" name="test_helper"/>
+  </testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn test_parse_and_match_junit() {
+        let cases = parse_junit(REPORT).unwrap();
+        assert_eq!(cases.len(), 4);
+        assert_eq!(cases[1].status, Some(VerificationStatus::Failed));
+        assert_eq!(cases[1].message.as_deref(), Some("expected \"bye\""));
+        assert_eq!(cases[2].status, Some(VerificationStatus::Skipped));
+
+        let options = JUnitMatch { attribute: "name".to_string(), pattern: Some("test_{id}".to_string()) };
+        let (results, unmatched) = match_results(&cases, &["TC-LOGIN", "TC-LOGOUT", "TC-LOCKOUT"], &options);
+        let matched: Vec<(&str, VerificationStatus)> =
+            results.iter().map(|r| (r.test_case.as_str(), r.status)).collect();
+        assert_eq!(
+            matched,
+            vec![
+                ("TC-LOGIN", VerificationStatus::Passed),
+                ("TC-LOGOUT", VerificationStatus::Failed),
+                ("TC-LOCKOUT", VerificationStatus::Skipped),
+            ]
+        );
+        assert_eq!(unmatched, vec!["auth::test_helper"]);
+    }
+
+    #[test]
+    fn test_status_rolls_up_to_requirements_and_features() {
+        let registry: Registry = serde_json::from_value(json!({
+            "F-ROOT": {"type": "feature", "id": "F-ROOT"},
+            "F-AUTH": {"type": "feature", "id": "F-AUTH", "parent": "F-ROOT"},
+            "F-LOG": {"type": "feature", "id": "F-LOG", "parent": "F-ROOT"},
+            "R-1": {"type": "req", "id": "R-1"},
+            "R-1.1": {"type": "req", "id": "R-1.1", "derives_from": "R-1"},
+            "R-1.2": {"type": "req", "id": "R-1.2", "derives_from": "R-1"},
+            "R-2": {"type": "req", "id": "R-2"},
+            "TC-1": {"type": "test_case", "id": "TC-1"},
+            "TC-2": {"type": "test_case", "id": "TC-2"}
+        }))
+        .unwrap();
+        let links: Vec<Link> = serde_json::from_value(json!([
+            {"source": "R-1", "type": "belongs_to", "target": "F-AUTH"},
+            {"source": "R-2", "type": "belongs_to", "target": "F-LOG"},
+            {"source": "TC-1", "type": "verify", "target": "R-1.1"},
+            {"source": "TC-2", "type": "verify", "target": "R-1.2"}
+        ]))
+        .unwrap();
+        let graph = build_model_graph(&registry, &links).0;
+        let results = vec![TestResult {
+            test_case: "TC-1".to_string(),
+            status: VerificationStatus::Passed,
+            name: String::new(),
+            message: None,
+        }];
+        let report = verification_report(&graph, &results);
+
+        assert_eq!(report.test_cases["TC-2"].status, VerificationStatus::NotRun);
+        assert_eq!(report.requirements["R-1.1"].status, VerificationStatus::Passed);
+        assert_eq!(report.requirements["R-1"].status, VerificationStatus::NotRun);
+        assert_eq!(report.requirements["R-1"].tests[&VerificationStatus::Passed], 1);
+        assert_eq!(report.requirements["R-2"].status, VerificationStatus::Unverified);
+        assert_eq!(report.features["F-AUTH"].status, VerificationStatus::NotRun);
+        assert_eq!(report.features["F-ROOT"].tests.values().sum::<usize>(), 2);
+    }
+}
@@ -0,0 +1,253 @@
+//! Minimal XML Reader and Escaping
+//!
+//! Just enough XML for the report and exchange formats the crate reads (JUnit,
+//! ReqIF): elements, attributes, text and CDATA. Comments, processing
+//! instructions and DOCTYPE declarations are skipped; namespaces are kept as
+//! written in names (`local_name` drops the prefix).
+
+use std::collections::BTreeMap;
+
+/// Node of a parsed document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+/// Element with attributes and child nodes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: BTreeMap<String, String>,
+    pub children: Vec<XmlNode>,
+    /// 1-based line of the start tag
+    pub line: usize,
+}
+
+impl XmlElement {
+    /// Name without namespace prefix
+    pub fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    /// Child elements
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// First child element with the given local name
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|e| e.local_name() == name)
+    }
+
+    /// Child elements with the given local name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.elements().filter(move |e| e.local_name() == name)
+    }
+
+    /// All descendant elements with the given local name, in document order
+    pub fn descendants(&self, name: &str) -> Vec<&XmlElement> {
+        let mut found = Vec::new();
+        for element in self.elements() {
+            if element.local_name() == name {
+                found.push(element);
+            }
+            found.extend(element.descendants(name));
+        }
+        found
+    }
+
+    /// Concatenated text of all descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                XmlNode::Text(t) => text.push_str(t),
+                XmlNode::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+}
+
+/// Escape text for use in element content or double-quoted attribute values
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn decode_entities(s: &str, line: usize) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| format!("line {}: unterminated entity", line))?;
+        let entity = &rest[start + 1..start + end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        out.push(decoded.ok_or_else(|| format!("line {}: unknown entity '&{};'", line, entity))?);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Index of the '>' closing a tag, ignoring '>' inside quoted attribute values
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(s: &str, line: usize) -> Result<BTreeMap<String, String>, String> {
+    let mut attributes = BTreeMap::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| format!("line {}: malformed attribute '{}'", line, rest))?;
+        let key = rest[..eq].trim().to_string();
+        let value_part = rest[eq + 1..].trim_start();
+        let Some(quote) = value_part.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            return Err(format!("line {}: unquoted value for attribute '{}'", line, key));
+        };
+        let close = value_part[1..]
+            .find(quote)
+            .ok_or_else(|| format!("line {}: unterminated value for attribute '{}'", line, key))?;
+        attributes.insert(key, decode_entities(&value_part[1..close + 1], line)?);
+        rest = value_part[close + 2..].trim_start();
+    }
+    Ok(attributes)
+}
+
+/// Parse a document and return its root element
+pub fn parse(xml: &str) -> Result<XmlElement, String> {
+    // Stack of open elements; the bottom entry collects the root
+    let mut stack: Vec<XmlElement> = vec![XmlElement::default()];
+    let mut pos = 0;
+    let line_at = |pos: usize| xml[..pos].matches('\n').count() + 1;
+
+    while pos < xml.len() {
+        let rest = &xml[pos..];
+        let Some(start) = rest.find('<') else {
+            if !rest.trim().is_empty() && stack.len() > 1 {
+                return Err(format!("line {}: unexpected end of document", line_at(xml.len())));
+            }
+            break;
+        };
+        if start > 0 && stack.len() > 1 {
+            let text = decode_entities(&rest[..start], line_at(pos))?;
+            stack.last_mut().unwrap().children.push(XmlNode::Text(text));
+        }
+        pos += start;
+        let rest = &xml[pos..];
+        let line = line_at(pos);
+        let skip_to = |end: &str| -> Result<usize, String> {
+            rest.find(end)
+                .map(|i| i + end.len())
+                .ok_or_else(|| format!("line {}: unterminated '{}'", line, &rest[..rest.len().min(9)]))
+        };
+
+        if rest.starts_with("<!--") {
+            pos += skip_to("-->")?;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = skip_to("]]>")?;
+            if stack.len() > 1 {
+                let text = cdata[..end - 12].to_string();
+                stack.last_mut().unwrap().children.push(XmlNode::Text(text));
+            }
+            pos += end;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            pos += skip_to(">")?;
+        } else {
+            let end = tag_end(rest).ok_or_else(|| format!("line {}: unterminated tag", line))?;
+            let tag = &rest[1..end];
+            pos += end + 1;
+            if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim();
+                let element = stack.pop().filter(|_| !stack.is_empty());
+                match element {
+                    Some(element) if element.name == name => {
+                        stack.last_mut().unwrap().children.push(XmlNode::Element(element));
+                    }
+                    _ => return Err(format!("line {}: unexpected closing tag '</{}>'", line, name)),
+                }
+            } else {
+                let empty = tag.ends_with('/');
+                let tag = tag.trim_end_matches('/');
+                let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+                let element = XmlElement {
+                    name: tag[..name_end].to_string(),
+                    attributes: parse_attributes(&tag[name_end..], line)?,
+                    children: Vec::new(),
+                    line,
+                };
+                if empty {
+                    stack.last_mut().unwrap().children.push(XmlNode::Element(element));
+                } else {
+                    stack.push(element);
+                }
+            }
+        }
+    }
+
+    if stack.len() > 1 {
+        let open = stack.last().unwrap();
+        return Err(format!("line {}: element '{}' is not closed", open.line, open.name));
+    }
+    let document = stack.pop().unwrap();
+    document
+        .elements()
+        .next()
+        .cloned()
+        .ok_or_else(|| "Document has no root element".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_document() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>\n<!-- c -->\n<a x='1 &amp; 2'>\n  <b>t&lt;<![CDATA[<raw>]]></b><ns:c/>\n</a>",
+        )
+        .unwrap();
+        assert_eq!(root.attr("x"), Some("1 & 2"));
+        assert_eq!(root.child("b").unwrap().text(), "t<<raw>");
+        assert_eq!(root.child("c").unwrap().line, 4);
+        assert_eq!(root.descendants("c").len(), 1);
+
+        let error = parse("<a>\n<b>\n</a>").unwrap_err();
+        assert!(error.starts_with("line 3"), "{}", error);
+    }
+}
@@ -30,7 +30,7 @@ fi
 # Build the WASM binary
 echo -e "${BLUE}Building WASM binary...${NC}"
 cd ${PLUGIN_DIR}
-cargo build --release --target wasm32-unknown-unknown
+cargo build --release --target wasm32-unknown-unknown --lib
 cd ..
 
 if [ ! -f "${SOURCE_WASM}" ]; then
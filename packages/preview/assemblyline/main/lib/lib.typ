@@ -5,6 +5,9 @@
 // Import Fletcher for automatic diagram generation
 #import "@preview/fletcher:0.5.8": diagram, node, edge
 
+// Coverage report for #coverage-table (re-exported with the other validation functions below)
+#import "validation.typ": coverage-report
+
 #let __registry      = state("asln-registry", (:))
 #let __links         = state("asln-links", ())        // Individual link records
 #let __active-config = state("asln-active-config", none)
@@ -595,6 +598,7 @@
 //   connectors:   ((from: "httpPort", to: "authService.authAPI", flow: "HTTPRequest"), ...) – internal wiring & delegation
 //                 - No dot = block's own port (delegation)
 //                 - With dot = part.port (internal wiring)
+//                 - tags: (mitigation: "E2E protection") documents the safety mechanism of a mixed-ASIL flow
 //   references:   ("BLK-EXT1", "BLK-EXT2") – associations (external block IDs)
 //   constraints:  ("weight < 500g", "power < 10W") – OCL-like constraints
 //   tags:         (stereotype: "subsystem", complexity: "high", ...)
@@ -1576,7 +1580,76 @@
   ]
 }
 
-#let coverage-table() = block(fill: luma(240))[Traceability matrix – rendered here]
+// #coverage-table: Requirement coverage matrix with aggregates per feature,
+// requirement type and safety level. Decomposed requirements roll up the
+// design/verification status of their leaves. By default only the active
+// configuration's product is shown; pass `active-config: none` for the whole model.
+#let coverage-table(show-aggregates: true, active-config: auto) = context {
+  let config = if active-config == auto { __active-config.get() } else { active-config }
+  let result = coverage-report(__registry.get(), __links.get(), active-config: config)
+  if not result.success {
+    block(fill: rgb("#fef2f2"), inset: 0.8em, radius: 4pt, stroke: 1pt + rgb("#dc2626"))[
+      *Coverage analysis failed:* #result.message
+    ]
+    return
+  }
+  let report = result.report
+  let mark(ok) = if ok { text(fill: rgb("#16a34a"))[✓] } else { text(fill: rgb("#dc2626"))[✗] }
+
+  table(
+    columns: (auto, 1fr, auto, auto, auto, auto, auto),
+    inset: 5pt,
+    align: (left, left, left, center, center, center, center),
+    fill: (_, row) => if row == 0 { luma(230) } else { none },
+    table.header([*ID*], [*Title*], [*Feature*], [*Design*], [*Verified*], [*Traced*], [*Leaf*]),
+    ..report.requirements.map(r => (
+      raw(r.id),
+      text(size: 0.9em)[#r.title],
+      text(size: 0.9em)[#r.at("feature", default: none)],
+      mark(r.designed),
+      mark(r.verified),
+      mark(r.traced),
+      if r.leaf [leaf] else [#r.derived.len() children],
+    )).flatten()
+  )
+
+  text(size: 0.9em)[*Coverage:* #result.message]
+
+  if show-aggregates {
+    let aggregate-rows(title, groups) = {
+      (table.cell(colspan: 6, fill: luma(245))[*#title*],)
+      for (key, agg) in groups {
+        (
+          [#key],
+          [#agg.total],
+          [#agg.designed],
+          [#agg.verified],
+          [#agg.traced],
+          [#calc.round(agg.percent, digits: 1)%],
+        )
+      }
+    }
+    table(
+      columns: (1fr, auto, auto, auto, auto, auto),
+      inset: 5pt,
+      align: (left, right, right, right, right, right),
+      table.header([*Group*], [*Total*], [*Designed*], [*Verified*], [*Traced*], [*Covered*]),
+      ..aggregate-rows("By feature", report.by_feature),
+      ..aggregate-rows("By requirement type", report.by_type),
+      ..aggregate-rows("By safety level", report.by_safety),
+    )
+  }
+
+  let uncovered = report.uncovered
+  if uncovered.undesigned.len() + uncovered.unverified.len() + uncovered.untraced.len() > 0 {
+    block(fill: rgb("#fffbeb"), inset: 0.8em, radius: 4pt, stroke: 1pt + rgb("#f59e0b"))[
+      *Uncovered requirements* \
+      #if uncovered.undesigned.len() > 0 [No design: #uncovered.undesigned.join(", ") \ ]
+      #if uncovered.unverified.len() > 0 [No verification: #uncovered.unverified.join(", ") \ ]
+      #if uncovered.untraced.len() > 0 [No use case trace: #uncovered.untraced.join(", ")]
+    ]
+  }
+}
 
 // #render-use-case: Render a single use case with all details
 #let render-use-case(uc) = {
@@ -2523,10 +2596,20 @@
   validate-specification as __validate-specification-wasm,
   validation-status,
   format-validation-errors,
+  format-suppressions,
+  query-model,
+  coverage-report,
+  impact-analysis,
+  create-link-baseline,
+  detect-suspect-links,
+  diff-models,
+  format-change-log,
+  interference-analysis,
+  verification-status,
 )
 
 // Create wrapper functions that respect validation options
-#let validate-traceability(registry, links, active-config: none) = context {
+#let validate-traceability(registry, links, active-config: none, rules: (:), suppressions: (), user-rules: (), tag-schemas: (:)) = context {
   let opts = __validation-options.get()
   if not opts.sat {
     // Return a skipped result
@@ -2537,10 +2620,10 @@
       validation_mode: "basic"
     )
   }
-  __validate-traceability-wasm(registry, links, active-config: active-config)
+  __validate-traceability-wasm(registry, links, active-config: active-config, rules: rules, suppressions: suppressions, user-rules: user-rules, tag-schemas: tag-schemas)
 }
 
-#let validate-specification(registry: (:), links: (), active-config: none) = context {
+#let validate-specification(registry: (:), links: (), active-config: none, rules: (:), suppressions: (), user-rules: (), tag-schemas: (:)) = context {
   let opts = __validation-options.get()
   if not opts.sat {
     // Return a skipped result
@@ -2551,7 +2634,7 @@
       validation_mode: "basic"
     )
   }
-  __validate-specification-wasm(registry: registry, links: links, active-config: active-config)
+  __validate-specification-wasm(registry: registry, links: links, active-config: active-config, rules: rules, suppressions: suppressions, user-rules: user-rules, tag-schemas: tag-schemas)
 }
 
 // Import and initialize parameter visualization module
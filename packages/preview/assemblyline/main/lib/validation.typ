@@ -29,13 +29,29 @@
 /// - RULE 6: Requirement allocation constraints
 /// - RULE 7: Requirement satisfaction constraints
 ///
+/// Optional rule configuration:
+/// - rules: per rule code, `false` to disable, a severity ("error", "warning", "info"),
+///   or (enabled: bool, severity: str) - e.g. (AL002: "warning", AL006: false)
+/// - suppressions: ((element_id: "UC-1", rule: "AL002", justification: "..."), ...)
+///   in addition to `suppress` tags on elements, e.g. tags: (suppress: (AL002: "why"))
+/// - user-rules: project rules in the plugin's rule language, reported like built-in rules:
+///   ((id: "PRJ-1", rule: "forall r: req where r.tags.safety == \"ASIL-D\" => exists t: test_case in r <-verify-",
+///     severity: "error", message: "{id} needs a verifying test case"),)
+/// - tag-schemas: per element type (or "*"), required tag keys and value constraints:
+///   (req: (required: ("type", "safety"), properties: (safety: (pattern: "ASIL-[A-D]|QM"),
+///     priority: (type: "string", enum: ("P1", "P2", "P3"))), closed: false))
+///
 /// Returns a validation result with status and details
-#let validate-traceability(registry, links, active-config: none) = {
+#let validate-traceability(registry, links, active-config: none, rules: (:), suppressions: (), user-rules: (), tag-schemas: (:)) = {
   // Prepare the input structure for the plugin
   let input = (
     registry: registry,
     links: links,
     active_config: active-config,
+    rules: rules,
+    suppressions: suppressions,
+    user_rules: user-rules,
+    tag_schemas: tag-schemas,
   )
 
   // Serialize to JSON and convert to bytes
@@ -65,9 +81,13 @@
 ///   )
 /// }
 ///
-/// Returns validation result dictionary with fields: passed, total_elements, message
-#let validate-specification(registry: (:), links: (), active-config: none) = {
-  validate-traceability(registry, links, active-config: active-config)
+/// Returns validation result dictionary with fields: passed, total_elements, message, diagnostics,
+/// suppressions (applied suppressions with element_id, code, justification, suppressed count)
+///
+/// Each diagnostic is a dictionary (code, severity, element_id, related_ids, message, hint),
+/// sorted by severity, code and element.
+#let validate-specification(registry: (:), links: (), active-config: none, rules: (:), suppressions: (), user-rules: (), tag-schemas: (:)) = {
+  validate-traceability(registry, links, active-config: active-config, rules: rules, suppressions: suppressions, user-rules: user-rules, tag-schemas: tag-schemas)
 }
 
 /// Get validation status as a display string
@@ -84,14 +104,268 @@
     []
   } else {
     let message = result.at("message", default: "Unknown error")
+    let diagnostics = result.at("diagnostics", default: ())
     block(
       inset: 0.5em,
       fill: rgb("#ffe6e6"),
       radius: 0.25em,
-      [
+      if diagnostics.len() == 0 [
         *Validation Error:*
 
         #message
+      ] else [
+        *Validation Errors (#diagnostics.len()):*
+
+        #table(
+          columns: (auto, auto, auto, 1fr),
+          stroke: 0.5pt + gray,
+          [*Code*], [*Severity*], [*Element*], [*Message*],
+          ..diagnostics.map(d => (
+            raw(d.code),
+            d.severity,
+            if d.element_id == none [—] else { raw(d.element_id) },
+            if d.hint == none [#d.message] else [#d.message \ #emph(d.hint)],
+          )).flatten()
+        )
+      ]
+    )
+  }
+}
+
+/// Query the model graph with a path pattern
+///
+/// Examples:
+/// - `"F-AUTH" <-belongs_to|derives_from-+ req` – requirements under a feature
+/// - `"REQ-SYS-001" <-derives_from-+ req <-satisfy- block_definition`
+/// - `shortest "UC-LOGIN" to test_case` – shortest trace path in any direction
+///
+/// Example:
+/// #context {
+///   let result = query-model(__registry.get(), __links.get(), "\"F-AUTH\" <-belongs_to-+ req")
+///   result.ids.join(", ")
+/// }
+///
+/// Returns a dictionary with fields: success, message, ids, matches (id, type, path)
+#let query-model(registry, links, query) = {
+  let input = (
+    registry: registry,
+    links: links,
+    query: query,
+  )
+  json(__validation-plugin.query(bytes(json.encode(input))))
+}
+
+/// Compute requirement coverage (design, verification, trace) via WASM plugin
+///
+/// Example:
+/// #context {
+///   let cov = coverage-report(__registry.get(), __links.get())
+///   cov.report.totals.percent
+/// }
+///
+/// With `active-config`, only the elements in that configuration's product are analysed.
+///
+/// Returns a dictionary with fields: success, message, report (requirements,
+/// totals, by_feature, by_type, by_safety, uncovered)
+#let coverage-report(registry, links, active-config: none) = {
+  let input = (
+    registry: registry,
+    links: links,
+    active_config: active-config,
+  )
+  json(__validation-plugin.coverage_report(bytes(json.encode(input))))
+}
+
+/// Compute the elements potentially affected by a change via WASM plugin
+///
+/// Example:
+/// #context {
+///   let impact = impact-analysis(__registry.get(), __links.get(), ("REQ-AUTH-001",))
+///   impact.report.affected.at("test_case", default: ()).map(h => h.id).join(", ")
+/// }
+///
+/// `follow` is an array of (kind: "derives_from", direction: "incoming"|"outgoing"|"both");
+/// none uses the default (derived requirements, design, implementations, tests).
+///
+/// Returns a dictionary with fields: success, message, report (changed, unknown,
+/// affected grouped by element type with id/path/via, total)
+#let impact-analysis(registry, links, changed, follow: none, include-configs: true) = {
+  let input = (
+    registry: registry,
+    links: links,
+    changed: changed,
+    include_configs: include-configs,
+  )
+  if follow != none {
+    input.insert("follow", follow)
+  }
+  json(__validation-plugin.impact_analysis(bytes(json.encode(input))))
+}
+
+/// Record the current content hash of every satisfy/verify link target
+///
+/// Save `result.baseline` as JSON (e.g. via `typst query`) after a review and
+/// pass it to `detect-suspect-links` later.
+///
+/// Returns a dictionary with fields: success, message, baseline (version, links)
+#let create-link-baseline(registry, links, kinds: ("satisfy", "verify")) = {
+  let input = (
+    registry: registry,
+    links: links,
+    kinds: kinds,
+  )
+  json(__validation-plugin.create_link_baseline(bytes(json.encode(input))))
+}
+
+/// List links whose target changed since they were last reviewed
+///
+/// Example:
+/// #context {
+///   let result = detect-suspect-links(__registry.get(), __links.get(), json("link-baseline.json"))
+///   for s in result.report.suspect [#s.source → #s.target (#s.reason) \ ]
+/// }
+///
+/// Returns a dictionary with fields: success, message, report (checked, suspect, removed)
+#let detect-suspect-links(registry, links, baseline, kinds: ("satisfy", "verify")) = {
+  let input = (
+    registry: registry,
+    links: links,
+    baseline: baseline,
+    kinds: kinds,
+  )
+  json(__validation-plugin.detect_suspect_links(bytes(json.encode(input))))
+}
+
+/// Compare two model snapshots via WASM plugin
+///
+/// Snapshots are dictionaries with `registry` and `links` (e.g. a baseline
+/// exported with `typst query` and loaded with `json(...)`).
+///
+/// Example:
+/// #context {
+///   let result = diff-models(json("baseline-2.3.json"), (registry: __registry.get(), links: __links.get()))
+///   format-change-log(result)
+/// }
+///
+/// Returns a dictionary with fields: success, message, diff (added, removed,
+/// modified with field changes, added_links, removed_links)
+#let diff-models(old, new) = {
+  let snapshot(s) = (registry: s.registry, links: s.at("links", default: ()))
+  let input = (old: snapshot(old), new: snapshot(new))
+  json(__validation-plugin.diff_models(bytes(json.encode(input))))
+}
+
+/// List flows from lower-ASIL into higher-ASIL blocks via WASM plugin
+///
+/// A flow is mitigated when the connector or the receiving port carries
+/// `tags: (mitigation: "...")`.
+///
+/// Returns a dictionary with fields: success, message, flows (context, from, to,
+/// flow, from_block, to_block, from_asil, to_asil, mitigation)
+#let interference-analysis(registry) = {
+  let input = (registry: registry)
+  json(__validation-plugin.interference_analysis(bytes(json.encode(input))))
+}
+
+/// Compute verification status from test results via WASM plugin
+///
+/// `junit` takes JUnit XML report texts; each `<testcase>` is matched to a
+/// `test_case` ID by the `name` attribute, or by `match: (attribute: ...,
+/// pattern: "test_{id}")` where `attribute` may be `property:<name>`.
+/// Already matched `results` (`(test_case: "TC-1", status: "passed")`) can be
+/// passed instead. Status rolls up through `verify`, `derives_from` and the
+/// feature tree.
+///
+/// Example:
+/// #context {
+///   let result = verification-status(__registry.get(), __links.get(),
+///     junit: (read("results/junit.xml"),), match: (pattern: "*test_{id}"))
+///   result.report.requirements.at("REQ-AUTH-001").status
+/// }
+///
+/// Returns a dictionary with fields: success, message, report (test_cases,
+/// requirements, features, unmatched)
+#let verification-status(registry, links, results: (), junit: (), match: (:)) = {
+  let input = (registry: registry, links: links, results: results, junit: junit, match: match)
+  json(__validation-plugin.verification_status(bytes(json.encode(input))))
+}
+
+/// Render the result of `diff-models` as a change log
+#let format-change-log(result) = {
+  if not result.success {
+    return block(inset: 0.5em, fill: rgb("#ffebee"), radius: 0.25em)[*Diff failed:* #result.message]
+  }
+  let diff = result.diff
+  let show-value(v) = if v == none { [—] } else if type(v) == str { raw(v) } else { raw(json.encode(v, pretty: false)) }
+  let element-list(items) = table(
+    columns: (auto, auto, 1fr),
+    stroke: 0.5pt + gray,
+    [*ID*], [*Type*], [*Title*],
+    ..items.map(e => (raw(e.id), e.type, e.title)).flatten()
+  )
+  let link-list(items) = table(
+    columns: (auto, auto, auto),
+    stroke: 0.5pt + gray,
+    [*Source*], [*Type*], [*Target*],
+    ..items.map(l => (raw(l.source), l.type, raw(l.target))).flatten()
+  )
+
+  [*Changes:* #result.message]
+
+  if diff.added.len() > 0 [
+    ==== Added Elements
+    #element-list(diff.added)
+  ]
+  if diff.removed.len() > 0 [
+    ==== Removed Elements
+    #element-list(diff.removed)
+  ]
+  if diff.modified.len() > 0 [
+    ==== Modified Elements
+    #table(
+      columns: (auto, auto, 1fr, 1fr),
+      stroke: 0.5pt + gray,
+      [*ID*], [*Field*], [*Old*], [*New*],
+      ..diff.modified.map(m => m.changes.map(c => (
+        raw(m.id),
+        raw(c.field),
+        show-value(c.old),
+        if c.at("added", default: ()).len() + c.at("removed", default: ()).len() > 0 [
+          #c.at("added", default: ()).map(a => [+ #raw(a)]).join(" ")
+          #c.at("removed", default: ()).map(r => [− #raw(r)]).join(" ")
+        ] else { show-value(c.new) },
+      )).flatten()).flatten()
+    )
+  ]
+  if diff.added_links.len() > 0 [
+    ==== Added Links
+    #link-list(diff.added_links)
+  ]
+  if diff.removed_links.len() > 0 [
+    ==== Removed Links
+    #link-list(diff.removed_links)
+  ]
+}
+
+/// List the suppressions that were applied during validation
+#let format-suppressions(result) = context {
+  let applied = result.at("suppressions", default: ())
+  if applied.len() == 0 {
+    []
+  } else {
+    block(
+      inset: 0.5em,
+      fill: rgb("#fff8e1"),
+      radius: 0.25em,
+      [
+        *Applied Suppressions (#applied.len()):*
+
+        #table(
+          columns: (auto, auto, auto, 1fr),
+          stroke: 0.5pt + gray,
+          [*Element*], [*Code*], [*Findings*], [*Justification*],
+          ..applied.map(s => (raw(s.element_id), raw(s.code), str(s.suppressed), s.justification)).flatten()
+        )
       ]
     )
   }
@@ -102,7 +376,7 @@
 /// Validates that all parameter bindings in a configuration match their schemas
 /// (type checking, range validation, enum membership, defaults)
 ///
-/// Returns a validation result with fields: is_valid, message, errors, num_features_checked, num_parameters_checked
+/// Returns a validation result with fields: is_valid, message, diagnostics, num_features_checked, num_parameters_checked
 #let validate-parameters-wasm(registry: (:), config-id: "") = {
   let input = (
     registry: registry,